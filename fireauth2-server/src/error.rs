@@ -57,6 +57,45 @@ pub enum Error {
     #[error(transparent)]
     FirebaseAuth(#[from] actix_firebase_auth::Error),
 
+    /// Request carries neither a Firebase ID token nor a session cookie.
+    #[error("Request is missing a Firebase ID token or session cookie")]
+    Unauthenticated,
+
+    /// Firebase ID token was issued too long after sign-in to create a session.
+    #[error("Firebase ID token is too old to create a session; sign in again")]
+    StaleIdToken,
+
+    /// The route requires the user to have signed in more recently.
+    #[error("Reauthentication required: sign in again to continue")]
+    ReauthenticationRequired {
+        /// Maximum time in seconds since the sign-in.
+        max_age: u64,
+        /// `/authorize` URL that makes the user sign in to Google again.
+        authorize_url: String,
+    },
+
+    /// Firebase App Check token is missing or invalid on an enforcing route.
+    #[error("App Check verification failed: {because}")]
+    AppCheckFailed {
+        /// The reason for why the App Check token was rejected.
+        because: String,
+    },
+
+    /// Server configuration is invalid.
+    #[error("Invalid configuration: {because}")]
+    InvalidConfig {
+        /// The reason for why the configuration is invalid.
+        because: String,
+    },
+
+    /// Cookie-authenticated request is missing a matching CSRF token.
+    #[error("CSRF token is missing or does not match")]
+    CsrfTokenMismatch,
+
+    /// Request is missing the `X-Requested-With` header.
+    #[error("Request is missing the X-Requested-With header")]
+    MissingRequestedWithHeader,
+
     /// Firebase ID token is missing required Google identity claims.
     #[error("Firebase ID token is missing Google identity claims")]
     FirebaseUserMissingGoogleIdentity,
@@ -78,14 +117,43 @@ pub enum Error {
         "Request is missing a valid redirect_to query param or Referer header"
     )]
     MissingRedirectUrl,
+
+    /// The redirect URL is not on one of the allowed JavaScript origins.
+    #[error("Redirect URL origin `{0}` is not allowed")]
+    DisallowedRedirectUrl(String),
 }
 
 // TODO: Map specific errors to appropriate HTTP codes
 impl ResponseError for Error {
     fn error_response(&self) -> HttpResponse {
-        let body = serde_json::json!({
+        if let Error::ReauthenticationRequired {
+            max_age,
+            authorize_url,
+        } = self
+        {
+            let body = serde_json::json!({
+                "error": "reauthentication_required",
+                "message": self.to_string(),
+                "maxAge": max_age,
+                "authorizeUrl": authorize_url,
+            });
+            return HttpResponse::build(self.status_code()).json(body);
+        }
+
+        let mut body = serde_json::json!({
             "error": self.to_string(),
         });
+        if let Error::FireAuth2(
+            fireauth2::Error::InvalidAuthorizationRequest(report),
+        ) = self
+        {
+            body["issues"] = serde_json::json!(report);
+        }
+        if let Error::FireAuth2(fireauth2::Error::ScopesNotGranted(scopes)) =
+            self
+        {
+            body["missingScopes"] = serde_json::json!(scopes);
+        }
 
         HttpResponse::build(self.status_code()).json(body)
     }
@@ -95,13 +163,24 @@ impl ResponseError for Error {
             Error::Actix(err) => err.as_response_error().status_code(),
             Error::FirebaseAuth(err) => err.error_response().status(),
 
+            Error::Unauthenticated
+            | Error::StaleIdToken
+            | Error::ReauthenticationRequired { .. }
+            | Error::AppCheckFailed { .. } => StatusCode::UNAUTHORIZED,
+
+            Error::CsrfTokenMismatch | Error::MissingRequestedWithHeader => {
+                StatusCode::FORBIDDEN
+            }
+
             Error::FailedToExtractAuthCookie { .. }
             | Error::FirebaseUserMissingGoogleIdentity
             | Error::InvalidRedirectUrl(_)
             | Error::MissingRedirectUrl
+            | Error::DisallowedRedirectUrl(_)
             | Error::UrlParse(_) => StatusCode::BAD_REQUEST,
 
             Error::Env(_)
+            | Error::InvalidConfig { .. }
             | Error::DotEnv(_)
             | Error::Net(_)
             | Error::Json(_)
@@ -112,23 +191,55 @@ impl ResponseError for Error {
 
             // FireAuth2 errors
             Error::FireAuth2(err) => match err {
+                fireauth2::Error::UnknownProvider(_) => StatusCode::NOT_FOUND,
+
+                fireauth2::Error::SignInDenied(_)
+                | fireauth2::Error::ScopesNotGranted(_) => {
+                    StatusCode::FORBIDDEN
+                }
+
                 fireauth2::Error::Firestore(_)
+                | fireauth2::Error::ProviderDiscoveryFailed { .. }
+                | fireauth2::Error::FirebaseAdmin { .. }
                 | fireauth2::Error::Http(_)
                 | fireauth2::Error::TokenRevocationFailed { .. } => {
                     StatusCode::BAD_GATEWAY
                 }
 
-                fireauth2::Error::InvalidPromptValue(_)
+                fireauth2::Error::DeviceAuthorizationFailed { .. }
+                | fireauth2::Error::InvalidPromptValue(_)
+                | fireauth2::Error::InvalidOpenIdScopePolicy(_)
+                | fireauth2::Error::InvalidAuthorizationRequest(_)
+                | fireauth2::Error::InvalidExtraParamValue { .. }
+                | fireauth2::Error::DisallowedExtraParam(_)
+                | fireauth2::Error::DisallowedScope(_)
+                | fireauth2::Error::UnknownScopeProfile(_)
+                | fireauth2::Error::UnknownGoogleScope(_)
+                | fireauth2::Error::MissingScope
+                | fireauth2::Error::OpenIdScopeRequired
                 | fireauth2::Error::MissingConfigField(_)
                 | fireauth2::Error::UserNotFound
-                | fireauth2::Error::UrlParse(_)
-                | fireauth2::Error::TokenVerification(_) => {
-                    StatusCode::BAD_REQUEST
+                | fireauth2::Error::UrlParse(_) => StatusCode::BAD_REQUEST,
+
+                fireauth2::Error::Jwt(_)
+                | fireauth2::Error::InvalidToken { .. }
+                | fireauth2::Error::NonceMismatch
+                | fireauth2::Error::AuthenticationTooOld { .. }
+                | fireauth2::Error::AccessTokenHashMismatch
+                | fireauth2::Error::AuthorizedPartyMismatch(_)
+                | fireauth2::Error::SigningKeyNotFound(_) => {
+                    StatusCode::UNAUTHORIZED
                 }
 
                 fireauth2::Error::Env(_)
+                | fireauth2::Error::Io(_)
+                | fireauth2::Error::LoopbackFlowFailed { .. }
                 | fireauth2::Error::Base64(_)
                 | fireauth2::Error::Json(_)
+                | fireauth2::Error::Regex(_)
+                | fireauth2::Error::InvalidRequestValidationMode(_)
+                | fireauth2::Error::DuplicateProvider(_)
+                | fireauth2::Error::ReservedProviderId(_)
                 | fireauth2::Error::TokenExchangeFailed { .. }
                 | fireauth2::Error::OAuthConfig(_) => {
                     StatusCode::INTERNAL_SERVER_ERROR
@@ -10,7 +10,11 @@ mod web;
 pub use error::*;
 
 use crate::web::AppState;
-use fireauth2::FireAuthClient;
+use fireauth2::{
+    AppCheckVerifier, FireAuthClient, FirebaseAdmin, GitHubProvider,
+    IdentityToolkitAdmin, ScopeClaimsSync, SessionCookieVerifier,
+    spawn_key_refresh,
+};
 
 use actix_firebase_auth::FirebaseAuth;
 use actix_web::{App, HttpServer, middleware, web::Data};
@@ -27,19 +31,84 @@ async fn main() -> Result<()> {
 
     // Setup shared application state
     let app_state = AppState::from_env().map(Arc::new)?;
-    let google_auth = FireAuthClient::new().await.map(Arc::new)?;
+    let mut google_auth = FireAuthClient::new()
+        .await?
+        .with_platform_client_ids(app_state.platform_client_ids())
+        .with_audiences(app_state.id_token_audiences())
+        .with_authorized_parties(app_state.id_token_authorized_parties())
+        .with_openid_policy(app_state.openid_scope_policy())
+        .with_request_validation(app_state.request_validation())
+        .with_scope_policy(app_state.scope_policy().clone())
+        .with_sign_in_policy(app_state.sign_in_policy().clone())
+        .with_custom_params_allowlist(
+            app_state.custom_params_allowlist().clone(),
+        );
+    if let Some(leeway) = app_state.id_token_clock_skew() {
+        google_auth = google_auth.with_clock_skew(leeway);
+    }
+    let mut firebase_admin =
+        IdentityToolkitAdmin::new(google_auth.project_id())?;
+    if let Some(email) = app_state.service_account_email() {
+        firebase_admin = firebase_admin.with_service_account_email(email);
+    }
+
+    if let Some((client_id, client_secret)) = app_state.device_client() {
+        google_auth = google_auth.with_device_client(client_id, client_secret);
+    }
+
+    if let Some((client_id, client_secret)) = app_state.github_client() {
+        google_auth.add_provider(
+            GitHubProvider::new(),
+            client_id,
+            client_secret,
+        )?;
+    }
+
+    for provider in app_state.oidc_providers() {
+        google_auth.add_oidc_provider(provider.clone()).await?;
+    }
+
+    // Mirror granted Google scopes into Firebase custom claims, if configured
+    if let Some(mapping) = app_state.scope_claims_mapping() {
+        let admin = Arc::new(firebase_admin.clone());
+        let sync = ScopeClaimsSync::new(mapping.clone(), admin);
+        google_auth = google_auth.with_scope_claims_sync(sync);
+    }
+
+    let google_auth = Arc::new(google_auth);
+
+    // Keep Google's signing keys fresh so ID token verification does not
+    // wait on the key endpoint
+    spawn_key_refresh(google_auth.id_token_key_source());
 
     // Initialize Firestore client using the Google project ID
     let project_id = google_auth.project_id();
     let firebase_auth = FirebaseAuth::new(project_id).await.map(Arc::new)?;
+    let session_cookie_verifier =
+        SessionCookieVerifier::new(project_id).map(Arc::new)?;
+    let firebase_admin: Arc<dyn FirebaseAdmin> = Arc::new(firebase_admin);
+
+    // Verify Firebase App Check tokens on routes configured via FIREAUTH2_APP_CHECK
+    let app_check_verifier = app_state
+        .app_check_project_number()
+        .map(AppCheckVerifier::new)
+        .transpose()?
+        .map(Arc::new);
 
     log::info!("Starting HTTP server on {socket_addr}");
 
     HttpServer::new(move || {
-        App::new()
-            .app_data(Data::from(app_state.clone()))
+        let mut app = App::new();
+        if let Some(verifier) = &app_check_verifier {
+            app = app.app_data(Data::from(verifier.clone()));
+        }
+
+        app.app_data(Data::from(app_state.clone()))
             .app_data(Data::from(firebase_auth.clone()))
             .app_data(Data::from(google_auth.clone()))
+            .app_data(Data::from(firebase_admin.clone()))
+            .app_data(Data::from(session_cookie_verifier.clone()))
+            .wrap(middleware::from_fn(web::app_check::verify_app_check))
             .wrap(actix_cors::Cors::permissive()) // TODO: tighten CORS in production
             .wrap(middleware::Logger::default())
             .wrap(middleware::NormalizePath::trim())
@@ -0,0 +1,232 @@
+use std::collections::HashMap;
+use std::str::FromStr;
+
+use actix_web::body::MessageBody;
+use actix_web::dev::{ServiceRequest, ServiceResponse};
+use actix_web::middleware::Next;
+use actix_web::web::Data;
+use fireauth2::AppCheckVerifier;
+
+use crate::web::AppState;
+
+/// How strictly Firebase App Check tokens are verified for a route.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum AppCheckMode {
+    /// App Check tokens are ignored.
+    #[default]
+    Off,
+    /// Tokens are verified and failures are logged, but requests proceed.
+    Monitor,
+    /// Requests without a valid token are rejected with `401 Unauthorized`.
+    Enforce,
+}
+
+impl FromStr for AppCheckMode {
+    type Err = crate::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "off" => Ok(AppCheckMode::Off),
+            "monitor" => Ok(AppCheckMode::Monitor),
+            "enforce" => Ok(AppCheckMode::Enforce),
+            other => Err(crate::Error::InvalidConfig {
+                because: format!("invalid App Check mode `{other}`"),
+            }),
+        }
+    }
+}
+
+/// Per-route App Check modes.
+///
+/// Parsed from a comma-separated list of `<path>=<mode>` entries. An entry
+/// without a path (or with path `*`) sets the mode of all other routes, e.g.
+/// `monitor,/token=enforce,/revoke=enforce`.
+#[derive(Debug, Clone, Default)]
+pub struct AppCheckPolicy {
+    default: AppCheckMode,
+    routes: HashMap<String, AppCheckMode>,
+}
+
+impl AppCheckPolicy {
+    /// Returns the mode applying to the given request path.
+    pub fn mode_for(&self, path: &str) -> AppCheckMode {
+        self.routes.get(path).copied().unwrap_or(self.default)
+    }
+
+    /// Returns whether App Check is verified on any route.
+    pub fn is_enabled(&self) -> bool {
+        self.default != AppCheckMode::Off
+            || self.routes.values().any(|mode| *mode != AppCheckMode::Off)
+    }
+}
+
+impl FromStr for AppCheckPolicy {
+    type Err = crate::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut policy = AppCheckPolicy::default();
+
+        for entry in s.split(',').filter(|entry| !entry.trim().is_empty()) {
+            match entry.split_once('=') {
+                None => policy.default = entry.parse()?,
+                Some((path, mode)) if path.trim() == "*" => {
+                    policy.default = mode.parse()?;
+                }
+                Some((path, mode)) => {
+                    policy.routes.insert(path.trim().to_owned(), mode.parse()?);
+                }
+            }
+        }
+
+        Ok(policy)
+    }
+}
+
+/// Middleware verifying the `X-Firebase-AppCheck` header according to the
+/// configured [`AppCheckPolicy`].
+pub async fn verify_app_check(
+    req: ServiceRequest,
+    next: Next<impl MessageBody>,
+) -> Result<ServiceResponse<impl MessageBody>, actix_web::Error> {
+    // The router matches the percent-decoded path, so `/%74oken` is routed
+    // to `/token` and must be checked as such.
+    let mode = req
+        .app_data::<Data<AppState>>()
+        .map_or(AppCheckMode::Off, |state| {
+            state.app_check_policy().mode_for(req.match_info().as_str())
+        });
+
+    if mode == AppCheckMode::Off {
+        return next.call(req).await;
+    }
+
+    let verifier = req
+        .app_data::<Data<AppCheckVerifier>>()
+        .cloned()
+        .ok_or_else(|| {
+            actix_web::error::ErrorInternalServerError(
+                "AppCheckVerifier should be initialized on application startup",
+            )
+        })?;
+
+    let token = req
+        .headers()
+        .get(AppCheckVerifier::HEADER_NAME)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_owned);
+
+    let result = match token {
+        Some(token) => verifier
+            .verify(&token)
+            .await
+            .map(|_| ())
+            .map_err(|err| err.to_string()),
+        None => Err("missing App Check token".to_owned()),
+    };
+
+    if let Err(because) = result {
+        if mode == AppCheckMode::Enforce {
+            return Err(crate::Error::AppCheckFailed { because }.into());
+        }
+        log::warn!(
+            "App Check verification failed for {}: {because}",
+            req.path()
+        );
+    }
+
+    next.call(req).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::test::{TestRequest, init_service, try_call_service};
+    use actix_web::{App, HttpResponse, middleware, web};
+    use fireauth2::test_keys;
+    use std::sync::Arc;
+
+    const PROJECT_NUMBER: &str = "123456789";
+
+    async fn call(
+        policy: &str,
+        path: &str,
+        token: Option<&str>,
+    ) -> actix_web::http::StatusCode {
+        let state =
+            AppState::default().with_app_check_policy(policy.parse().unwrap());
+        let verifier = AppCheckVerifier::with_key_source(
+            PROJECT_NUMBER,
+            Arc::new(test_keys::key_source()),
+        );
+        let app = init_service(
+            App::new()
+                .app_data(Data::new(state))
+                .app_data(Data::new(verifier))
+                .wrap(middleware::from_fn(verify_app_check))
+                .route("/token", web::post().to(HttpResponse::Ok)),
+        )
+        .await;
+
+        let mut req = TestRequest::post().uri(path);
+        if let Some(token) = token {
+            req = req.insert_header((AppCheckVerifier::HEADER_NAME, token));
+        }
+        match try_call_service(&app, req.to_request()).await {
+            Ok(response) => response.status(),
+            Err(err) => err.as_response_error().status_code(),
+        }
+    }
+
+    fn token() -> String {
+        let now = test_keys::now();
+        test_keys::sign(&serde_json::json!({
+            "sub": "1:123456789:web:abc",
+            "aud": [format!("projects/{PROJECT_NUMBER}")],
+            "iss": format!("https://firebaseappcheck.googleapis.com/{PROJECT_NUMBER}"),
+            "iat": now,
+            "exp": now + 3600,
+        }))
+    }
+
+    #[actix_web::test]
+    async fn test_middleware_applies_route_mode() {
+        assert_eq!(call("/token=monitor", "/token", None).await, 200);
+        assert_eq!(call("/token=enforce", "/token", None).await, 401);
+        assert_eq!(
+            call("/token=enforce", "/token", Some("invalid")).await,
+            401
+        );
+        assert_eq!(call("/token=enforce", "/token", Some(&token())).await, 200);
+    }
+
+    #[actix_web::test]
+    async fn test_middleware_enforces_encoded_paths() {
+        assert_eq!(call("/token=enforce", "/%74oken", None).await, 401);
+        assert_eq!(
+            call("/token=enforce", "/%74oken", Some(&token())).await,
+            200
+        );
+    }
+
+    #[test]
+    fn test_parse_policy_with_default_and_routes() {
+        let policy: AppCheckPolicy =
+            "monitor,/token=enforce,/introspect=off".parse().unwrap();
+        assert_eq!(policy.mode_for("/token"), AppCheckMode::Enforce);
+        assert_eq!(policy.mode_for("/introspect"), AppCheckMode::Off);
+        assert_eq!(policy.mode_for("/authorize"), AppCheckMode::Monitor);
+        assert!(policy.is_enabled());
+    }
+
+    #[test]
+    fn test_parse_empty_policy_is_disabled() {
+        let policy: AppCheckPolicy = "".parse().unwrap();
+        assert_eq!(policy.mode_for("/token"), AppCheckMode::Off);
+        assert!(!policy.is_enabled());
+    }
+
+    #[test]
+    fn test_parse_policy_rejects_unknown_mode() {
+        assert!("/token=strict".parse::<AppCheckPolicy>().is_err());
+    }
+}
@@ -0,0 +1,209 @@
+use std::ops::Deref;
+
+use actix_firebase_auth::{FirebaseUser, GoogleUserId};
+use actix_web::http::header::AUTHORIZATION;
+use actix_web::web::Data;
+use actix_web::{FromRequest, HttpRequest, dev::Payload};
+use fireauth2::{FireAuthClient, SessionCookieVerifier};
+use futures::future::{FutureExt, LocalBoxFuture};
+
+use crate::web::AppState;
+use crate::web::firebase_session::FirebaseSession;
+
+/// A Firebase user authenticated either by a Bearer ID token in the
+/// `Authorization` header or by a Firebase session cookie.
+///
+/// The `Authorization` header takes precedence. Cookie-authenticated requests
+/// using an unsafe method (e.g. `POST`) must also pass the CSRF check. Routes
+/// with a step-up requirement additionally require a recent sign-in.
+pub struct AuthenticatedUser(FirebaseUser);
+
+impl Deref for AuthenticatedUser {
+    type Target = FirebaseUser;
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl FromRequest for AuthenticatedUser {
+    type Error = actix_web::Error;
+    type Future = LocalBoxFuture<'static, Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, payload: &mut Payload) -> Self::Future {
+        let req = req.clone();
+        let user = Self::authenticate(&req, payload);
+        async move {
+            let user = user.await?;
+            let Some(state) = req.app_data::<Data<AppState>>() else {
+                return Ok(user);
+            };
+
+            let policy = state.step_up_policy();
+            let login_hint = user.email.as_ref().map(AsRef::as_ref);
+            if policy.enforce(&req, user.auth_time, login_hint).is_err() {
+                let auth_time = user.auth_time.max(
+                    Self::google_auth_time(&req, &user).await.unwrap_or(0),
+                );
+                policy.enforce(&req, auth_time, login_hint)?;
+            }
+            Ok(user)
+        }
+        .boxed_local()
+    }
+}
+
+/// Where the credentials of a request come from.
+#[derive(Debug, PartialEq, Eq)]
+enum Credentials {
+    /// Bearer Firebase ID token in the `Authorization` header.
+    IdToken,
+    /// Firebase session cookie.
+    SessionCookie(String),
+}
+
+impl Credentials {
+    /// Selects the credentials of the request, preferring the
+    /// `Authorization` header and enforcing the CSRF check for
+    /// cookie-authenticated unsafe methods.
+    fn from_request(req: &HttpRequest) -> crate::Result<Self> {
+        if req.headers().contains_key(AUTHORIZATION) {
+            return Ok(Self::IdToken);
+        }
+
+        let session_cookie = req
+            .app_data::<Data<AppState>>()
+            .and_then(|state| req.cookie(state.firebase_session_cookie_name()))
+            .ok_or(crate::Error::Unauthenticated)?;
+
+        if !req.method().is_safe() {
+            FirebaseSession::verify_csrf(req)?;
+        }
+
+        Ok(Self::SessionCookie(session_cookie.value().to_owned()))
+    }
+}
+
+impl AuthenticatedUser {
+    /// Returns when the user last signed in to Google via `/authorize` with
+    /// `max_age`, as recorded by `/callback`.
+    async fn google_auth_time(
+        req: &HttpRequest,
+        user: &FirebaseUser,
+    ) -> Option<u64> {
+        let client = req.app_data::<Data<FireAuthClient>>()?;
+        let google_user_id = GoogleUserId::try_from(user).ok()?;
+        match client.google_authenticated_at(&google_user_id).await {
+            Ok(auth_time) => auth_time.and_then(|t| u64::try_from(t).ok()),
+            Err(err) => {
+                log::warn!("Failed to look up the Google sign-in: {err}");
+                None
+            }
+        }
+    }
+
+    fn authenticate(
+        req: &HttpRequest,
+        payload: &mut Payload,
+    ) -> LocalBoxFuture<'static, actix_web::Result<Self>> {
+        let session_cookie = match Credentials::from_request(req) {
+            Ok(Credentials::IdToken) => {
+                return FirebaseUser::from_request(req, payload)
+                    .map(|user| user.map(AuthenticatedUser))
+                    .boxed_local();
+            }
+            Ok(Credentials::SessionCookie(session_cookie)) => session_cookie,
+            Err(err) => return futures::future::err(err.into()).boxed_local(),
+        };
+
+        let Some(verifier) = req.app_data::<Data<SessionCookieVerifier>>()
+        else {
+            return futures::future::err(
+                actix_web::error::ErrorInternalServerError(
+                    "SessionCookieVerifier should be initialized on application startup",
+                ),
+            )
+            .boxed_local();
+        };
+
+        let verifier = verifier.clone();
+
+        async move {
+            verifier
+                .verify::<FirebaseUser>(&session_cookie)
+                .await
+                .map(AuthenticatedUser)
+                .map_err(|err| crate::Error::from(err).into())
+        }
+        .boxed_local()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::cookie::Cookie;
+    use actix_web::test::TestRequest;
+
+    fn request(method: &str) -> TestRequest {
+        let state = AppState::default();
+        let cookie = Cookie::new(
+            state.firebase_session_cookie_name().to_owned(),
+            "session-cookie",
+        );
+        TestRequest::default()
+            .method(method.parse().unwrap())
+            .app_data(Data::new(state))
+            .cookie(cookie)
+            .cookie(Cookie::new(FirebaseSession::CSRF_COOKIE_NAME, "csrf"))
+    }
+
+    #[test]
+    fn test_authorization_header_takes_precedence() {
+        let req = request("POST")
+            .insert_header((AUTHORIZATION, "Bearer id-token"))
+            .to_http_request();
+        assert_eq!(
+            Credentials::from_request(&req).unwrap(),
+            Credentials::IdToken
+        );
+
+        let req = request("GET").to_http_request();
+        assert_eq!(
+            Credentials::from_request(&req).unwrap(),
+            Credentials::SessionCookie("session-cookie".into())
+        );
+
+        let req = TestRequest::get()
+            .app_data(Data::new(AppState::default()))
+            .to_http_request();
+        assert!(matches!(
+            Credentials::from_request(&req),
+            Err(crate::Error::Unauthenticated)
+        ));
+    }
+
+    #[test]
+    fn test_session_cookie_requires_csrf_on_unsafe_methods() {
+        let req = request("POST").to_http_request();
+        assert!(matches!(
+            Credentials::from_request(&req),
+            Err(crate::Error::CsrfTokenMismatch)
+        ));
+
+        let req = request("POST")
+            .insert_header((FirebaseSession::CSRF_HEADER_NAME, "forged"))
+            .to_http_request();
+        assert!(matches!(
+            Credentials::from_request(&req),
+            Err(crate::Error::CsrfTokenMismatch)
+        ));
+
+        let req = request("POST")
+            .insert_header((FirebaseSession::CSRF_HEADER_NAME, "csrf"))
+            .to_http_request();
+        assert_eq!(
+            Credentials::from_request(&req).unwrap(),
+            Credentials::SessionCookie("session-cookie".into())
+        );
+    }
+}
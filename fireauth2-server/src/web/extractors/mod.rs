@@ -1,4 +1,7 @@
+mod authenticated_user;
 mod fireauth;
 mod redirect_uri;
 
+pub use authenticated_user::*;
 pub use fireauth::*;
+pub use redirect_uri::get_redirect_uri_from_request;
@@ -0,0 +1,104 @@
+use crate::error::{Error, Result};
+use actix_web::{
+    HttpRequest,
+    cookie::{Cookie, SameSite, time::Duration},
+};
+use fireauth2::CsrfToken;
+
+/// Cookies backing a Firebase session cookie login.
+///
+/// The session cookie itself is `httpOnly`. It is paired with a readable
+/// CSRF cookie whose value must be echoed in the [`Self::CSRF_HEADER_NAME`]
+/// header on every state-changing, cookie-authenticated request
+/// (double-submit cookie pattern).
+pub struct FirebaseSession {
+    /// Firebase session cookie as returned by `createSessionCookie`.
+    session_cookie: String,
+
+    /// Anti-CSRF token bound to this session.
+    csrf_token: CsrfToken,
+}
+
+impl FirebaseSession {
+    /// Name of the cookie holding the anti-CSRF token.
+    pub const CSRF_COOKIE_NAME: &'static str = "fireauth2_csrf";
+
+    /// Name of the header that must echo the anti-CSRF token.
+    pub const CSRF_HEADER_NAME: &'static str = "X-CSRF-Token";
+
+    /// Creates a new session with a freshly generated anti-CSRF token.
+    pub fn new(session_cookie: String) -> Self {
+        Self {
+            session_cookie,
+            csrf_token: CsrfToken::new_random(),
+        }
+    }
+
+    /// Returns the anti-CSRF token bound to this session.
+    pub fn csrf_token(&self) -> &str {
+        self.csrf_token.secret()
+    }
+
+    /// Builds the session and CSRF cookies.
+    ///
+    /// - The session cookie is `http_only` to keep it out of reach of scripts.
+    /// - Both cookies are `secure`, `same_site=Lax` and share the same lifetime.
+    pub fn into_cookies<'c>(
+        self,
+        cookie_name: &str,
+        max_age: u64,
+    ) -> (Cookie<'c>, Cookie<'c>) {
+        let max_age =
+            Duration::seconds(i64::try_from(max_age).unwrap_or(i64::MAX));
+
+        let session =
+            Cookie::build(cookie_name.to_owned(), self.session_cookie)
+                .path("/")
+                .http_only(true)
+                .secure(true)
+                .same_site(SameSite::Lax)
+                .max_age(max_age)
+                .finish();
+
+        let csrf = Cookie::build(
+            Self::CSRF_COOKIE_NAME,
+            self.csrf_token.secret().clone(),
+        )
+        .path("/")
+        .secure(true)
+        .same_site(SameSite::Lax)
+        .max_age(max_age)
+        .finish();
+
+        (session, csrf)
+    }
+
+    /// Builds removal cookies for the session and CSRF cookies.
+    pub fn removal_cookies<'c>(cookie_name: &str) -> [Cookie<'c>; 2] {
+        [cookie_name.to_owned(), Self::CSRF_COOKIE_NAME.to_owned()].map(
+            |name| {
+                let mut cookie = Cookie::build(name, "").path("/").finish();
+                cookie.make_removal();
+                cookie
+            },
+        )
+    }
+
+    /// Ensures the CSRF header matches the CSRF cookie of the request.
+    pub fn verify_csrf(req: &HttpRequest) -> Result<()> {
+        let cookie = req.cookie(Self::CSRF_COOKIE_NAME);
+        let header = req
+            .headers()
+            .get(Self::CSRF_HEADER_NAME)
+            .and_then(|value| value.to_str().ok());
+
+        match (cookie, header) {
+            (Some(cookie), Some(header))
+                if !header.is_empty() && cookie.value() == header =>
+            {
+                Ok(())
+            }
+            _ => Err(Error::CsrfTokenMismatch),
+        }
+    }
+}
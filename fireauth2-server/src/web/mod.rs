@@ -1,7 +1,12 @@
+pub mod app_check;
 mod extractors;
+mod firebase_session;
+mod response_mode;
 pub mod routes;
 mod session;
 mod state;
+pub mod step_up;
 mod utils;
 
+pub use response_mode::*;
 pub use state::*;
@@ -0,0 +1,25 @@
+use std::str::FromStr;
+
+/// How sign-in results are returned to the client.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ResponseMode {
+    /// Redirect back to the calling page with tokens in the URL fragment.
+    #[default]
+    Redirect,
+    /// Return tokens in a JSON body.
+    Json,
+}
+
+impl FromStr for ResponseMode {
+    type Err = crate::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "redirect" => Ok(ResponseMode::Redirect),
+            "json" => Ok(ResponseMode::Json),
+            other => Err(crate::Error::InvalidConfig {
+                because: format!("invalid response mode `{other}`"),
+            }),
+        }
+    }
+}
@@ -1,13 +1,33 @@
 use crate::Result;
+use crate::web::AppState;
 use crate::web::extractors::FireAuth;
 use crate::web::session::Session;
 use crate::web::utils::get_referer_url;
-use fireauth2::{RequestAccessTokenConfig, RequestAccessTokenPayload};
+use fireauth2::{
+    RequestAccessTokenConfig, RequestAccessTokenExtraParams,
+    RequestAccessTokenPayload,
+};
 
 use actix_web::http::header;
 use actix_web::{HttpRequest, HttpResponse, get, web};
 use url::Url;
 
+/// Rejects hybrid response types, whose responses arrive in the URL
+/// fragment instead of the query read by `/callback`.
+fn require_code_response_type(
+    params: &RequestAccessTokenExtraParams,
+) -> Result<()> {
+    let response_type = params.response_type();
+    if response_type.is_hybrid() {
+        return Err(fireauth2::Error::InvalidExtraParamValue {
+            name: "response_type".into(),
+            value: response_type.to_string(),
+        }
+        .into());
+    }
+    Ok(())
+}
+
 /// GET `/authorize`
 ///
 /// Initiates the Google OAuth 2.0 authorization flow by redirecting the user to Google’s consent screen.
@@ -23,6 +43,21 @@ use url::Url;
 ///   - `prompt=consent` — forces the consent screen to appear, even if the user has already authorized the app.
 ///   - `access_type=offline` — requests a `refresh_token` in addition to the `access_token`.
 ///   - `scope=email%20profile` — custom scopes to request specific permissions.
+///     Rejected unless allowlisted if `FIREAUTH2_SCOPE_POLICY` sets `allowedScopes`.
+///   - `profile=calendar-read` — a scope profile defined by `FIREAUTH2_SCOPE_POLICY`,
+///     adding its scopes and default `access_type` and `prompt`. `scope` may then be omitted.
+///   - `login_hint`, `include_granted_scopes`, `hd`, `nonce`, `enable_granular_consent`,
+///     `hl`, `display` and `max_age` — forwarded to Google as documented in
+///     <https://developers.google.com/identity/protocols/oauth2/web-server#creatingclient>.
+///   - `response_type` — only `code` is accepted. Hybrid types such as `code id_token`
+///     return the code in the URL fragment, which `/callback` cannot read.
+///   - `partial_consent=accept|report|fail` — how `/callback` handles scopes the user
+///     did not grant on Google's granular consent screen. Not sent to Google.
+///   - `required_scopes` — the scopes `partial_consent=fail` insists on, defaulting to
+///     all requested scopes. Not sent to Google.
+///   - If `FIREAUTH2_STEP_UP` has an `/authorize` entry, `max_age` is capped at it, so
+///     `/callback` only accepts users who signed in to Google recently enough.
+///   - Any other parameter is forwarded only if allowed by `FIREAUTH2_CUSTOM_AUTHORIZATION_PARAMS`.
 ///
 /// ### Example Request
 /// ```http
@@ -49,7 +84,13 @@ use url::Url;
 /// ```
 ///
 /// ### Errors
-/// - `400 Bad Request` — if no valid `redirect_uri` can be resolved.
+/// - `400 Bad Request` — if no valid `redirect_uri` can be resolved, or a
+///   parameter is invalid or not allowlisted.
+/// - `400 Bad Request` — if neither `scope` nor `profile` is given, the profile is
+///   unknown, or a scope is not allowlisted.
+/// - `400 Bad Request` — if `response_type` is a hybrid type.
+/// - `400 Bad Request` — if `FIREAUTH2_REQUEST_VALIDATION=reject` and the
+///   request fails validation. The body lists all `issues`.
 /// - `500 Internal Server Error` — if session creation or URL construction fails.
 ///
 /// ---
@@ -57,6 +98,7 @@ use url::Url;
 pub async fn authorize(
     req: HttpRequest,
     fireauth2: FireAuth,
+    state: AppState,
     query: web::Query<RequestAccessTokenPayload>,
 ) -> Result<HttpResponse> {
     let redirect_to = query
@@ -70,15 +112,21 @@ pub async fn authorize(
     // (todo) Verify that it's an http/s scheme
     let redirect_uri = Url::parse(&redirect_uri_decoded)?;
 
-    let payload = query.into_inner();
+    let mut payload = query.into_inner();
+    require_code_response_type(&payload.extra_params)?;
+    if let Some(max_age) = state.step_up_policy().max_age_for(req.path()) {
+        payload.extra_params.require_authenticated_within(max_age);
+    }
     let config = RequestAccessTokenConfig::from(&payload);
-    let response = fireauth2.request_access_token(&config);
+    let response = fireauth2.request_access_token(&config)?;
 
     let session = Session::new(
         response.pkce_verifier(),
         response.csrf_token(),
         redirect_uri,
         payload.extra_params,
+        response.nonce(),
+        response.scopes(),
     );
 
     let redirect_response = HttpResponse::Found()
@@ -88,3 +136,33 @@ pub async fn authorize(
 
     Ok(redirect_response)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_require_code_response_type() {
+        let payload = |response_type: &str| -> RequestAccessTokenPayload {
+            serde_json::from_value(serde_json::json!({
+                "scope": "openid",
+                "response_type": response_type,
+            }))
+            .unwrap()
+        };
+
+        assert!(
+            require_code_response_type(&payload("code").extra_params).is_ok()
+        );
+        for response_type in ["code id_token", "code token"] {
+            assert!(matches!(
+                require_code_response_type(
+                    &payload(response_type).extra_params
+                ),
+                Err(crate::Error::FireAuth2(
+                    fireauth2::Error::InvalidExtraParamValue { .. }
+                ))
+            ));
+        }
+    }
+}
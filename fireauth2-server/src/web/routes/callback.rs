@@ -28,11 +28,25 @@ use actix_web::{HttpRequest, HttpResponse, get, web};
 /// ### Flow:
 /// 1. Validates the CSRF token against the session.
 /// 2. Exchanges the authorization `code` and `pkce_verifier` for tokens.
-/// 3. Verifies the ID token to ensure it was issued by Google.
-/// 4. If a `refresh_token` is included:
+/// 3. Verifies the ID token to ensure it was issued by Google and is bound to
+///    the authorization request (`nonce`) and the access token (`at_hash`).
+///    If `max_age` was requested, `auth_time` must be recent enough. It is then
+///    recorded, renewing the step-up authentication of `FIREAUTH2_STEP_UP` routes.
+/// 4. Evaluates the configured sign-in policy (`FIREAUTH2_SIGN_IN_POLICY`). Rejected
+///    accounts are redirected with a stable `error` code such as `domain_blocked`,
+///    and nothing is stored.
+/// 5. Compares the granted with the requested scopes. With `partial_consent=fail`,
+///    missing `required_scopes` (all requested scopes by default) redirect with
+///    `error=scopes_not_granted` and nothing is stored. The tokens are revoked
+///    unless they extend an earlier grant, whose stored refresh token Google
+///    would revoke along with them.
+///    With `report` (default), they are listed as space-separated `missing_scopes`
+///    in the URL fragment.
+/// 6. If a `refresh_token` is included:
 ///    - Stores the user and `refresh_token` in Firestore under `users/{sub}`.
 ///    - Avoids overwriting existing entries if no `refresh_token` is returned (e.g., due to `access_type=online`).
-/// 5. Redirects the user to the original post-authentication URL, encoding tokens in the URL fragment.
+///    - Redirects with an `error` instead of the tokens if it cannot be stored.
+/// 7. Redirects the user to the original post-authentication URL, encoding tokens in the URL fragment.
 ///
 /// ### Important Notes:
 /// - A `refresh_token` is only returned when `access_type=offline` **and** `prompt=consent`
@@ -60,6 +74,8 @@ pub async fn exchange_authorization_code(
         .code(query.code.clone())
         .pkce_verifier(session.pkce_verifier)
         .params(session.extra_params)
+        .nonce(session.nonce)
+        .scopes(session.scopes)
         .redirect_to(session.redirect_to)
         .revoke_existing_tokens(state.enable_existing_token_revocation())
         .build()?;
@@ -0,0 +1,113 @@
+use crate::Result;
+use crate::web::extractors::{AuthenticatedUser, FireAuth};
+use actix_firebase_auth::GoogleUserId;
+use actix_web::{HttpResponse, post, web};
+use fireauth2::{
+    DeviceAuthorizationPayload, DeviceTokenPayload, DeviceTokenPoll,
+};
+use serde_json::json;
+
+/// POST `/device/code`
+///
+/// Starts an OAuth 2.0 device authorization (RFC 8628) for devices without a
+/// browser, such as kiosks or CLI tools. Requires `FIREAUTH2_DEVICE_CLIENT_ID`
+/// and `FIREAUTH2_DEVICE_CLIENT_SECRET`.
+///
+/// ### Request Body
+/// ```json
+/// { "scope": "openid email https://www.googleapis.com/auth/drive.file" }
+/// ```
+///
+/// Like in `/authorize`, raw scopes are rejected unless allowlisted if
+/// `FIREAUTH2_SCOPE_POLICY` sets `allowedScopes`, and a `profile` adds the
+/// scopes of a scope profile, in which case `scope` may be omitted.
+///
+/// ### Response
+/// ```json
+/// {
+///   "deviceCode": "AH-1Ng...",
+///   "userCode": "GQVQ-JKEC",
+///   "verificationUrl": "https://www.google.com/device",
+///   "expiresIn": 1800,
+///   "interval": 5
+/// }
+/// ```
+///
+/// The device shows `userCode` and `verificationUrl` to the user and then
+/// polls `POST /device/token` every `interval` seconds.
+///
+/// ### Errors
+/// - `400 Bad Request` — if neither `scope` nor `profile` is given, the profile is
+///   unknown, or a scope is not allowlisted.
+///
+/// ---
+#[post("/device/code")]
+pub async fn start_device_authorization(
+    fireauth2: FireAuth,
+    payload: web::Json<DeviceAuthorizationPayload>,
+) -> Result<HttpResponse> {
+    let response = fireauth2.start_device_authorization(&payload).await?;
+    Ok(HttpResponse::Ok().json(response))
+}
+
+/// POST `/device/token`
+///
+/// Polls for the tokens of a device authorization. Once the user granted
+/// access, the refresh token is persisted like in `/callback`, but apart from
+/// the refresh token of the web client. It is refreshed via
+/// `POST /device/refresh`.
+///
+/// ### Request Body
+/// ```json
+/// { "deviceCode": "AH-1Ng..." }
+/// ```
+///
+/// ### Response
+/// - `200 OK` with the same body as `POST /exchange` once access is granted.
+/// - `400 Bad Request` with `{ "error": "authorization_pending" }` while the
+///   user has not finished, or `{ "error": "slow_down" }` if the device must
+///   increase its polling interval by 5 seconds.
+///
+/// ### Errors
+/// - `400 Bad Request` — if the user denied access or the device code expired.
+///
+/// ---
+#[post("/device/token")]
+pub async fn poll_device_token(
+    fireauth2: FireAuth,
+    payload: web::Json<DeviceTokenPayload>,
+) -> Result<HttpResponse> {
+    let response = match fireauth2
+        .poll_device_token(&payload.device_code)
+        .await?
+    {
+        DeviceTokenPoll::AuthorizationPending => HttpResponse::BadRequest()
+            .json(json!({ "error": "authorization_pending" })),
+        DeviceTokenPoll::SlowDown => {
+            HttpResponse::BadRequest().json(json!({ "error": "slow_down" }))
+        }
+        DeviceTokenPoll::Complete(tokens) => HttpResponse::Ok().json(tokens),
+    };
+    Ok(response)
+}
+
+/// POST `/device/refresh`
+///
+/// Exchanges the refresh token the authenticated user granted via the device
+/// authorization for a new access token, using the device client.
+///
+/// ### Response
+/// Same as `POST /token`.
+///
+/// ---
+#[post("/device/refresh")]
+pub async fn exchange_device_refresh_token(
+    fireauth2: FireAuth,
+    firebase_user: AuthenticatedUser,
+) -> Result<HttpResponse> {
+    let google_user_id = GoogleUserId::try_from(&*firebase_user)?;
+    let response = fireauth2
+        .exchange_device_refresh_token(&*google_user_id)
+        .await?;
+    Ok(HttpResponse::Ok().json(response))
+}
@@ -0,0 +1,155 @@
+use crate::Result;
+use crate::web::AppState;
+use crate::web::extractors::{AuthenticatedUser, FireAuth};
+use actix_firebase_auth::GoogleUserId;
+use actix_web::{HttpRequest, HttpResponse, post, web};
+use fireauth2::{ExchangeCodeConfig, ExchangeCodePayload};
+
+/// Header Google recommends requiring on popup-mode code submissions.
+const X_REQUESTED_WITH: &str = "X-Requested-With";
+
+/// POST `/exchange`
+///
+/// Exchanges an authorization code obtained by a Google Identity Services
+/// code client in popup mode (`initCodeClient` with `ux_mode: 'popup'`).
+/// Such codes are issued for `redirect_uri=postmessage` and are not tied to
+/// the PKCE session created by `/authorize`.
+///
+/// ### Request
+/// - `Authorization: Bearer <Firebase ID token>` or a Firebase session cookie
+/// - `X-Requested-With: XmlHttpRequest`
+///
+/// ```json
+/// { "code": "4/0AbCD...", "scope": "openid email", "partial_consent": "report" }
+/// ```
+///
+/// The code must belong to the Google account linked to the Firebase user.
+/// If Google returns a refresh token, it is persisted like in `/callback`.
+///
+/// `scope` (optional) lists the scopes the code was requested for. Those the
+/// user did not grant are handled according to `partial_consent`: `accept`,
+/// `report` (default, listed as `missingScopes` in the response) or `fail`.
+/// With `fail`, `required_scopes` (optional, defaults to `scope`) lists the
+/// scopes that must be granted; missing optional ones are reported.
+///
+/// ### Response
+/// ```json
+/// {
+///   "accessToken": "ya29.a0AfH6SMDs...",
+///   "idToken": "eyJhbGciOi...",
+///   "scope": ["openid", "email"],
+///   "issuedAt": 1759485825,
+///   "expiresIn": 3599
+/// }
+/// ```
+///
+/// ### Errors
+/// - `401 Unauthorized` — if the Firebase user is not authenticated or the
+///   ID token belongs to a different Google account.
+/// - `403 Forbidden` — if the `X-Requested-With` header is missing.
+/// - `403 Forbidden` — if `partial_consent` is `fail` and not all required
+///   scopes were granted. The tokens are revoked unless they extend a stored
+///   earlier grant, and the body lists the missing required scopes as
+///   `missingScopes`.
+///
+/// ---
+#[post("/exchange")]
+pub async fn exchange_postmessage_code(
+    req: HttpRequest,
+    fireauth2: FireAuth,
+    state: AppState,
+    firebase_user: AuthenticatedUser,
+    payload: web::Json<ExchangeCodePayload>,
+) -> Result<HttpResponse> {
+    require_requested_with(&req)?;
+
+    let google_user_id = GoogleUserId::try_from(&*firebase_user)?;
+    let payload = payload.into_inner();
+    let config = ExchangeCodeConfig::new(payload.code)
+        .google_user_id(&*google_user_id)
+        .revoke_existing_tokens(state.enable_existing_token_revocation())
+        .requested_scopes(payload.scope.unwrap_or_default().0)
+        .required_scopes(payload.required_scopes.unwrap_or_default().0)
+        .partial_consent(payload.partial_consent);
+
+    let response = fireauth2.exchange_postmessage_code(config).await?;
+    Ok(HttpResponse::Ok().json(response))
+}
+
+/// Rejects requests without the `X-Requested-With` header, which cannot be
+/// sent cross-origin without a CORS preflight.
+fn require_requested_with(req: &HttpRequest) -> Result<()> {
+    if !req.headers().contains_key(X_REQUESTED_WITH) {
+        return Err(crate::Error::MissingRequestedWithHeader);
+    }
+    Ok(())
+}
+
+/// POST `/exchange/mobile`
+///
+/// Exchanges a `serverAuthCode` obtained by native Google Sign-In on Android
+/// or iOS with offline access. The ID token must be issued for the web client
+/// and authorized by one of the client IDs in `FIREAUTH2_PLATFORM_CLIENT_IDS`.
+///
+/// ### Request
+/// - `Authorization: Bearer <Firebase ID token>` or a Firebase session cookie
+///
+/// ```json
+/// { "code": "4/0AbCD..." }
+/// ```
+///
+/// The code must belong to the Google account linked to the Firebase user.
+/// If Google returns a refresh token, it is persisted like in `/callback`.
+///
+/// `scope`, `partial_consent` and `required_scopes` are supported as in
+/// `POST /exchange`.
+///
+/// ### Response
+/// Same as `POST /exchange`.
+///
+/// ### Errors
+/// - `401 Unauthorized` — if the Firebase user is not authenticated, the ID
+///   token belongs to a different Google account or was issued to an unknown
+///   platform client.
+/// - `400 Bad Request` — if no platform client IDs are configured.
+///
+/// ---
+#[post("/exchange/mobile")]
+pub async fn exchange_server_auth_code(
+    fireauth2: FireAuth,
+    state: AppState,
+    firebase_user: AuthenticatedUser,
+    payload: web::Json<ExchangeCodePayload>,
+) -> Result<HttpResponse> {
+    let google_user_id = GoogleUserId::try_from(&*firebase_user)?;
+    let payload = payload.into_inner();
+    let config = ExchangeCodeConfig::new(payload.code)
+        .google_user_id(&*google_user_id)
+        .revoke_existing_tokens(state.enable_existing_token_revocation())
+        .requested_scopes(payload.scope.unwrap_or_default().0)
+        .required_scopes(payload.required_scopes.unwrap_or_default().0)
+        .partial_consent(payload.partial_consent);
+
+    let response = fireauth2.exchange_server_auth_code(config).await?;
+    Ok(HttpResponse::Ok().json(response))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::test::TestRequest;
+
+    #[test]
+    fn test_require_requested_with() {
+        let req = TestRequest::post()
+            .insert_header((X_REQUESTED_WITH, "XmlHttpRequest"))
+            .to_http_request();
+        assert!(require_requested_with(&req).is_ok());
+
+        let req = TestRequest::post().to_http_request();
+        assert!(matches!(
+            require_requested_with(&req),
+            Err(crate::Error::MissingRequestedWithHeader)
+        ));
+    }
+}
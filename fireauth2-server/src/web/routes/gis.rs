@@ -0,0 +1,263 @@
+use crate::Result;
+use crate::web::extractors::FireAuth;
+use crate::web::utils::{allowed_redirect_uri, get_referer_url};
+use crate::web::{AppState, ResponseMode};
+use fireauth2::{
+    FirebaseAdmin, GOOGLE_PROVIDER_ID, GisCredentialPayload, IdTokenClaims,
+};
+
+use actix_web::http::header;
+use actix_web::{HttpRequest, HttpResponse, post, web};
+use serde::Deserialize;
+use serde_json::json;
+use url::Url;
+
+#[derive(Debug, Deserialize)]
+pub struct GisCredentialQueryParams {
+    redirect_uri: Option<String>,
+}
+
+/// POST `/gis/credential`
+///
+/// Login URI for Google Identity Services (One Tap and the "Sign In With
+/// Google" button). Google POSTs the signed-in user's ID token as an
+/// `application/x-www-form-urlencoded` body.
+///
+/// ### Form fields (from Google):
+/// - `credential`: The Google-issued ID token.
+/// - `g_csrf_token`: Double-submit CSRF token, also sent as the `g_csrf_token` cookie.
+///
+/// ### Query Parameters:
+/// - `redirect_uri` (optional): Where to redirect in `redirect` mode.
+///   Defaults to the Referer header. Its origin must be one of the
+///   `javascript_origins` of the Google OAuth client.
+///
+/// ### Flow:
+/// 1. Verifies the `g_csrf_token` form field matches the `g_csrf_token` cookie.
+/// 2. Validates the ID token with Google's public keys and evaluates
+///    `FIREAUTH2_SIGN_IN_POLICY` before any token is returned or minted.
+/// 3. If `FIREAUTH2_GIS_CUSTOM_TOKEN` is enabled, mints a Firebase custom token
+///    for the Firebase user linked to the Google account. No custom token is
+///    returned for Google accounts without a Firebase user; such clients sign in
+///    with the ID token via `signInWithCredential` instead.
+/// 4. Responds according to `FIREAUTH2_GIS_RESPONSE_MODE`.
+///
+/// ### Response:
+/// - `redirect` (default): `302 Found` to the redirect URI with
+///   `id_token` and `custom_token` in the URL fragment.
+/// - `json`: `200 OK` with
+///
+/// ```json
+/// { "idToken": "...", "sub": "...", "email": "...", "customToken": "..." }
+/// ```
+///
+/// ### Errors
+/// - `400 Bad Request` — if the redirect URI is not on an allowed origin.
+/// - `403 Forbidden` — if the CSRF token is missing or does not match, or the
+///   sign-in policy rejects the account.
+/// - `401 Unauthorized` / `400 Bad Request` — if the ID token is invalid.
+///
+/// ---
+#[post("/gis/credential")]
+pub async fn gis_credential(
+    req: HttpRequest,
+    fireauth2: FireAuth,
+    admin: web::Data<dyn FirebaseAdmin>,
+    state: AppState,
+    query: web::Query<GisCredentialQueryParams>,
+    form: web::Form<GisCredentialPayload>,
+) -> Result<HttpResponse> {
+    verify_csrf_token(&req, &form)?;
+
+    let claims = fireauth2.verify_gis_credential(form.credential()).await?;
+
+    let custom_token = if state.gis_custom_token() {
+        create_custom_token(admin.as_ref(), &claims.sub).await?
+    } else {
+        None
+    };
+
+    let redirect_uri = match state.gis_response_mode() {
+        ResponseMode::Json => None,
+        ResponseMode::Redirect => {
+            let redirect_to = query
+                .into_inner()
+                .redirect_uri
+                .or_else(|| get_referer_url(&req))
+                .ok_or(crate::Error::MissingRedirectUrl)?;
+            Some(allowed_redirect_uri(
+                &redirect_to,
+                fireauth2.allowed_origins(),
+            )?)
+        }
+    };
+
+    Ok(respond(
+        redirect_uri,
+        form.credential(),
+        &claims,
+        custom_token.as_deref(),
+    ))
+}
+
+/// Ensures the `g_csrf_token` form field matches the `g_csrf_token` cookie.
+fn verify_csrf_token(
+    req: &HttpRequest,
+    form: &GisCredentialPayload,
+) -> Result<()> {
+    let csrf_cookie = req
+        .cookie(GisCredentialPayload::CSRF_TOKEN_NAME)
+        .ok_or(crate::Error::CsrfTokenMismatch)?;
+    if form.csrf_token().is_empty() || csrf_cookie.value() != form.csrf_token()
+    {
+        return Err(crate::Error::CsrfTokenMismatch);
+    }
+    Ok(())
+}
+
+/// Mints a Firebase custom token for the Firebase user linked to the given
+/// Google user, if any.
+///
+/// Minting one for an unlinked Google user would create a second Firebase
+/// user next to the one `signInWithCredential` creates for the account.
+async fn create_custom_token(
+    admin: &dyn FirebaseAdmin,
+    google_user_id: &str,
+) -> Result<Option<String>> {
+    let Some(uid) =
+        admin.lookup_uid(GOOGLE_PROVIDER_ID, google_user_id).await?
+    else {
+        log::debug!(
+            "Skipping custom token. No Firebase user linked to Google user"
+        );
+        return Ok(None);
+    };
+    Ok(Some(admin.create_custom_token(&uid).await?))
+}
+
+/// Returns the sign-in result as JSON, or as a redirect to `redirect_uri`
+/// with the tokens in the URL fragment.
+fn respond(
+    redirect_uri: Option<Url>,
+    id_token: &str,
+    claims: &IdTokenClaims,
+    custom_token: Option<&str>,
+) -> HttpResponse {
+    let Some(mut redirect_uri) = redirect_uri else {
+        let mut body = json!({
+            "idToken": id_token,
+            "sub": claims.sub,
+            "email": claims.email,
+        });
+        if let Some(custom_token) = custom_token {
+            body["customToken"] = custom_token.into();
+        }
+        return HttpResponse::Ok().json(body);
+    };
+
+    let mut fragment = url::form_urlencoded::Serializer::new(String::new());
+    fragment.append_pair("id_token", id_token);
+    if let Some(custom_token) = custom_token {
+        fragment.append_pair("custom_token", custom_token);
+    }
+    redirect_uri.set_fragment(Some(&fragment.finish()));
+
+    HttpResponse::Found()
+        .append_header((header::LOCATION, redirect_uri.to_string()))
+        .finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::body::MessageBody;
+    use actix_web::cookie::Cookie;
+    use actix_web::test::TestRequest;
+    use fireauth2::InMemoryFirebaseAdmin;
+
+    fn form(csrf_token: &str) -> GisCredentialPayload {
+        serde_json::from_value(json!({
+            "credential": "id-token",
+            "g_csrf_token": csrf_token,
+        }))
+        .unwrap()
+    }
+
+    fn claims() -> IdTokenClaims {
+        serde_json::from_value(json!({
+            "iss": "https://accounts.google.com",
+            "aud": "client-id",
+            "sub": "google-123",
+            "exp": 0,
+            "iat": 0,
+            "email": "alice@example.com",
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_verify_csrf_token() {
+        let req = TestRequest::post()
+            .cookie(Cookie::new(GisCredentialPayload::CSRF_TOKEN_NAME, "csrf"))
+            .to_http_request();
+        assert!(verify_csrf_token(&req, &form("csrf")).is_ok());
+        assert!(matches!(
+            verify_csrf_token(&req, &form("forged")),
+            Err(crate::Error::CsrfTokenMismatch)
+        ));
+
+        let req = TestRequest::post().to_http_request();
+        assert!(matches!(
+            verify_csrf_token(&req, &form("")),
+            Err(crate::Error::CsrfTokenMismatch)
+        ));
+    }
+
+    #[actix_web::test]
+    async fn test_create_custom_token_requires_linked_user() {
+        let admin = InMemoryFirebaseAdmin::new();
+        assert_eq!(
+            create_custom_token(&admin, "google-123").await.unwrap(),
+            None
+        );
+
+        admin.link(GOOGLE_PROVIDER_ID, "google-123", "firebase-uid");
+        assert_eq!(
+            create_custom_token(&admin, "google-123").await.unwrap(),
+            Some("fake-custom-token.firebase-uid".into())
+        );
+    }
+
+    #[test]
+    fn test_respond_by_response_mode() {
+        let origins = [Url::parse("https://app.example.com").unwrap()];
+        assert!(matches!(
+            allowed_redirect_uri("https://evil.example.com/", &origins),
+            Err(crate::Error::DisallowedRedirectUrl(_))
+        ));
+        let redirect_uri =
+            allowed_redirect_uri("https://app.example.com/home", &origins)
+                .unwrap();
+
+        let response =
+            respond(Some(redirect_uri), "id-token", &claims(), Some("ct"));
+        assert_eq!(response.status(), 302);
+        assert_eq!(
+            response.headers().get(header::LOCATION).unwrap(),
+            "https://app.example.com/home#id_token=id-token&custom_token=ct"
+        );
+
+        let response = respond(None, "id-token", &claims(), None);
+        assert_eq!(response.status(), 200);
+        let body = response.into_body().try_into_bytes().unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(
+            body,
+            json!({
+                "idToken": "id-token",
+                "sub": "google-123",
+                "email": "alice@example.com",
+            })
+        );
+    }
+}
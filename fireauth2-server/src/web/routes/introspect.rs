@@ -1,6 +1,5 @@
 use crate::Result;
-use crate::web::extractors::FireAuth;
-use actix_firebase_auth::FirebaseUser;
+use crate::web::extractors::{AuthenticatedUser, FireAuth};
 use actix_web::{HttpResponse, Responder, post, web};
 use fireauth2::{TokenIntrospectionPayload, TokenIntrospectionTypeHint};
 use serde_json::json;
@@ -10,7 +9,7 @@ use serde_json::json;
 pub async fn introspect(
     fireauth2: FireAuth,
     form: web::Form<TokenIntrospectionPayload>,
-    _firebase_user: FirebaseUser,
+    _firebase_user: AuthenticatedUser,
 ) -> Result<impl Responder> {
     let payload = form.into_inner();
 
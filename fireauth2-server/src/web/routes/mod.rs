@@ -2,8 +2,13 @@ use actix_web::web;
 
 mod authorize;
 mod callback;
+mod device;
+mod exchange;
+mod gis;
 mod introspect;
+mod providers;
 mod revoke;
+mod session;
 mod token;
 
 pub fn configure(cfg: &mut web::ServiceConfig) {
@@ -11,5 +16,15 @@ pub fn configure(cfg: &mut web::ServiceConfig) {
         .service(callback::exchange_authorization_code)
         .service(revoke::revoke_token)
         .service(token::exchange_refresh_token)
-        .service(introspect::introspect);
+        .service(introspect::introspect)
+        .service(session::create_session)
+        .service(session::logout)
+        .service(gis::gis_credential)
+        .service(exchange::exchange_postmessage_code)
+        .service(exchange::exchange_server_auth_code)
+        .service(device::start_device_authorization)
+        .service(device::poll_device_token)
+        .service(device::exchange_device_refresh_token)
+        .service(providers::authorize)
+        .service(providers::callback);
 }
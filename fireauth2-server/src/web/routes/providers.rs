@@ -0,0 +1,236 @@
+use crate::Result;
+use crate::web::extractors::{FireAuth, get_redirect_uri_from_request};
+use crate::web::session::Session;
+use crate::web::utils::{allowed_redirect_uri, get_referer_url};
+use fireauth2::{
+    ExchangeAuthorizationCodeQueryParams, RequestAccessTokenExtraParams,
+    ScopeList,
+};
+
+use actix_web::http::header;
+use actix_web::{HttpRequest, HttpResponse, get, web};
+use serde::Deserialize;
+use url::Url;
+
+#[derive(Debug, Deserialize)]
+pub struct ProviderAuthorizeQueryParams {
+    redirect_uri: Option<String>,
+    scope: Option<ScopeList>,
+}
+
+/// Returns the server-side callback URI of the given provider.
+fn callback_uri(req: &HttpRequest, provider_id: &str) -> Result<Url> {
+    get_redirect_uri_from_request(
+        req,
+        &format!("/providers/{provider_id}/callback"),
+    )
+}
+
+/// Returns where to redirect after authentication, taken from the
+/// `redirect_uri` query parameter or the `Referer` header.
+///
+/// It must be on one of the allowed origins since the tokens are appended to
+/// it.
+fn redirect_to(
+    req: &HttpRequest,
+    redirect_uri: Option<&str>,
+    allowed_origins: &[Url],
+) -> Result<Url> {
+    let redirect_to = redirect_uri
+        .map(str::to_owned)
+        .or_else(|| get_referer_url(req))
+        .ok_or(crate::Error::MissingRedirectUrl)?;
+    allowed_redirect_uri(&urlencoding::decode(&redirect_to)?, allowed_origins)
+}
+
+/// GET `/providers/{provider}/authorize`
+///
+/// Initiates the authorization code flow of an additional provider, either
+/// GitHub or an OIDC provider configured via `FIREAUTH2_OIDC_PROVIDERS`
+/// (e.g. Microsoft Entra ID or Okta).
+///
+/// ### Query Parameters
+/// - `redirect_uri` _(optional)_: Where to redirect after authentication.
+///   Falls back to the `Referer` header if omitted. Must be on one of the
+///   allowed JavaScript origins of the OAuth client.
+/// - `scope` _(optional)_: Space-separated scopes. Defaults to the scopes
+///   configured for the provider.
+///
+/// `<origin>/providers/{provider}/callback` must be registered as redirect
+/// URI with the provider.
+///
+/// ### Response
+/// - `302 Found` Redirect to the provider's authorization endpoint.
+///
+/// ### Errors
+/// - `400 Bad Request` — if the redirect URI is not on an allowed origin.
+///
+/// ---
+#[get("/providers/{provider}/authorize")]
+pub async fn authorize(
+    req: HttpRequest,
+    fireauth2: FireAuth,
+    provider_id: web::Path<String>,
+    query: web::Query<ProviderAuthorizeQueryParams>,
+) -> Result<HttpResponse> {
+    let provider = fireauth2.provider(&provider_id)?;
+    let query = query.into_inner();
+
+    let redirect_to = redirect_to(
+        &req,
+        query.redirect_uri.as_deref(),
+        fireauth2.allowed_origins(),
+    )?;
+
+    let scopes = query.scope.map(|scopes| scopes.0).unwrap_or_default();
+    let response = provider
+        .request_access_token(callback_uri(&req, &provider_id)?, &scopes);
+
+    let session = Session::new(
+        response.pkce_verifier(),
+        response.csrf_token(),
+        redirect_to,
+        RequestAccessTokenExtraParams::default(),
+        response.nonce(),
+        response.scopes(),
+    )
+    .with_provider(provider_id.as_str());
+
+    Ok(HttpResponse::Found()
+        .append_header((header::LOCATION, response.url().to_string()))
+        .cookie(session.into_cookie()?)
+        .finish())
+}
+
+/// Checks that the callback belongs to the session's authorization request
+/// and returns the error reported to the client otherwise.
+fn verify_session(
+    session: &Session,
+    provider_id: &str,
+    state: &str,
+) -> std::result::Result<(), &'static str> {
+    if session.provider.as_deref() != Some(provider_id) {
+        return Err("Session was not started for this provider");
+    }
+    if session.csrf_token != state {
+        return Err("CSRF token mismatch");
+    }
+    Ok(())
+}
+
+/// GET `/providers/{provider}/callback`
+///
+/// Finalizes the authorization code flow of an additional provider.
+/// Verifies that the session was started for the provider and the CSRF
+/// token, exchanges the code, checks the ID token's nonce, resolves the user (from the
+/// verified ID token or the provider's user API) and stores the refresh
+/// token, if any, in the `<provider>Users` collection.
+///
+/// ### Response
+/// - `302 Found` Redirect to the original application URL with
+///   `access_token`, `issued_at` and, if issued, `id_token` and `expires_in`
+///   in the URL fragment, or `error` on failure.
+///
+/// ---
+#[get("/providers/{provider}/callback")]
+pub async fn callback(
+    req: HttpRequest,
+    fireauth2: FireAuth,
+    provider_id: web::Path<String>,
+    query: web::Query<ExchangeAuthorizationCodeQueryParams>,
+) -> Result<HttpResponse> {
+    let provider = fireauth2.provider(&provider_id)?;
+    let session = Session::from_request(&req)?;
+    let verified = verify_session(&session, &provider_id, &query.state);
+    let mut redirect_to = session.redirect_to;
+
+    let mut fragment = url::form_urlencoded::Serializer::new(String::new());
+    if let Err(error) = verified {
+        fragment.append_pair("error", error);
+    } else {
+        match provider
+            .exchange_authorization_code(
+                callback_uri(&req, &provider_id)?,
+                query.code.clone(),
+                session.pkce_verifier,
+                session.nonce.as_deref(),
+            )
+            .await
+        {
+            Ok(tokens) => {
+                fragment
+                    .append_pair("access_token", tokens.access_token())
+                    .append_pair("issued_at", &tokens.issued_at().to_string());
+                if let Some(id_token) = tokens.id_token() {
+                    fragment.append_pair("id_token", id_token);
+                }
+                if let Some(expires_in) = tokens.expires_in() {
+                    fragment.append_pair("expires_in", &expires_in.to_string());
+                }
+            }
+            Err(err) => {
+                fragment.append_pair("error", &err.to_string());
+            }
+        }
+    }
+    redirect_to.set_fragment(Some(&fragment.finish()));
+
+    Ok(HttpResponse::Found()
+        .append_header((header::LOCATION, redirect_to.to_string()))
+        .finish())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::test::TestRequest;
+    use fireauth2::{CsrfToken, PkceCodeVerifier};
+
+    #[test]
+    fn test_redirect_to_requires_allowed_origin() {
+        let origins = [Url::parse("https://app.example.com").unwrap()];
+
+        let req = TestRequest::get()
+            .insert_header((header::REFERER, "https://evil.example.com/"))
+            .to_http_request();
+        assert!(matches!(
+            redirect_to(&req, None, &origins),
+            Err(crate::Error::DisallowedRedirectUrl(_))
+        ));
+        assert!(matches!(
+            redirect_to(&req, Some("https://evil.example.com/"), &origins),
+            Err(crate::Error::DisallowedRedirectUrl(_))
+        ));
+
+        let redirect_uri = redirect_to(
+            &req,
+            Some("https%3A%2F%2Fapp.example.com%2Fhome"),
+            &origins,
+        )
+        .unwrap();
+        assert_eq!(redirect_uri.as_str(), "https://app.example.com/home");
+    }
+
+    #[test]
+    fn test_verify_session_checks_provider_and_state() {
+        let csrf_token = CsrfToken::new("state".into());
+        let session = Session::new(
+            &PkceCodeVerifier::new("verifier".into()),
+            &csrf_token,
+            Url::parse("https://app.example.com").unwrap(),
+            RequestAccessTokenExtraParams::default(),
+            None,
+            &[],
+        );
+
+        assert!(verify_session(&session, "github", "state").is_err());
+
+        let session = session.with_provider("github");
+        assert!(verify_session(&session, "github", "state").is_ok());
+        assert!(verify_session(&session, "okta", "state").is_err());
+        assert_eq!(
+            verify_session(&session, "github", "other"),
+            Err("CSRF token mismatch")
+        );
+    }
+}
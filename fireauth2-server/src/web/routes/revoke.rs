@@ -1,6 +1,6 @@
 use crate::Result;
-use crate::web::extractors::FireAuth;
-use actix_firebase_auth::{FirebaseUser, GoogleUserId};
+use crate::web::extractors::{AuthenticatedUser, FireAuth};
+use actix_firebase_auth::GoogleUserId;
 use actix_web::{HttpResponse, post, web};
 use fireauth2::{TokenRevocationConfig, TokenRevocationPayload};
 
@@ -39,17 +39,20 @@ use fireauth2::{TokenRevocationConfig, TokenRevocationPayload};
 /// ### Response
 /// - `200 OK`: Token successfully revoked (empty response body).
 /// - `400 Bad Request`: Invalid input.
+/// - `401 Unauthorized`: If `FIREAUTH2_STEP_UP` requires a more recent sign-in,
+///   with `error: "reauthentication_required"`, the `maxAge` in seconds and an
+///   `authorizeUrl` to sign in to Google again.
 /// - `500 Internal Server Error`: If the request to Google's revocation endpoint fails.
 ///
 /// ---
 #[post("/revoke")]
 pub async fn revoke_token(
     fireauth2: FireAuth,
-    firebase_user: FirebaseUser,
+    firebase_user: AuthenticatedUser,
     payload: web::Json<TokenRevocationPayload>,
 ) -> Result<HttpResponse> {
     let payload = payload.into_inner();
-    let google_user_id = GoogleUserId::try_from(&firebase_user)?;
+    let google_user_id = GoogleUserId::try_from(&*firebase_user)?;
 
     let config = TokenRevocationConfig::new(payload, &*google_user_id);
     fireauth2.revoke_token(config).await?;
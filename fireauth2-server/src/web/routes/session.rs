@@ -0,0 +1,215 @@
+use crate::Result;
+use crate::web::AppState;
+use crate::web::firebase_session::FirebaseSession;
+use crate::web::utils::get_bearer_token;
+use actix_firebase_auth::FirebaseUser;
+use actix_web::{HttpResponse, post, web};
+use fireauth2::FirebaseAdmin;
+use serde_json::json;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Maximum time since sign-in for an ID token to be exchanged for a session cookie.
+const MAX_SIGN_IN_AGE: u64 = 5 * 60; // in seconds
+
+/// POST `/session`
+///
+/// Exchanges a freshly issued Firebase ID token for a Firebase **session cookie**.
+/// Afterwards, the `/token`, `/revoke` and `/introspect` routes accept the cookie
+/// instead of a Bearer ID token, so the frontend never has to handle tokens in JavaScript.
+///
+/// ### Request
+/// - `Authorization: Bearer <Firebase ID token>`
+///
+/// The user must have signed in within the last 5 minutes.
+///
+/// ### Response
+/// - `200 OK` with two cookies:
+///   - the `httpOnly` session cookie (default name `__session`)
+///   - a readable `fireauth2_csrf` cookie
+///
+///   and the CSRF token in the body:
+///
+/// ```json
+/// { "csrfToken": "..." }
+/// ```
+///
+/// Every cookie-authenticated `POST` must send this token in the `X-CSRF-Token` header.
+///
+/// ### Errors
+/// - `401 Unauthorized` — if the ID token is missing, invalid or too old.
+///
+/// ---
+#[post("/session")]
+pub async fn create_session(
+    req: actix_web::HttpRequest,
+    admin: web::Data<dyn FirebaseAdmin>,
+    state: AppState,
+    firebase_user: FirebaseUser,
+) -> Result<HttpResponse> {
+    let id_token =
+        get_bearer_token(&req).ok_or(crate::Error::Unauthenticated)?;
+    start_session(admin.as_ref(), &id_token, firebase_user.auth_time, &state)
+        .await
+}
+
+/// Exchanges a verified Firebase ID token, signed in at `auth_time`, for the
+/// session and CSRF cookies.
+async fn start_session(
+    admin: &dyn FirebaseAdmin,
+    id_token: &str,
+    auth_time: u64,
+    state: &AppState,
+) -> Result<HttpResponse> {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs());
+    if now.saturating_sub(auth_time) > MAX_SIGN_IN_AGE {
+        return Err(crate::Error::StaleIdToken);
+    }
+
+    let max_age = state.firebase_session_cookie_max_age();
+    let session_cookie = admin
+        .create_session_cookie(id_token, Duration::from_secs(max_age))
+        .await?;
+
+    let session = FirebaseSession::new(session_cookie);
+    let body = json!({ "csrfToken": session.csrf_token() });
+    let (session_cookie, csrf_cookie) =
+        session.into_cookies(state.firebase_session_cookie_name(), max_age);
+
+    Ok(HttpResponse::Ok()
+        .cookie(session_cookie)
+        .cookie(csrf_cookie)
+        .json(body))
+}
+
+/// POST `/session/logout`
+///
+/// Clears the Firebase session and CSRF cookies.
+///
+/// ### Request
+/// - `X-CSRF-Token` header matching the `fireauth2_csrf` cookie, if a session
+///   cookie is sent.
+///
+/// ### Response
+/// - `204 No Content`
+///
+/// ### Errors
+/// - `403 Forbidden` — if the CSRF token is missing or does not match.
+///
+/// ---
+#[post("/session/logout")]
+pub async fn logout(
+    req: actix_web::HttpRequest,
+    state: AppState,
+) -> Result<HttpResponse> {
+    if req.cookie(state.firebase_session_cookie_name()).is_some() {
+        FirebaseSession::verify_csrf(&req)?;
+    }
+
+    let mut response = HttpResponse::NoContent();
+    for cookie in
+        FirebaseSession::removal_cookies(state.firebase_session_cookie_name())
+    {
+        response.cookie(cookie);
+    }
+    Ok(response.finish())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::body::MessageBody;
+    use actix_web::cookie::Cookie;
+    use actix_web::{App, test};
+    use fireauth2::InMemoryFirebaseAdmin;
+
+    fn now() -> u64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs()
+    }
+
+    #[actix_web::test]
+    async fn test_start_session_sets_cookies() {
+        let state = AppState::default();
+        let admin = InMemoryFirebaseAdmin::new();
+
+        let response = start_session(&admin, "id-token", now(), &state)
+            .await
+            .unwrap();
+
+        let cookies = response.cookies().collect::<Vec<_>>();
+        let session = cookies
+            .iter()
+            .find(|c| c.name() == state.firebase_session_cookie_name())
+            .unwrap();
+        assert!(session.value().ends_with(".id-token"));
+        assert_eq!(session.http_only(), Some(true));
+        let csrf = cookies
+            .iter()
+            .find(|c| c.name() == FirebaseSession::CSRF_COOKIE_NAME)
+            .unwrap();
+        assert_ne!(csrf.http_only(), Some(true));
+        let csrf_token = csrf.value().to_owned();
+
+        let body = response.into_body().try_into_bytes().unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body["csrfToken"], csrf_token);
+
+        let stale = now() - MAX_SIGN_IN_AGE - 1;
+        assert!(matches!(
+            start_session(&admin, "id-token", stale, &state).await,
+            Err(crate::Error::StaleIdToken)
+        ));
+    }
+
+    #[actix_web::test]
+    async fn test_logout_checks_csrf_and_removes_cookies() {
+        let state = AppState::default();
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(state.clone()))
+                .service(logout),
+        )
+        .await;
+
+        let session_cookie = Cookie::new(
+            state.firebase_session_cookie_name().to_owned(),
+            "session-cookie",
+        );
+        let csrf_cookie =
+            Cookie::new(FirebaseSession::CSRF_COOKIE_NAME, "csrf");
+        let req = test::TestRequest::post()
+            .uri("/session/logout")
+            .cookie(session_cookie.clone())
+            .cookie(csrf_cookie.clone())
+            .to_request();
+        let response = test::call_service(&app, req).await;
+        assert_eq!(response.status(), 403);
+
+        let req = test::TestRequest::post()
+            .uri("/session/logout")
+            .cookie(session_cookie)
+            .cookie(csrf_cookie)
+            .insert_header((FirebaseSession::CSRF_HEADER_NAME, "csrf"))
+            .to_request();
+        let response = test::call_service(&app, req).await;
+
+        assert_eq!(response.status(), 204);
+        let removed = response
+            .response()
+            .cookies()
+            .filter(|c| c.value().is_empty() && c.max_age().is_some())
+            .map(|c| c.name().to_owned())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            removed,
+            [
+                state.firebase_session_cookie_name(),
+                FirebaseSession::CSRF_COOKIE_NAME
+            ]
+        );
+    }
+}
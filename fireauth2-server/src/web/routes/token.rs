@@ -1,6 +1,6 @@
 use crate::Result;
-use crate::web::extractors::FireAuth;
-use actix_firebase_auth::{FirebaseUser, GoogleUserId};
+use crate::web::extractors::{AuthenticatedUser, FireAuth};
+use actix_firebase_auth::GoogleUserId;
 use actix_web::{HttpResponse, post};
 
 /// POST `/token`
@@ -59,9 +59,9 @@ use actix_web::{HttpResponse, post};
 #[post("/token")]
 pub async fn exchange_refresh_token(
     fireauth2: FireAuth,
-    firebase_user: FirebaseUser,
+    firebase_user: AuthenticatedUser,
 ) -> Result<HttpResponse> {
-    let google_user_id = GoogleUserId::try_from(&firebase_user)?;
+    let google_user_id = GoogleUserId::try_from(&*firebase_user)?;
     let response = fireauth2.exchange_refresh_token(&*google_user_id).await?;
     Ok(HttpResponse::Ok().json(response))
 }
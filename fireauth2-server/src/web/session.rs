@@ -3,7 +3,9 @@ use actix_web::{
     HttpRequest,
     cookie::{Cookie, SameSite, time::Duration},
 };
-use fireauth2::{CsrfToken, PkceCodeVerifier, RequestAccessTokenExtraParams};
+use fireauth2::{
+    CsrfToken, Nonce, PkceCodeVerifier, RequestAccessTokenExtraParams, Scope,
+};
 use serde::{Deserialize, Serialize};
 use url::Url;
 
@@ -26,6 +28,18 @@ pub struct Session {
 
     /// Arbitrary user-defined extra OAuth2 parameters (e.g., prompt, login_hint).
     pub(crate) extra_params: RequestAccessTokenExtraParams,
+
+    /// Nonce the ID token issued on callback must carry.
+    #[serde(default)]
+    pub(crate) nonce: Option<Nonce>,
+
+    /// Scopes sent with the authorization request.
+    #[serde(default)]
+    pub(crate) scopes: Vec<Scope>,
+
+    /// ID of the additional provider the flow was started for, if any.
+    #[serde(default)]
+    pub(crate) provider: Option<String>,
 }
 
 impl Session {
@@ -40,15 +54,27 @@ impl Session {
         csrf_token: &CsrfToken,
         redirect_to: Url,
         extra_params: RequestAccessTokenExtraParams,
+        nonce: Option<&Nonce>,
+        scopes: &[Scope],
     ) -> Self {
         Self {
-            pkce_verifier: verifier.secret().to_string(),
-            csrf_token: csrf_token.secret().to_string(),
+            pkce_verifier: verifier.secret().clone(),
+            csrf_token: csrf_token.secret().clone(),
             redirect_to,
             extra_params,
+            nonce: nonce.cloned(),
+            scopes: scopes.to_vec(),
+            provider: None,
         }
     }
 
+    /// Binds the session to the flow of the given additional provider.
+    #[must_use]
+    pub fn with_provider(mut self, provider_id: impl Into<String>) -> Self {
+        self.provider = Some(provider_id.into());
+        self
+    }
+
     /// Serializes the payload into a secure, short-lived HTTP cookie.
     ///
     /// - `http_only`: Prevents client-side JS access (defense against XSS).
@@ -1,12 +1,23 @@
 #![expect(unused)]
 
+use std::time::Duration;
+
 use crate::impl_actix_from_request;
+use crate::web::ResponseMode;
+use crate::web::app_check::AppCheckPolicy;
+use crate::web::step_up::StepUpPolicy;
+use fireauth2::{
+    CustomParamsAllowlist, OidcProviderConfig, OpenIdScopePolicy,
+    RequestValidationMode, ScopeClaimsMapping, ScopePolicy, SignInPolicy,
+};
 
 const DEFAULT_FIREAUTH2_REDIRECT_URI_PATH: &str = "/callback";
 const DEFAULT_FIREAUTH2_SESSION_COOKIE_NAME: &str = "FIREAUTH2_SESSION";
 const DEFAULT_FIREAUTH2_SESSION_COOKIE_MAX_AGE: u16 = 180; // in seconds
 const DEFAULT_FIREAUTH2_FIRESTORE_COLLECTION: &str = "googleUsers";
 const DEFAULT_FIREAUTH2_ENABLE_EXISTING_TOKEN_REVOCATION: bool = false;
+const DEFAULT_FIREAUTH2_FIREBASE_SESSION_COOKIE_NAME: &str = "__session";
+const DEFAULT_FIREAUTH2_FIREBASE_SESSION_COOKIE_MAX_AGE: u64 = 432_000; // in seconds (5 days)
 
 #[derive(Debug, Clone)]
 pub struct AppState {
@@ -21,42 +32,114 @@ pub struct AppState {
     /// Note, that the URI provided here must also be set in the the OAuth 2.0 Client config
     /// json in the Google Cloud Platform console.
     redirect_uri_path: String,
+    /// Projection of granted Google scopes into Firebase custom claims.
+    /// Scope claims sync is disabled if unset.
+    scope_claims_mapping: Option<ScopeClaimsMapping>,
+    /// Name of the cookie holding the Firebase session cookie.
+    ///
+    /// Defaults to `__session`, the only cookie forwarded by Firebase Hosting.
+    firebase_session_cookie_name: String,
+    /// Lifetime of Firebase session cookies in seconds.
+    /// Firebase accepts values between 5 minutes and 2 weeks.
+    firebase_session_cookie_max_age: u64,
+    /// Per-route Firebase App Check enforcement. Disabled by default.
+    app_check_policy: AppCheckPolicy,
+    /// Firebase project number App Check tokens must be issued for.
+    app_check_project_number: Option<String>,
+    /// Per-route maximum sign-in age of authenticated users.
+    step_up_policy: StepUpPolicy,
+    /// How `POST /gis/credential` returns sign-in results.
+    gis_response_mode: ResponseMode,
+    /// Whether `POST /gis/credential` also returns a Firebase custom token.
+    gis_custom_token: bool,
+    /// Service account used to sign Firebase custom tokens.
+    service_account_email: Option<String>,
+    /// Android and iOS OAuth client IDs whose `serverAuthCode`s are accepted.
+    platform_client_ids: Vec<String>,
+    /// Additional OAuth client IDs accepted as ID token audience.
+    id_token_audiences: Vec<String>,
+    /// OAuth client IDs ID tokens must be authorized by (`azp`), if any.
+    id_token_authorized_parties: Vec<String>,
+    /// Clock skew tolerated when validating ID tokens, in seconds.
+    id_token_clock_skew: Option<u64>,
+    /// OAuth client ("TVs and Limited Input devices") used for the device
+    /// authorization grant. Device routes are rejected if unset.
+    device_client: Option<(String, String)>,
+    /// GitHub OAuth app client ID and secret. The `github` provider is
+    /// disabled if unset.
+    github_client: Option<(String, String)>,
+    /// Custom authorization parameters `/authorize` may forward to Google.
+    custom_params_allowlist: CustomParamsAllowlist,
+    /// How `/authorize` requests without the `openid` scope are handled.
+    openid_scope_policy: OpenIdScopePolicy,
+    /// How `/authorize` requests failing validation are handled.
+    request_validation: RequestValidationMode,
+    /// Scope profiles and raw scopes `/authorize` accepts.
+    scope_policy: ScopePolicy,
+    /// Google accounts allowed to sign in.
+    sign_in_policy: SignInPolicy,
+    /// Additional OIDC providers, discovered on startup.
+    oidc_providers: Vec<OidcProviderConfig>,
 }
 
 impl AppState {
     pub fn from_env() -> crate::Result<Self> {
-        let redirect_uri_path = option_env!("FIREAUTH2_REDIRECT_URI_PATH")
-            .map(std::borrow::ToOwned::to_owned)
+        let redirect_uri_path = env_var("FIREAUTH2_REDIRECT_URI_PATH")
             .unwrap_or_else(|| DEFAULT_FIREAUTH2_REDIRECT_URI_PATH.to_string());
 
-        let cookie_name = option_env!("FIREAUTH2_SESSION_COOKIE_NAME")
-            .map(std::borrow::ToOwned::to_owned)
+        let cookie_name = env_var("FIREAUTH2_SESSION_COOKIE_NAME")
             .unwrap_or_else(|| {
                 DEFAULT_FIREAUTH2_SESSION_COOKIE_NAME.to_string()
             });
 
-        let cookie_max_age = option_env!("FIREAUTH2_SESSION_COOKIE_MAX_AGE")
-            .map(std::borrow::ToOwned::to_owned)
-            .unwrap_or_else(|| {
-                DEFAULT_FIREAUTH2_SESSION_COOKIE_MAX_AGE.to_string()
-            })
-            .parse::<u16>()?;
+        let cookie_max_age = env_parse("FIREAUTH2_SESSION_COOKIE_MAX_AGE")?
+            .unwrap_or(DEFAULT_FIREAUTH2_SESSION_COOKIE_MAX_AGE);
 
         let firestore_collection_name =
-            option_env!("FIREAUTH2_FIRESTORE_COLLECTION")
-                .map(std::borrow::ToOwned::to_owned)
-                .unwrap_or_else(|| {
-                    DEFAULT_FIREAUTH2_FIRESTORE_COLLECTION.to_string()
-                });
+            env_var("FIREAUTH2_FIRESTORE_COLLECTION").unwrap_or_else(|| {
+                DEFAULT_FIREAUTH2_FIRESTORE_COLLECTION.to_string()
+            });
 
         let enable_existing_token_revocation =
-            option_env!("FIREAUTH2_ENABLE_EXISTING_TOKEN_REVOCATION")
-                .map(std::borrow::ToOwned::to_owned)
-                .unwrap_or_else(|| {
-                    DEFAULT_FIREAUTH2_ENABLE_EXISTING_TOKEN_REVOCATION
-                        .to_string()
-                })
-                .parse::<bool>()?;
+            env_parse("FIREAUTH2_ENABLE_EXISTING_TOKEN_REVOCATION")?
+                .unwrap_or(DEFAULT_FIREAUTH2_ENABLE_EXISTING_TOKEN_REVOCATION);
+
+        let firebase_session_cookie_name =
+            env_var("FIREAUTH2_FIREBASE_SESSION_COOKIE_NAME").unwrap_or_else(
+                || DEFAULT_FIREAUTH2_FIREBASE_SESSION_COOKIE_NAME.to_string(),
+            );
+
+        let firebase_session_cookie_max_age =
+            env_parse("FIREAUTH2_FIREBASE_SESSION_COOKIE_MAX_AGE")?
+                .unwrap_or(DEFAULT_FIREAUTH2_FIREBASE_SESSION_COOKIE_MAX_AGE);
+
+        let app_check_policy: AppCheckPolicy =
+            env_parse("FIREAUTH2_APP_CHECK")?.unwrap_or_default();
+
+        let app_check_project_number =
+            env_var("FIREAUTH2_APP_CHECK_PROJECT_NUMBER");
+
+        if app_check_policy.is_enabled() && app_check_project_number.is_none() {
+            return Err(crate::Error::InvalidConfig {
+                because: "FIREAUTH2_APP_CHECK_PROJECT_NUMBER is required when App Check is enabled".into(),
+            });
+        }
+
+        let gis_custom_token =
+            env_parse("FIREAUTH2_GIS_CUSTOM_TOKEN")?.unwrap_or_default();
+
+        let service_account_email = env_var("FIREAUTH2_SERVICE_ACCOUNT_EMAIL");
+
+        if gis_custom_token && service_account_email.is_none() {
+            return Err(crate::Error::InvalidConfig {
+                because: "FIREAUTH2_SERVICE_ACCOUNT_EMAIL is required when FIREAUTH2_GIS_CUSTOM_TOKEN is enabled".into(),
+            });
+        }
+
+        let github_client = env_pair(
+            "FIREAUTH2_GITHUB_CLIENT_ID",
+            "FIREAUTH2_GITHUB_CLIENT_SECRET",
+        )?;
 
         Ok(Self {
             cookie_name,
@@ -64,6 +147,37 @@ impl AppState {
             enable_existing_token_revocation,
             firestore_collection_name,
             redirect_uri_path,
+            scope_claims_mapping: env_json("FIREAUTH2_SCOPE_CLAIMS_MAPPING")?,
+            firebase_session_cookie_name,
+            firebase_session_cookie_max_age,
+            app_check_policy,
+            step_up_policy: env_parse("FIREAUTH2_STEP_UP")?.unwrap_or_default(),
+            app_check_project_number,
+            gis_response_mode: env_parse("FIREAUTH2_GIS_RESPONSE_MODE")?
+                .unwrap_or_default(),
+            gis_custom_token,
+            service_account_email,
+            platform_client_ids: env_list("FIREAUTH2_PLATFORM_CLIENT_IDS"),
+            id_token_audiences: env_list("FIREAUTH2_ID_TOKEN_AUDIENCES"),
+            id_token_authorized_parties: env_list(
+                "FIREAUTH2_ID_TOKEN_AUTHORIZED_PARTIES",
+            ),
+            id_token_clock_skew: env_parse("FIREAUTH2_ID_TOKEN_CLOCK_SKEW")?,
+            device_client: env_pair(
+                "FIREAUTH2_DEVICE_CLIENT_ID",
+                "FIREAUTH2_DEVICE_CLIENT_SECRET",
+            )?,
+            github_client,
+            custom_params_allowlist: env_json_or_default(
+                "FIREAUTH2_CUSTOM_AUTHORIZATION_PARAMS",
+            )?,
+            openid_scope_policy: env_parse("FIREAUTH2_OPENID_SCOPE_POLICY")?
+                .unwrap_or_default(),
+            request_validation: env_parse("FIREAUTH2_REQUEST_VALIDATION")?
+                .unwrap_or_default(),
+            scope_policy: env_json_or_default("FIREAUTH2_SCOPE_POLICY")?,
+            sign_in_policy: env_json_or_default("FIREAUTH2_SIGN_IN_POLICY")?,
+            oidc_providers: env_json_or_default("FIREAUTH2_OIDC_PROVIDERS")?,
         })
     }
 
@@ -86,6 +200,208 @@ impl AppState {
     pub fn enable_existing_token_revocation(&self) -> bool {
         self.enable_existing_token_revocation
     }
+
+    pub fn scope_claims_mapping(&self) -> Option<&ScopeClaimsMapping> {
+        self.scope_claims_mapping.as_ref()
+    }
+
+    pub fn firebase_session_cookie_name(&self) -> &str {
+        &self.firebase_session_cookie_name
+    }
+
+    pub fn firebase_session_cookie_max_age(&self) -> u64 {
+        self.firebase_session_cookie_max_age
+    }
+
+    pub fn app_check_policy(&self) -> &AppCheckPolicy {
+        &self.app_check_policy
+    }
+
+    pub fn step_up_policy(&self) -> &StepUpPolicy {
+        &self.step_up_policy
+    }
+
+    pub fn app_check_project_number(&self) -> Option<&str> {
+        self.app_check_project_number.as_deref()
+    }
+
+    pub fn gis_response_mode(&self) -> ResponseMode {
+        self.gis_response_mode
+    }
+
+    pub fn gis_custom_token(&self) -> bool {
+        self.gis_custom_token
+    }
+
+    pub fn service_account_email(&self) -> Option<&str> {
+        self.service_account_email.as_deref()
+    }
+
+    pub fn platform_client_ids(&self) -> &[String] {
+        &self.platform_client_ids
+    }
+
+    pub fn id_token_audiences(&self) -> &[String] {
+        &self.id_token_audiences
+    }
+
+    pub fn id_token_authorized_parties(&self) -> &[String] {
+        &self.id_token_authorized_parties
+    }
+
+    pub fn id_token_clock_skew(&self) -> Option<Duration> {
+        self.id_token_clock_skew.map(Duration::from_secs)
+    }
+
+    pub fn custom_params_allowlist(&self) -> &CustomParamsAllowlist {
+        &self.custom_params_allowlist
+    }
+
+    pub fn openid_scope_policy(&self) -> OpenIdScopePolicy {
+        self.openid_scope_policy
+    }
+
+    pub fn request_validation(&self) -> RequestValidationMode {
+        self.request_validation
+    }
+
+    pub fn scope_policy(&self) -> &ScopePolicy {
+        &self.scope_policy
+    }
+
+    pub fn sign_in_policy(&self) -> &SignInPolicy {
+        &self.sign_in_policy
+    }
+
+    pub fn oidc_providers(&self) -> &[OidcProviderConfig] {
+        &self.oidc_providers
+    }
+
+    /// Returns the device client ID and secret, if configured.
+    pub fn device_client(&self) -> Option<(&str, &str)> {
+        self.device_client
+            .as_ref()
+            .map(|(id, secret)| (id.as_str(), secret.as_str()))
+    }
+
+    /// Returns the GitHub client ID and secret, if configured.
+    pub fn github_client(&self) -> Option<(&str, &str)> {
+        self.github_client
+            .as_ref()
+            .map(|(id, secret)| (id.as_str(), secret.as_str()))
+    }
+}
+
+/// Default settings, independent of the environment tests run in.
+#[cfg(test)]
+impl Default for AppState {
+    fn default() -> Self {
+        Self {
+            cookie_name: DEFAULT_FIREAUTH2_SESSION_COOKIE_NAME.to_string(),
+            cookie_max_age: DEFAULT_FIREAUTH2_SESSION_COOKIE_MAX_AGE,
+            enable_existing_token_revocation:
+                DEFAULT_FIREAUTH2_ENABLE_EXISTING_TOKEN_REVOCATION,
+            firestore_collection_name: DEFAULT_FIREAUTH2_FIRESTORE_COLLECTION
+                .to_string(),
+            redirect_uri_path: DEFAULT_FIREAUTH2_REDIRECT_URI_PATH.to_string(),
+            scope_claims_mapping: None,
+            firebase_session_cookie_name:
+                DEFAULT_FIREAUTH2_FIREBASE_SESSION_COOKIE_NAME.to_string(),
+            firebase_session_cookie_max_age:
+                DEFAULT_FIREAUTH2_FIREBASE_SESSION_COOKIE_MAX_AGE,
+            app_check_policy: AppCheckPolicy::default(),
+            app_check_project_number: None,
+            step_up_policy: StepUpPolicy::default(),
+            gis_response_mode: ResponseMode::default(),
+            gis_custom_token: false,
+            service_account_email: None,
+            platform_client_ids: Vec::new(),
+            id_token_audiences: Vec::new(),
+            id_token_authorized_parties: Vec::new(),
+            id_token_clock_skew: None,
+            device_client: None,
+            github_client: None,
+            custom_params_allowlist: CustomParamsAllowlist::default(),
+            openid_scope_policy: OpenIdScopePolicy::default(),
+            request_validation: RequestValidationMode::default(),
+            scope_policy: ScopePolicy::default(),
+            sign_in_policy: SignInPolicy::default(),
+            oidc_providers: Vec::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+impl AppState {
+    /// Replaces the App Check policy.
+    pub(crate) fn with_app_check_policy(
+        mut self,
+        app_check_policy: AppCheckPolicy,
+    ) -> Self {
+        self.app_check_policy = app_check_policy;
+        self
+    }
+}
+
+/// Returns the value of the given environment variable, treating empty
+/// values as unset.
+fn env_var(name: &str) -> Option<String> {
+    std::env::var(name)
+        .ok()
+        .filter(|value| !value.trim().is_empty())
+}
+
+/// Returns the comma-separated values of the given environment variable.
+fn env_list(name: &str) -> Vec<String> {
+    env_var(name)
+        .map(|raw| {
+            raw.split(',')
+                .map(str::trim)
+                .filter(|value| !value.is_empty())
+                .map(str::to_owned)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Returns the values of two environment variables that must be set
+/// together, such as a client ID and secret.
+fn env_pair(
+    first: &str,
+    second: &str,
+) -> crate::Result<Option<(String, String)>> {
+    match (env_var(first), env_var(second)) {
+        (Some(first), Some(second)) => Ok(Some((first, second))),
+        (None, None) => Ok(None),
+        _ => Err(crate::Error::InvalidConfig {
+            because: format!("{first} and {second} must be set together"),
+        }),
+    }
+}
+
+/// Parses the given environment variable with [`str::parse`].
+fn env_parse<T>(name: &str) -> crate::Result<Option<T>>
+where
+    T: std::str::FromStr,
+    crate::Error: From<T::Err>,
+{
+    Ok(env_var(name).map(|raw| raw.trim().parse()).transpose()?)
+}
+
+/// Parses the given environment variable as JSON.
+fn env_json<T: serde::de::DeserializeOwned>(
+    name: &str,
+) -> crate::Result<Option<T>> {
+    Ok(env_var(name)
+        .map(|raw| serde_json::from_str(&raw))
+        .transpose()?)
+}
+
+/// Parses the given environment variable as JSON, defaulting if unset.
+fn env_json_or_default<T: serde::de::DeserializeOwned + Default>(
+    name: &str,
+) -> crate::Result<T> {
+    Ok(env_json(name)?.unwrap_or_default())
 }
 
 impl_actix_from_request!(for AppState);
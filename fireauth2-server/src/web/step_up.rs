@@ -0,0 +1,181 @@
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use actix_web::HttpRequest;
+use url::Url;
+
+use crate::web::extractors::get_redirect_uri_from_request;
+use crate::web::utils::get_referer_url;
+
+/// Per-route step-up authentication requirements.
+///
+/// Parsed from a comma-separated list of `<path>=<minutes>` entries, e.g.
+/// `/revoke=5,/session/logout=15`. Authenticated requests to these routes
+/// are rejected unless the user signed in within the given number of
+/// minutes, either to Firebase (`auth_time` of the Firebase ID token or
+/// session cookie) or to Google via `/authorize` with `max_age`, whose
+/// `auth_time` `/callback` verifies and records.
+///
+/// An `/authorize` entry makes `/authorize` itself send the `max_age`, so
+/// that connecting new scopes requires a recent Google sign-in, too.
+#[derive(Debug, Clone, Default)]
+pub struct StepUpPolicy {
+    routes: HashMap<String, Duration>,
+}
+
+impl StepUpPolicy {
+    /// Returns the maximum authentication age for the given request path.
+    pub fn max_age_for(&self, path: &str) -> Option<Duration> {
+        self.routes.get(path).copied()
+    }
+
+    /// Ensures the user signed in recently enough, given the latest
+    /// `auth_time` of their Firebase or Google sign-in, for the route of
+    /// `req`.
+    ///
+    /// Otherwise, fails with [`crate::Error::ReauthenticationRequired`]
+    /// carrying an `/authorize` URL that makes the user sign in to Google
+    /// again.
+    pub fn enforce(
+        &self,
+        req: &HttpRequest,
+        auth_time: u64,
+        login_hint: Option<&str>,
+    ) -> crate::Result<()> {
+        let Some(max_age) = self.max_age_for(req.path()) else {
+            return Ok(());
+        };
+
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs());
+        if now.saturating_sub(auth_time) <= max_age.as_secs() {
+            return Ok(());
+        }
+
+        Err(crate::Error::ReauthenticationRequired {
+            max_age: max_age.as_secs(),
+            authorize_url: Self::reauthentication_url(
+                req, max_age, login_hint,
+            )?
+            .into(),
+        })
+    }
+
+    /// Builds an `/authorize` URL that requires a Google sign-in within
+    /// `max_age` of the given user and returns to the referring page.
+    fn reauthentication_url(
+        req: &HttpRequest,
+        max_age: Duration,
+        login_hint: Option<&str>,
+    ) -> crate::Result<Url> {
+        let mut url = get_redirect_uri_from_request(req, "/authorize")?;
+        {
+            let mut query = url.query_pairs_mut();
+            query.append_pair("scope", "openid email");
+            query.append_pair("max_age", &max_age.as_secs().to_string());
+            if let Some(login_hint) = login_hint {
+                query.append_pair("login_hint", login_hint);
+            }
+            if let Some(referer) = get_referer_url(req) {
+                query.append_pair("redirect_uri", &referer);
+            }
+        }
+        Ok(url)
+    }
+}
+
+impl FromStr for StepUpPolicy {
+    type Err = crate::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut policy = StepUpPolicy::default();
+
+        for entry in s.split(',').filter(|entry| !entry.trim().is_empty()) {
+            let invalid = || crate::Error::InvalidConfig {
+                because: format!("invalid step-up entry `{entry}`"),
+            };
+            let (path, minutes) = entry.split_once('=').ok_or_else(invalid)?;
+            let seconds = minutes
+                .trim()
+                .parse::<u64>()
+                .ok()
+                .and_then(|minutes| minutes.checked_mul(60))
+                .ok_or_else(invalid)?;
+            policy
+                .routes
+                .insert(path.trim().to_owned(), Duration::from_secs(seconds));
+        }
+
+        Ok(policy)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::ResponseError;
+    use actix_web::body::to_bytes;
+    use actix_web::http::{StatusCode, header};
+    use actix_web::test::TestRequest;
+
+    #[test]
+    fn test_parse_policy() {
+        let policy: StepUpPolicy =
+            "/revoke=5, /session/logout=15".parse().unwrap();
+        assert_eq!(
+            policy.max_age_for("/revoke"),
+            Some(Duration::from_secs(300))
+        );
+        assert_eq!(policy.max_age_for("/token"), None);
+
+        assert!("/revoke".parse::<StepUpPolicy>().is_err());
+        assert!("/revoke=soon".parse::<StepUpPolicy>().is_err());
+        let overflowing = format!("/revoke={}", u64::MAX / 2);
+        assert!(overflowing.parse::<StepUpPolicy>().is_err());
+    }
+
+    #[test]
+    fn test_enforce_checks_auth_time() {
+        let policy: StepUpPolicy = "/revoke=5".parse().unwrap();
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        let req = TestRequest::post()
+            .uri("/revoke")
+            .insert_header((header::REFERER, "https://app.example.com/"))
+            .to_http_request();
+        let hint = Some("alice@example.com");
+
+        assert!(policy.enforce(&req, now - 60, hint).is_ok());
+        let other = TestRequest::post().uri("/token").to_http_request();
+        assert!(policy.enforce(&other, now - 3600, hint).is_ok());
+
+        let err = policy.enforce(&req, now - 3600, hint).unwrap_err();
+        let crate::Error::ReauthenticationRequired {
+            max_age: 300,
+            authorize_url,
+        } = &err
+        else {
+            panic!("unexpected error: {err}");
+        };
+        let authorize_url = Url::parse(authorize_url).unwrap();
+        assert_eq!(authorize_url.path(), "/authorize");
+        let query: HashMap<_, _> = authorize_url.query_pairs().collect();
+        assert_eq!(query["max_age"], "300");
+        assert_eq!(query["login_hint"], "alice@example.com");
+        assert_eq!(query["redirect_uri"], "https://app.example.com/");
+
+        let response = err.error_response();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        let body = actix_web::rt::System::new()
+            .block_on(to_bytes(response.into_body()))
+            .unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body["error"], "reauthentication_required");
+        assert_eq!(body["maxAge"], 300);
+        assert_eq!(body["authorizeUrl"], authorize_url.as_str());
+    }
+}
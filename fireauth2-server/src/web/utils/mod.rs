@@ -1,5 +1,6 @@
 use actix_web::HttpRequest;
-use actix_web::http::header::REFERER;
+use actix_web::http::header::{AUTHORIZATION, REFERER};
+use url::Url;
 
 pub fn get_referer_url(req: &HttpRequest) -> Option<String> {
     req.headers()
@@ -7,3 +8,30 @@ pub fn get_referer_url(req: &HttpRequest) -> Option<String> {
         .and_then(|h| h.to_str().ok())
         .map(str::to_owned)
 }
+
+/// Returns the token of a `Bearer` `Authorization` header, if any.
+pub fn get_bearer_token(req: &HttpRequest) -> Option<String> {
+    req.headers()
+        .get(AUTHORIZATION)
+        .and_then(|h| h.to_str().ok())
+        .and_then(|h| h.strip_prefix("Bearer "))
+        .map(|token| token.trim().to_owned())
+}
+
+/// Parses the redirect URI, which must be on one of the allowed origins
+/// since the tokens are appended to it.
+pub fn allowed_redirect_uri(
+    redirect_to: &str,
+    allowed_origins: &[Url],
+) -> crate::Result<Url> {
+    let redirect_uri = Url::parse(redirect_to)?;
+    if !allowed_origins
+        .iter()
+        .any(|origin| origin.origin() == redirect_uri.origin())
+    {
+        return Err(crate::Error::DisallowedRedirectUrl(
+            redirect_uri.origin().ascii_serialization(),
+        ));
+    }
+    Ok(redirect_uri)
+}
@@ -0,0 +1,247 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use gcloud_sdk::{GoogleAuthTokenGenerator, TokenSourceType};
+use oauth2::reqwest;
+use serde::Deserialize;
+use serde::de::DeserializeOwned;
+use serde_json::{Map, Value, json};
+use tokio::sync::OnceCell;
+
+use super::FirebaseAdmin;
+
+/// [`FirebaseAdmin`] implementation backed by the Identity Toolkit REST API.
+///
+/// Requests are authorized with Google Application Default Credentials,
+/// i.e. the service account the server runs as must be allowed to manage
+/// Firebase Authentication users. The credentials are only resolved on the
+/// first request, so applications not using the admin API need none.
+///
+/// Custom tokens are signed through the IAM Credentials `signJwt` API and
+/// require [`IdentityToolkitAdmin::with_service_account_email`].
+#[derive(Clone)]
+pub struct IdentityToolkitAdmin {
+    project_id: String,
+    service_account_email: Option<String>,
+    http_client: reqwest::Client,
+    token_generator: Arc<OnceCell<GoogleAuthTokenGenerator>>,
+}
+
+impl IdentityToolkitAdmin {
+    const BASE_URL: &'static str = "https://identitytoolkit.googleapis.com/v1";
+    const IAM_CREDENTIALS_URL: &'static str =
+        "https://iamcredentials.googleapis.com/v1/projects/-/serviceAccounts";
+    const CUSTOM_TOKEN_AUDIENCE: &'static str = "https://identitytoolkit.googleapis.com/google.identity.identitytoolkit.v1.IdentityToolkit";
+    const CUSTOM_TOKEN_LIFETIME: i64 = 3600; // in seconds
+    const SCOPES: [&'static str; 2] = [
+        "https://www.googleapis.com/auth/identitytoolkit",
+        "https://www.googleapis.com/auth/cloud-platform",
+    ];
+
+    /// Creates a new admin client for the given Firebase project using
+    /// Application Default Credentials.
+    pub fn new(project_id: impl AsRef<str>) -> crate::Result<Self> {
+        let http_client = reqwest::ClientBuilder::new()
+            .redirect(reqwest::redirect::Policy::none())
+            .build()?;
+
+        Ok(Self {
+            project_id: project_id.as_ref().to_owned(),
+            service_account_email: None,
+            http_client,
+            token_generator: Arc::new(OnceCell::new()),
+        })
+    }
+
+    /// Sets the email of the service account used to sign custom tokens.
+    ///
+    /// The service account the server runs as needs the
+    /// `iam.serviceAccounts.signJwt` permission on it.
+    #[must_use]
+    pub fn with_service_account_email(
+        mut self,
+        email: impl Into<String>,
+    ) -> Self {
+        self.service_account_email = Some(email.into());
+        self
+    }
+
+    /// Sends an authorized `POST` request to the given project resource,
+    /// e.g. `/accounts:lookup` or `:createSessionCookie`.
+    async fn post<T: DeserializeOwned>(
+        &self,
+        resource: &str,
+        body: &Value,
+    ) -> crate::Result<T> {
+        let url = format!(
+            "{}/projects/{}{resource}",
+            Self::BASE_URL,
+            self.project_id
+        );
+        self.post_url(&url, body).await
+    }
+
+    async fn post_url<T: DeserializeOwned>(
+        &self,
+        url: &str,
+        body: &Value,
+    ) -> crate::Result<T> {
+        let token =
+            self.token_generator().await?.create_token().await.map_err(
+                |err| crate::Error::FirebaseAdmin {
+                    because: err.to_string(),
+                },
+            )?;
+
+        let response = self
+            .http_client
+            .post(url)
+            .header(reqwest::header::AUTHORIZATION, token.header_value())
+            .json(body)
+            .send()
+            .await?;
+
+        let status = response.status();
+        let bytes = response.bytes().await?;
+
+        if !status.is_success() {
+            return Err(crate::Error::FirebaseAdmin {
+                because: format!(
+                    "{url} returned {status}: {}",
+                    String::from_utf8_lossy(&bytes)
+                ),
+            });
+        }
+
+        Ok(serde_json::from_slice(&bytes)?)
+    }
+
+    /// Returns the token generator, resolving the Application Default
+    /// Credentials on first use.
+    async fn token_generator(
+        &self,
+    ) -> crate::Result<&GoogleAuthTokenGenerator> {
+        self.token_generator
+            .get_or_try_init(|| async {
+                let scopes = Self::SCOPES.map(ToOwned::to_owned).to_vec();
+                GoogleAuthTokenGenerator::new(TokenSourceType::Default, scopes)
+                    .await
+                    .map_err(|err| crate::Error::FirebaseAdmin {
+                        because: err.to_string(),
+                    })
+            })
+            .await
+    }
+
+    async fn lookup(&self, body: &Value) -> crate::Result<Option<UserRecord>> {
+        let response: LookupResponse =
+            self.post("/accounts:lookup", body).await?;
+        Ok(response.users.into_iter().next())
+    }
+}
+
+#[derive(Deserialize)]
+struct LookupResponse {
+    #[serde(default)]
+    users: Vec<UserRecord>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SessionCookieResponse {
+    session_cookie: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SignJwtResponse {
+    signed_jwt: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct UserRecord {
+    local_id: String,
+    custom_attributes: Option<String>,
+}
+
+#[async_trait::async_trait]
+impl FirebaseAdmin for IdentityToolkitAdmin {
+    async fn lookup_uid(
+        &self,
+        provider_id: &str,
+        raw_id: &str,
+    ) -> crate::Result<Option<String>> {
+        let body = json!({
+            "federatedUserId": [{ "providerId": provider_id, "rawId": raw_id }],
+        });
+        let user = self.lookup(&body).await?;
+        Ok(user.map(|user| user.local_id))
+    }
+
+    async fn custom_claims(
+        &self,
+        uid: &str,
+    ) -> crate::Result<Map<String, Value>> {
+        let body = json!({ "localId": [uid] });
+        let claims = self
+            .lookup(&body)
+            .await?
+            .and_then(|user| user.custom_attributes)
+            .map(|raw| serde_json::from_str(&raw))
+            .transpose()?
+            .unwrap_or_default();
+
+        Ok(claims)
+    }
+
+    async fn set_custom_claims(
+        &self,
+        uid: &str,
+        claims: Map<String, Value>,
+    ) -> crate::Result<()> {
+        let custom_attributes = serde_json::to_string(&claims)?;
+        let body = json!({
+            "localId": uid,
+            "customAttributes": custom_attributes,
+        });
+        let _: Value = self.post("/accounts:update", &body).await?;
+        Ok(())
+    }
+
+    async fn create_session_cookie(
+        &self,
+        id_token: &str,
+        valid_duration: Duration,
+    ) -> crate::Result<String> {
+        let body = json!({
+            "idToken": id_token,
+            "validDuration": valid_duration.as_secs(),
+        });
+        let response: SessionCookieResponse =
+            self.post(":createSessionCookie", &body).await?;
+        Ok(response.session_cookie)
+    }
+
+    async fn create_custom_token(&self, uid: &str) -> crate::Result<String> {
+        let email = self
+            .service_account_email
+            .as_deref()
+            .ok_or(crate::Error::MissingConfigField("service_account_email"))?;
+
+        let iat = chrono::Utc::now().timestamp();
+        let payload = json!({
+            "iss": email,
+            "sub": email,
+            "aud": Self::CUSTOM_TOKEN_AUDIENCE,
+            "iat": iat,
+            "exp": iat + Self::CUSTOM_TOKEN_LIFETIME,
+            "uid": uid,
+        });
+
+        let url = format!("{}/{email}:signJwt", Self::IAM_CREDENTIALS_URL);
+        let body = json!({ "payload": payload.to_string() });
+        let response: SignJwtResponse = self.post_url(&url, &body).await?;
+        Ok(response.signed_jwt)
+    }
+}
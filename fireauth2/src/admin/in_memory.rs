@@ -0,0 +1,98 @@
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+use serde_json::{Map, Value};
+
+use super::FirebaseAdmin;
+
+/// In-memory [`FirebaseAdmin`] fake for tests and local development.
+///
+/// Users must be registered with [`InMemoryFirebaseAdmin::link`] before
+/// they can be looked up. Clones share the same underlying state.
+#[derive(Debug, Clone, Default)]
+pub struct InMemoryFirebaseAdmin {
+    state: Arc<RwLock<InMemoryState>>,
+}
+
+#[derive(Debug, Default)]
+struct InMemoryState {
+    /// Maps `(provider_id, raw_id)` to a Firebase UID.
+    identities: HashMap<(String, String), String>,
+    /// Custom claims keyed by Firebase UID.
+    claims: HashMap<String, Map<String, Value>>,
+}
+
+impl InMemoryFirebaseAdmin {
+    /// Creates an empty fake.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Links a federated identity to a Firebase UID.
+    pub fn link(
+        &self,
+        provider_id: impl Into<String>,
+        raw_id: impl Into<String>,
+        uid: impl Into<String>,
+    ) {
+        let mut state = self.state.write().unwrap();
+        state
+            .identities
+            .insert((provider_id.into(), raw_id.into()), uid.into());
+    }
+
+    /// Returns the custom claims currently stored for the given UID.
+    pub fn claims_of(&self, uid: &str) -> Map<String, Value> {
+        let state = self.state.read().unwrap();
+        state.claims.get(uid).cloned().unwrap_or_default()
+    }
+}
+
+#[async_trait::async_trait]
+impl FirebaseAdmin for InMemoryFirebaseAdmin {
+    async fn lookup_uid(
+        &self,
+        provider_id: &str,
+        raw_id: &str,
+    ) -> crate::Result<Option<String>> {
+        let state = self.state.read().unwrap();
+        let key = (provider_id.to_owned(), raw_id.to_owned());
+        Ok(state.identities.get(&key).cloned())
+    }
+
+    async fn custom_claims(
+        &self,
+        uid: &str,
+    ) -> crate::Result<Map<String, Value>> {
+        Ok(self.claims_of(uid))
+    }
+
+    async fn set_custom_claims(
+        &self,
+        uid: &str,
+        claims: Map<String, Value>,
+    ) -> crate::Result<()> {
+        let mut state = self.state.write().unwrap();
+        state.claims.insert(uid.to_owned(), claims);
+        Ok(())
+    }
+
+    /// Returns an opaque, unsigned placeholder cookie. It is not accepted by
+    /// [`SessionCookieVerifier`](super::SessionCookieVerifier).
+    async fn create_session_cookie(
+        &self,
+        id_token: &str,
+        valid_duration: Duration,
+    ) -> crate::Result<String> {
+        Ok(format!(
+            "fake-session.{}.{id_token}",
+            valid_duration.as_secs()
+        ))
+    }
+
+    /// Returns an opaque, unsigned placeholder token.
+    async fn create_custom_token(&self, uid: &str) -> crate::Result<String> {
+        Ok(format!("fake-custom-token.{uid}"))
+    }
+}
@@ -0,0 +1,60 @@
+mod identity_toolkit;
+mod in_memory;
+mod scope_claims;
+mod session_cookie;
+
+pub use identity_toolkit::*;
+pub use in_memory::*;
+pub use scope_claims::*;
+pub use session_cookie::*;
+
+use std::time::Duration;
+
+use serde_json::{Map, Value};
+
+/// Identity provider ID Firebase uses for Google sign-ins.
+pub const GOOGLE_PROVIDER_ID: &str = "google.com";
+
+/// Abstraction over the subset of the Firebase Admin (Identity Toolkit) API
+/// used by this crate.
+///
+/// The production implementation is [`IdentityToolkitAdmin`], which talks to
+/// Google's REST API. [`InMemoryFirebaseAdmin`] is a local fake intended for
+/// tests and local development.
+#[async_trait::async_trait]
+pub trait FirebaseAdmin: Send + Sync {
+    /// Resolves the Firebase UID of the user linked to the given federated
+    /// identity (e.g. `google.com` + Google user ID).
+    ///
+    /// Returns `None` if no Firebase user is linked to that identity.
+    async fn lookup_uid(
+        &self,
+        provider_id: &str,
+        raw_id: &str,
+    ) -> crate::Result<Option<String>>;
+
+    /// Returns the custom claims currently set on the given Firebase user.
+    async fn custom_claims(
+        &self,
+        uid: &str,
+    ) -> crate::Result<Map<String, Value>>;
+
+    /// Replaces all custom claims of the given Firebase user.
+    async fn set_custom_claims(
+        &self,
+        uid: &str,
+        claims: Map<String, Value>,
+    ) -> crate::Result<()>;
+
+    /// Exchanges a Firebase ID token for a session cookie valid for
+    /// `valid_duration` (between 5 minutes and 2 weeks).
+    async fn create_session_cookie(
+        &self,
+        id_token: &str,
+        valid_duration: Duration,
+    ) -> crate::Result<String>;
+
+    /// Creates a Firebase custom token for the given UID, which clients can
+    /// exchange via `signInWithCustomToken`.
+    async fn create_custom_token(&self, uid: &str) -> crate::Result<String>;
+}
@@ -0,0 +1,229 @@
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::sync::Arc;
+
+use oauth2::Scope;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::{FirebaseAdmin, GOOGLE_PROVIDER_ID};
+use crate::client::GoogleScope;
+
+/// Projection of granted Google scopes into a Firebase custom claim.
+///
+/// Each configured Google scope URL is mapped to a short claim name, e.g.
+/// `https://www.googleapis.com/auth/calendar.readonly` → `gcal:read`.
+/// Scopes without a mapping are not exposed. Scopes are matched in their
+/// canonical form, so an `email` key matches the stored
+/// `https://www.googleapis.com/auth/userinfo.email`.
+///
+/// ### Example
+/// ```json
+/// {
+///   "claim": "google_scopes",
+///   "scopes": {
+///     "https://www.googleapis.com/auth/calendar.readonly": "gcal:read"
+///   }
+/// }
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScopeClaimsMapping {
+    /// Name of the custom claim the projected scope names are written to.
+    #[serde(default = "ScopeClaimsMapping::default_claim")]
+    claim: String,
+
+    /// Google scope URLs mapped to their short claim names.
+    scopes: HashMap<String, String>,
+}
+
+impl ScopeClaimsMapping {
+    const DEFAULT_CLAIM: &'static str = "google_scopes";
+
+    /// Creates a new mapping written to the given custom claim.
+    pub fn new(
+        claim: impl Into<String>,
+        scopes: HashMap<String, String>,
+    ) -> Self {
+        Self {
+            claim: claim.into(),
+            scopes,
+        }
+    }
+
+    fn default_claim() -> String {
+        Self::DEFAULT_CLAIM.to_owned()
+    }
+
+    /// Returns the name of the custom claim.
+    pub fn claim(&self) -> &str {
+        &self.claim
+    }
+
+    /// Projects the given scopes into a sorted, de-duplicated list of
+    /// short claim names.
+    pub fn project(&self, scopes: &[Scope]) -> Vec<String> {
+        let scopes = scopes
+            .iter()
+            .map(|scope| GoogleScope::normalize(scope.as_str()))
+            .collect::<HashSet<_>>();
+        self.scopes
+            .iter()
+            .filter(|(scope, _)| {
+                scopes.contains(&GoogleScope::normalize(scope))
+            })
+            .map(|(_, claim)| claim.clone())
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect()
+    }
+}
+
+/// Keeps a Firebase custom claim in sync with the scopes stored for a
+/// [`GoogleUser`](crate::GoogleUser).
+///
+/// Only the configured claim is touched; all other custom claims of the
+/// Firebase user are preserved.
+#[derive(Clone)]
+pub struct ScopeClaimsSync {
+    mapping: Arc<ScopeClaimsMapping>,
+    admin: Arc<dyn FirebaseAdmin>,
+}
+
+impl ScopeClaimsSync {
+    /// Creates a new sync using the given mapping and admin API.
+    pub fn new(
+        mapping: ScopeClaimsMapping,
+        admin: Arc<dyn FirebaseAdmin>,
+    ) -> Self {
+        Self {
+            mapping: Arc::new(mapping),
+            admin,
+        }
+    }
+
+    /// Returns the configured scope mapping.
+    pub fn mapping(&self) -> &ScopeClaimsMapping {
+        &self.mapping
+    }
+
+    /// Writes the projection of `scopes` to the Firebase user linked to the
+    /// given Google user ID.
+    ///
+    /// The claim is removed entirely when no mapped scope remains. Google
+    /// users without a linked Firebase account are skipped.
+    pub async fn sync(
+        &self,
+        google_user_id: &str,
+        scopes: &[Scope],
+    ) -> crate::Result<()> {
+        let Some(uid) = self
+            .admin
+            .lookup_uid(GOOGLE_PROVIDER_ID, google_user_id)
+            .await?
+        else {
+            log::debug!(
+                "Skipping scope claims sync. No Firebase user linked to Google user"
+            );
+            return Ok(());
+        };
+
+        let projected = self.mapping.project(scopes);
+        let mut claims = self.admin.custom_claims(&uid).await?;
+
+        let current = claims.get(self.mapping.claim());
+        let next = (!projected.is_empty()).then(|| Value::from(projected));
+        if current == next.as_ref() {
+            return Ok(());
+        }
+
+        match next {
+            Some(value) => {
+                claims.insert(self.mapping.claim().to_owned(), value)
+            }
+            None => claims.remove(self.mapping.claim()),
+        };
+
+        self.admin.set_custom_claims(&uid, claims).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::admin::InMemoryFirebaseAdmin;
+    use serde_json::json;
+
+    const CALENDAR: &str = "https://www.googleapis.com/auth/calendar.readonly";
+    const DRIVE: &str = "https://www.googleapis.com/auth/drive.file";
+
+    fn mapping() -> ScopeClaimsMapping {
+        serde_json::from_value(json!({
+            "scopes": {
+                CALENDAR: "gcal:read",
+                DRIVE: "gdrive:file",
+            }
+        }))
+        .unwrap()
+    }
+
+    fn scopes(values: &[&str]) -> Vec<Scope> {
+        values.iter().map(|s| Scope::new((*s).to_owned())).collect()
+    }
+
+    #[test]
+    fn test_project_ignores_unmapped_scopes() {
+        let projected =
+            mapping().project(&scopes(&[DRIVE, "openid", CALENDAR, DRIVE]));
+        assert_eq!(projected, vec!["gcal:read", "gdrive:file"]);
+    }
+
+    #[test]
+    fn test_project_matches_normalized_scopes() {
+        let mapping: ScopeClaimsMapping = serde_json::from_value(json!({
+            "scopes": { "email": "email", CALENDAR: "gcal:read" }
+        }))
+        .unwrap();
+        let projected = mapping.project(&scopes(&[
+            "https://www.googleapis.com/auth/userinfo.email",
+            CALENDAR,
+        ]));
+        assert_eq!(projected, vec!["email", "gcal:read"]);
+        assert_eq!(mapping.project(&scopes(&["email"])), vec!["email"]);
+    }
+
+    #[tokio::test]
+    async fn test_sync_preserves_unrelated_claims() {
+        let admin = InMemoryFirebaseAdmin::new();
+        admin.link(GOOGLE_PROVIDER_ID, "google-123", "firebase-uid");
+        admin
+            .set_custom_claims(
+                "firebase-uid",
+                json!({ "admin": true }).as_object().cloned().unwrap(),
+            )
+            .await
+            .unwrap();
+
+        let sync = ScopeClaimsSync::new(mapping(), Arc::new(admin.clone()));
+        sync.sync("google-123", &scopes(&[CALENDAR])).await.unwrap();
+
+        assert_eq!(
+            Value::Object(admin.claims_of("firebase-uid")),
+            json!({ "admin": true, "google_scopes": ["gcal:read"] })
+        );
+
+        sync.sync("google-123", &[]).await.unwrap();
+        assert_eq!(
+            Value::Object(admin.claims_of("firebase-uid")),
+            json!({ "admin": true })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_sync_skips_unlinked_users() {
+        let admin = InMemoryFirebaseAdmin::new();
+        let sync = ScopeClaimsSync::new(mapping(), Arc::new(admin.clone()));
+        sync.sync("google-unknown", &scopes(&[CALENDAR]))
+            .await
+            .unwrap();
+        assert!(admin.claims_of("google-unknown").is_empty());
+    }
+}
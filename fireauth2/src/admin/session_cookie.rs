@@ -0,0 +1,54 @@
+use std::sync::Arc;
+
+use jsonwebtoken::{Algorithm, Validation};
+use serde::de::DeserializeOwned;
+
+use crate::keys::{KeySource, X509CertKeySource, verify_jwt};
+
+/// Verifies Firebase session cookies created via
+/// [`FirebaseAdmin::create_session_cookie`](super::FirebaseAdmin::create_session_cookie).
+///
+/// Session cookies are JWTs carrying the same claims as Firebase ID tokens,
+/// but are signed with a different key set and issued by
+/// `https://session.firebase.google.com/<project_id>`.
+#[derive(Clone)]
+pub struct SessionCookieVerifier {
+    keys: Arc<dyn KeySource>,
+    validation: Validation,
+}
+
+impl SessionCookieVerifier {
+    const PUBLIC_KEYS_URL: &'static str =
+        "https://www.googleapis.com/identitytoolkit/v3/relyingparty/publicKeys";
+    const ISSUER: &'static str = "https://session.firebase.google.com";
+
+    /// Creates a verifier for the given Firebase project using Google's
+    /// published session cookie keys.
+    pub fn new(project_id: impl AsRef<str>) -> crate::Result<Self> {
+        let keys = X509CertKeySource::new(Self::PUBLIC_KEYS_URL)?;
+        Ok(Self::with_key_source(project_id, Arc::new(keys)))
+    }
+
+    /// Creates a verifier for the given Firebase project using a custom
+    /// key source.
+    pub fn with_key_source(
+        project_id: impl AsRef<str>,
+        keys: Arc<dyn KeySource>,
+    ) -> Self {
+        let project_id = project_id.as_ref();
+        let mut validation = Validation::new(Algorithm::RS256);
+        validation.set_audience(&[project_id]);
+        validation.set_issuer(&[format!("{}/{project_id}", Self::ISSUER)]);
+        validation.set_required_spec_claims(&["exp", "iat", "sub"]);
+
+        Self { keys, validation }
+    }
+
+    /// Verifies the session cookie and returns its decoded claims.
+    pub async fn verify<T: DeserializeOwned>(
+        &self,
+        session_cookie: &str,
+    ) -> crate::Result<T> {
+        verify_jwt(session_cookie, self.keys.as_ref(), &self.validation).await
+    }
+}
@@ -0,0 +1,118 @@
+use std::sync::Arc;
+
+use jsonwebtoken::{Algorithm, Validation};
+use serde::{Deserialize, Serialize};
+
+use crate::keys::{JwksKeySource, KeySource, verify_jwt};
+
+/// Claims of a verified Firebase App Check token.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AppCheckClaims {
+    /// The Firebase App ID of the app the token was issued to.
+    sub: String,
+    /// Audiences, e.g. `projects/<project_number>` and `projects/<project_id>`.
+    aud: Vec<String>,
+    /// Issuer, `https://firebaseappcheck.googleapis.com/<project_number>`.
+    iss: String,
+    /// Expiration time (epoch seconds).
+    exp: u64,
+    /// Issued-at time (epoch seconds).
+    iat: u64,
+}
+
+impl AppCheckClaims {
+    /// Returns the Firebase App ID the token was issued to.
+    pub fn app_id(&self) -> &str {
+        &self.sub
+    }
+
+    /// Returns the expiration time of the token (epoch seconds).
+    pub fn expires_at(&self) -> u64 {
+        self.exp
+    }
+}
+
+/// Verifies Firebase App Check tokens sent in the `X-Firebase-AppCheck` header.
+///
+/// Tokens are RS256 JWTs whose audience must contain the project number.
+#[derive(Clone)]
+pub struct AppCheckVerifier {
+    keys: Arc<dyn KeySource>,
+    validation: Validation,
+}
+
+impl AppCheckVerifier {
+    /// Name of the request header carrying the App Check token.
+    pub const HEADER_NAME: &'static str = "X-Firebase-AppCheck";
+
+    const JWKS_URL: &'static str =
+        "https://firebaseappcheck.googleapis.com/v1/jwks";
+    const ISSUER: &'static str = "https://firebaseappcheck.googleapis.com";
+
+    /// Creates a verifier for the given Firebase project number using
+    /// App Check's published JWKS.
+    pub fn new(project_number: impl AsRef<str>) -> crate::Result<Self> {
+        let keys = JwksKeySource::new(Self::JWKS_URL)?;
+        Ok(Self::with_key_source(project_number, Arc::new(keys)))
+    }
+
+    /// Creates a verifier for the given Firebase project number using a
+    /// custom key source.
+    pub fn with_key_source(
+        project_number: impl AsRef<str>,
+        keys: Arc<dyn KeySource>,
+    ) -> Self {
+        let project_number = project_number.as_ref();
+        let mut validation = Validation::new(Algorithm::RS256);
+        validation.set_audience(&[format!("projects/{project_number}")]);
+        validation.set_issuer(&[format!("{}/{project_number}", Self::ISSUER)]);
+        validation.set_required_spec_claims(&["exp", "iss", "aud", "sub"]);
+
+        Self { keys, validation }
+    }
+
+    /// Verifies the App Check token and returns its claims.
+    pub async fn verify(&self, token: &str) -> crate::Result<AppCheckClaims> {
+        verify_jwt(token, self.keys.as_ref(), &self.validation).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::keys::test_keys;
+    use serde_json::json;
+
+    const PROJECT_NUMBER: &str = "123456789";
+
+    fn verifier() -> AppCheckVerifier {
+        AppCheckVerifier::with_key_source(
+            PROJECT_NUMBER,
+            Arc::new(test_keys::key_source()),
+        )
+    }
+
+    fn token(aud: &[&str]) -> String {
+        let now = test_keys::now();
+        test_keys::sign(&json!({
+            "sub": "1:123456789:web:abc",
+            "aud": aud,
+            "iss": format!("https://firebaseappcheck.googleapis.com/{PROJECT_NUMBER}"),
+            "iat": now,
+            "exp": now + 3600,
+        }))
+    }
+
+    #[tokio::test]
+    async fn test_verify_accepts_project_number_audience() {
+        let token = token(&["projects/123456789", "projects/my-project"]);
+        let claims = verifier().verify(&token).await.unwrap();
+        assert_eq!(claims.app_id(), "1:123456789:web:abc");
+    }
+
+    #[tokio::test]
+    async fn test_verify_rejects_foreign_audience() {
+        let token = token(&["projects/987654321"]);
+        assert!(verifier().verify(&token).await.is_err());
+    }
+}
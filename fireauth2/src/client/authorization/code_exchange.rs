@@ -0,0 +1,151 @@
+use super::consent::PartialConsentPolicy;
+use super::scope::{Scope, ScopeList, ScopeSet};
+use crate::client::google::GoogleOAuthTokenResponse;
+
+use oauth2::{AuthorizationCode, TokenResponse};
+use serde::{Deserialize, Serialize};
+
+/// JSON body carrying an authorization code obtained outside of the
+/// server-side redirect flow, e.g. by a Google Identity Services popup or
+/// a native Google Sign-In `serverAuthCode`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ExchangeCodePayload {
+    /// The authorization code to exchange for tokens.
+    pub code: String,
+
+    /// The scopes the code was requested for, if known, to detect scopes
+    /// the user did not grant.
+    #[serde(default)]
+    pub scope: Option<ScopeList>,
+
+    /// How scopes the user did not grant are handled.
+    #[serde(default)]
+    pub partial_consent: PartialConsentPolicy,
+
+    /// Scopes [`PartialConsentPolicy::Fail`] insists on. Defaults to all
+    /// scopes in `scope`.
+    #[serde(default)]
+    pub required_scopes: Option<ScopeList>,
+}
+
+/// Configuration for exchanging an authorization code obtained outside of
+/// the server-side redirect flow.
+///
+/// Used for Google Identity Services popup-mode codes, which are issued for
+/// the special `postmessage` redirect URI, and for `serverAuthCode`s of native
+/// Google Sign-In on Android or iOS, which are redeemed with an empty redirect
+/// URI. Unlike [`ExchangeAuthorizationCodeConfig`](super::ExchangeAuthorizationCodeConfig),
+/// no PKCE verifier or CSRF state is involved.
+#[derive(Debug)]
+pub struct ExchangeCodeConfig {
+    pub(crate) code: AuthorizationCode,
+    pub(crate) google_user_id: Option<String>,
+    pub(crate) revoke_existing_tokens: bool,
+    pub(crate) requested_scopes: Vec<Scope>,
+    pub(crate) required_scopes: Vec<Scope>,
+    pub(crate) partial_consent: PartialConsentPolicy,
+}
+
+impl ExchangeCodeConfig {
+    /// Creates a new configuration for the given authorization code.
+    pub fn new(code: impl Into<String>) -> Self {
+        Self {
+            code: AuthorizationCode::new(code.into()),
+            google_user_id: None,
+            revoke_existing_tokens: false,
+            requested_scopes: Vec::new(),
+            required_scopes: Vec::new(),
+            partial_consent: PartialConsentPolicy::default(),
+        }
+    }
+
+    /// Requires the exchanged ID token to belong to the given Google user.
+    #[must_use]
+    pub fn google_user_id(mut self, google_user_id: impl Into<String>) -> Self {
+        self.google_user_id = Some(google_user_id.into());
+        self
+    }
+
+    /// Sets whether to revoke existing tokens upon exchanging the code.
+    #[must_use]
+    pub fn revoke_existing_tokens(mut self, yes: bool) -> Self {
+        self.revoke_existing_tokens = yes;
+        self
+    }
+
+    /// Sets the scopes the code was requested for, used to detect scopes the
+    /// user did not grant.
+    #[must_use]
+    pub fn requested_scopes(
+        mut self,
+        scopes: impl IntoIterator<Item = Scope>,
+    ) -> Self {
+        self.requested_scopes = scopes.into_iter().collect();
+        self
+    }
+
+    /// Sets the scopes [`PartialConsentPolicy::Fail`] insists on. Defaults to
+    /// all requested scopes.
+    #[must_use]
+    pub fn required_scopes(
+        mut self,
+        scopes: impl IntoIterator<Item = Scope>,
+    ) -> Self {
+        self.required_scopes = scopes.into_iter().collect();
+        self
+    }
+
+    /// Sets how scopes the user did not grant are handled.
+    #[must_use]
+    pub fn partial_consent(mut self, policy: PartialConsentPolicy) -> Self {
+        self.partial_consent = policy;
+        self
+    }
+}
+
+/// Response returned when exchanging an authorization code outside of the
+/// server-side redirect flow.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExchangeCodeResponse {
+    /// The access token string.
+    pub(crate) access_token: String,
+    /// The `OpenID` Connect ID token string, if the `openid` scope was granted.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) id_token: Option<String>,
+    /// The scopes granted by the user.
+    pub(crate) scope: Vec<String>,
+    /// The UNIX timestamp when the token was issued.
+    pub(crate) issued_at: i64,
+    /// Token lifetime in seconds.
+    pub(crate) expires_in: u64,
+    /// Requested scopes the user did not grant.
+    #[serde(skip_serializing_if = "ScopeSet::is_empty")]
+    pub(crate) missing_scopes: ScopeSet,
+}
+
+impl ExchangeCodeResponse {
+    /// Sets the requested scopes the user did not grant.
+    #[must_use]
+    pub fn with_missing_scopes(mut self, scopes: ScopeSet) -> Self {
+        self.missing_scopes = scopes;
+        self
+    }
+}
+
+impl From<&GoogleOAuthTokenResponse> for ExchangeCodeResponse {
+    fn from(value: &GoogleOAuthTokenResponse) -> Self {
+        let scope = value
+            .scopes()
+            .map(|scopes| scopes.iter().map(|s| s.to_string()).collect())
+            .unwrap_or_default();
+        Self {
+            access_token: value.access_token().secret().to_owned(),
+            id_token: value.extra_fields().id_token().map(str::to_owned),
+            scope,
+            issued_at: chrono::Utc::now().timestamp(),
+            expires_in: value.expires_in().map_or(0, |d| d.as_secs()),
+            missing_scopes: ScopeSet::default(),
+        }
+    }
+}
@@ -0,0 +1,136 @@
+use serde::{Deserialize, Serialize};
+
+use super::scope::{Scope, ScopeSet};
+
+/// Determines how authorizations are handled in which the user did not
+/// grant all requested scopes, e.g. by unchecking them on Google's granular
+/// consent screen.
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum PartialConsentPolicy {
+    /// Succeeds without reporting the missing scopes.
+    Accept,
+    /// Succeeds and reports the missing scopes (default).
+    #[default]
+    Report,
+    /// Fails with [`crate::Error::ScopesNotGranted`] if a required scope is
+    /// missing, revoking the grant instead of persisting it. Without
+    /// explicitly required scopes, every requested scope is required.
+    /// Missing optional scopes are reported.
+    Fail,
+}
+
+impl PartialConsentPolicy {
+    /// Returns the requested scopes missing from the granted ones, as far as
+    /// they are reported by the policy.
+    ///
+    /// `required` restricts the scopes [`PartialConsentPolicy::Fail`] insists
+    /// on; if empty, all requested scopes are required. Google omits the
+    /// granted scopes if they equal the requested ones, so `None` means
+    /// nothing is missing.
+    pub(crate) fn missing_scopes(
+        self,
+        requested: &[Scope],
+        required: &[Scope],
+        granted: Option<&[Scope]>,
+    ) -> crate::Result<ScopeSet> {
+        let Some(granted) = granted else {
+            return Ok(ScopeSet::default());
+        };
+        let missing =
+            ScopeSet::from(requested).difference(&ScopeSet::from(granted));
+        if missing.is_empty() {
+            return Ok(missing);
+        }
+
+        log::debug!("Requested scopes were not granted: {missing}");
+        match self {
+            Self::Accept => Ok(ScopeSet::default()),
+            Self::Report => Ok(missing),
+            Self::Fail => {
+                let missing_required = if required.is_empty() {
+                    missing.clone()
+                } else {
+                    missing.intersection(&ScopeSet::from(required))
+                };
+                if missing_required.is_empty() {
+                    Ok(missing)
+                } else {
+                    Err(crate::Error::ScopesNotGranted(missing_required))
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CALENDAR: &str = "https://www.googleapis.com/auth/calendar.readonly";
+
+    fn scopes(scopes: &[&str]) -> Vec<Scope> {
+        scopes.iter().map(|s| Scope::new((*s).to_owned())).collect()
+    }
+
+    #[test]
+    fn test_missing_scopes() {
+        let requested = scopes(&["openid", "email", CALENDAR]);
+        let granted = scopes(&[
+            "openid",
+            "https://www.googleapis.com/auth/userinfo.email",
+        ]);
+
+        let missing = PartialConsentPolicy::Report
+            .missing_scopes(&requested, &[], Some(&granted))
+            .unwrap();
+        assert_eq!(missing.iter().collect::<Vec<_>>(), [CALENDAR]);
+
+        let accepted = PartialConsentPolicy::Accept
+            .missing_scopes(&requested, &[], Some(&granted))
+            .unwrap();
+        assert!(accepted.is_empty());
+
+        assert!(matches!(
+            PartialConsentPolicy::Fail.missing_scopes(
+                &requested,
+                &[],
+                Some(&granted)
+            ),
+            Err(crate::Error::ScopesNotGranted(_))
+        ));
+        assert!(
+            PartialConsentPolicy::Fail
+                .missing_scopes(&requested, &[], None)
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_fail_only_requires_required_scopes() {
+        let requested = scopes(&["openid", "email", CALENDAR]);
+        let granted = scopes(&["openid", CALENDAR]);
+
+        let optional = PartialConsentPolicy::Fail
+            .missing_scopes(&requested, &scopes(&[CALENDAR]), Some(&granted))
+            .unwrap();
+        assert_eq!(
+            optional.iter().collect::<Vec<_>>(),
+            ["https://www.googleapis.com/auth/userinfo.email"]
+        );
+
+        let required = PartialConsentPolicy::Fail.missing_scopes(
+            &requested,
+            &scopes(&[CALENDAR, "email"]),
+            Some(&granted),
+        );
+        assert!(matches!(
+            required,
+            Err(crate::Error::ScopesNotGranted(missing))
+                if missing.contains("email")
+        ));
+    }
+}
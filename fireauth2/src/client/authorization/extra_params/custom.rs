@@ -0,0 +1,218 @@
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap};
+
+use regex::Regex;
+use serde::{Deserialize, Serialize, de};
+
+use super::extra_param::{ExtraParam, ToExtraParams};
+
+/// Parameters that are set by the `OAuth2` client itself, modeled by
+/// [`crate::RequestAccessTokenExtraParams`] or that would move the response
+/// out of the query read by the callback (`response_mode`), and can never be
+/// overridden by custom parameters.
+const RESERVED_PARAMS: &[&str] = &[
+    "access_type",
+    "client_id",
+    "client_secret",
+    "code",
+    "code_challenge",
+    "code_challenge_method",
+    "code_verifier",
+    "display",
+    "enable_granular_consent",
+    "grant_type",
+    "hd",
+    "hl",
+    "include_granted_scopes",
+    "login_hint",
+    "max_age",
+    "nonce",
+    "partial_consent",
+    "profile",
+    "prompt",
+    "redirect_uri",
+    "required_scopes",
+    "response_mode",
+    "response_type",
+    "scope",
+    "state",
+];
+
+/// Authorization parameters without a typed representation, such as
+/// experimental or partner-specific flags, forwarded to Google as-is.
+///
+/// Only parameters permitted by a [`CustomParamsAllowlist`] are accepted
+/// by [`crate::FireAuthClient::request_access_token`]. Parameters that
+/// would override the client ID, redirect URI, state, PKCE challenge or any
+/// typed parameter such as `nonce` or `prompt` are rejected outright.
+#[derive(Debug, Clone, Default, Serialize, PartialEq, Eq)]
+#[serde(transparent)]
+pub struct CustomParams(BTreeMap<String, String>);
+
+impl CustomParams {
+    /// Creates an empty set of custom parameters.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a parameter, failing if its name is reserved.
+    pub fn insert(
+        &mut self,
+        name: impl Into<String>,
+        value: impl Into<String>,
+    ) -> crate::Result<()> {
+        let name = name.into();
+        if RESERVED_PARAMS.contains(&name.as_str()) {
+            return Err(crate::Error::DisallowedExtraParam(name));
+        }
+        self.0.insert(name, value.into());
+        Ok(())
+    }
+
+    /// Returns `true` if no custom parameters are set.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Iterates over the parameter names and values.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.0
+            .iter()
+            .map(|(name, value)| (name.as_str(), value.as_str()))
+    }
+}
+
+impl<'de> Deserialize<'de> for CustomParams {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        let raw = BTreeMap::<String, String>::deserialize(deserializer)?;
+        let mut params = CustomParams::new();
+        for (name, value) in raw {
+            params.insert(name, value).map_err(de::Error::custom)?;
+        }
+        Ok(params)
+    }
+}
+
+impl<'a> ToExtraParams<'a> for CustomParams {
+    fn to_extra_params(&self) -> Vec<(ExtraParam, Cow<'a, str>)> {
+        self.0
+            .iter()
+            .map(|(name, value)| {
+                (ExtraParam::custom(name.clone()), Cow::Owned(value.clone()))
+            })
+            .collect()
+    }
+}
+
+/// Names and value patterns of the [`CustomParams`] a client may forward.
+///
+/// Patterns are regular expressions that must match the entire value.
+/// The default allowlist is empty, rejecting all custom parameters.
+///
+/// ### Example
+/// ```json
+/// { "ack_oob_shutdown": "\\d{4}-\\d{2}-\\d{2}", "partner_id": "[a-z0-9-]{1,32}" }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct CustomParamsAllowlist(HashMap<String, Regex>);
+
+impl CustomParamsAllowlist {
+    /// Creates an allowlist from parameter names and value patterns.
+    pub fn new(
+        rules: impl IntoIterator<Item = (impl Into<String>, impl AsRef<str>)>,
+    ) -> crate::Result<Self> {
+        let mut allowlist = HashMap::new();
+        for (name, pattern) in rules {
+            let name = name.into();
+            if RESERVED_PARAMS.contains(&name.as_str()) {
+                return Err(crate::Error::DisallowedExtraParam(name));
+            }
+            let pattern = Regex::new(&format!("^(?:{})$", pattern.as_ref()))?;
+            allowlist.insert(name, pattern);
+        }
+        Ok(Self(allowlist))
+    }
+
+    /// Ensures every parameter is allowlisted and matches its pattern.
+    pub fn check(&self, params: &CustomParams) -> crate::Result<()> {
+        for (name, value) in params.iter() {
+            match self.0.get(name) {
+                Some(pattern) if pattern.is_match(value) => {}
+                Some(_) => {
+                    return Err(crate::Error::InvalidExtraParamValue {
+                        name: name.to_owned(),
+                        value: value.to_owned(),
+                    });
+                }
+                None => {
+                    return Err(crate::Error::DisallowedExtraParam(
+                        name.to_owned(),
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<'de> Deserialize<'de> for CustomParamsAllowlist {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        let rules = HashMap::<String, String>::deserialize(deserializer)?;
+        CustomParamsAllowlist::new(rules).map_err(de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn params(name: &str, value: &str) -> CustomParams {
+        let mut params = CustomParams::new();
+        params.insert(name, value).unwrap();
+        params
+    }
+
+    #[test]
+    fn test_allowlist_checks_names_and_full_value_match() {
+        let allowlist: CustomParamsAllowlist = serde_json::from_value(
+            json!({ "ack_oob_shutdown": "\\d{4}-\\d{2}-\\d{2}" }),
+        )
+        .unwrap();
+
+        assert!(
+            allowlist
+                .check(&params("ack_oob_shutdown", "2022-10-03"))
+                .is_ok()
+        );
+        assert!(
+            allowlist
+                .check(&params("ack_oob_shutdown", "2022-10-03&x=1"))
+                .is_err()
+        );
+        assert!(allowlist.check(&params("partner_id", "acme")).is_err());
+        assert!(allowlist.check(&CustomParams::new()).is_ok());
+    }
+
+    #[test]
+    fn test_reserved_params_are_rejected() {
+        assert!(CustomParams::new().insert("client_id", "other").is_err());
+        assert!(
+            serde_json::from_value::<CustomParams>(json!({ "state": "x" }))
+                .is_err()
+        );
+        assert!(
+            CustomParamsAllowlist::new([("code_challenge", ".*")]).is_err()
+        );
+        for name in ["nonce", "response_mode", "max_age", "login_hint", "hd"] {
+            assert!(CustomParams::new().insert(name, "x").is_err());
+            assert!(CustomParamsAllowlist::new([(name, ".*")]).is_err());
+        }
+    }
+}
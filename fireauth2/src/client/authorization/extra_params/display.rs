@@ -0,0 +1,37 @@
+use std::{borrow::Cow, fmt};
+
+use serde::{Deserialize, Serialize};
+
+use super::extra_param::{ExtraParam, IntoExtraParam};
+
+/// Specifies how the authorization server displays the sign-in and
+/// consent screens.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum DisplayMode {
+    /// A full user agent page.
+    Page,
+    /// A popup window.
+    Popup,
+    /// A touch interface.
+    Touch,
+    /// A feature phone.
+    Wap,
+}
+
+impl fmt::Display for DisplayMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Page => write!(f, "page"),
+            Self::Popup => write!(f, "popup"),
+            Self::Touch => write!(f, "touch"),
+            Self::Wap => write!(f, "wap"),
+        }
+    }
+}
+
+impl<'a> IntoExtraParam<'a> for DisplayMode {
+    fn into_extra_param(self) -> (ExtraParam, Cow<'a, str>) {
+        (ExtraParam::DISPLAY, Cow::Owned(self.to_string()))
+    }
+}
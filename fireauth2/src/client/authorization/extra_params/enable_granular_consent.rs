@@ -0,0 +1,46 @@
+use std::borrow::Cow;
+use std::ops::Deref;
+
+use serde::de::Deserializer;
+use serde::{Deserialize, Serialize};
+
+use super::extra_param::{ExtraParam, IntoExtraParam, deserialize_bool};
+
+/// A newtype struct for the `enable_granular_consent` extra param.
+///
+/// When `false`, older clients opt out of granular consent, so users
+/// cannot grant only a subset of the requested scopes.
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+pub struct EnableGranularConsent(bool);
+
+impl EnableGranularConsent {
+    /// Creates the parameter with the given value.
+    pub fn new(enabled: bool) -> Self {
+        EnableGranularConsent(enabled)
+    }
+}
+
+impl Deref for EnableGranularConsent {
+    type Target = bool;
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<'de> Deserialize<'de> for EnableGranularConsent {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserialize_bool(deserializer).map(EnableGranularConsent)
+    }
+}
+
+impl<'a> IntoExtraParam<'a> for EnableGranularConsent {
+    fn into_extra_param(self) -> (ExtraParam, Cow<'a, str>) {
+        (
+            ExtraParam::ENABLE_GRANULAR_CONSENT,
+            Cow::Owned(self.0.to_string()),
+        )
+    }
+}
@@ -1,39 +1,73 @@
+use serde::de::{self, Deserializer, Visitor};
 use serde::{Deserialize, Serialize};
-use std::{borrow::Cow, ops::Deref};
+use std::{borrow::Cow, fmt, ops::Deref};
 
 /// Wrapper type for extra `OAuth2` authorization request parameters.
 ///
 /// Provides a strongly typed representation of common extra parameters
 /// as well as utilities for conversion to query parameter formats.
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
-pub struct ExtraParam(pub(super) &'static str);
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExtraParam(pub(super) Cow<'static, str>);
 
 impl Deref for ExtraParam {
     type Target = str;
 
     /// Dereferences to the underlying string slice.
     fn deref(&self) -> &Self::Target {
-        self.0
+        &self.0
     }
 }
 
 impl ExtraParam {
     /// Include scopes previously granted by the user.
     pub const INCLUDE_GRANTED_SCOPES: ExtraParam =
-        ExtraParam("include_granted_scopes");
+        ExtraParam(Cow::Borrowed("include_granted_scopes"));
 
     /// Hint to the authorization server about the user to authenticate.
-    pub const LOGIN_HINT: ExtraParam = ExtraParam("login_hint");
+    pub const LOGIN_HINT: ExtraParam = ExtraParam(Cow::Borrowed("login_hint"));
 
     /// Type of access requested (e.g., "offline" or "online").
-    pub const ACCESS_TYPE: ExtraParam = ExtraParam("access_type");
+    pub const ACCESS_TYPE: ExtraParam =
+        ExtraParam(Cow::Borrowed("access_type"));
 
     /// Specifies whether the user should be prompted for consent.
-    pub const PROMPT: ExtraParam = ExtraParam("prompt");
+    pub const PROMPT: ExtraParam = ExtraParam(Cow::Borrowed("prompt"));
 
-    /// Converts the parameter name into a borrowed `Cow<str>`.
+    /// Restricts sign-in to accounts of a Google Workspace domain.
+    pub const HD: ExtraParam = ExtraParam(Cow::Borrowed("hd"));
+
+    /// Value echoed in the ID token to mitigate replay attacks.
+    pub const NONCE: ExtraParam = ExtraParam(Cow::Borrowed("nonce"));
+
+    /// Enables or disables granular (per-scope) consent.
+    pub const ENABLE_GRANULAR_CONSENT: ExtraParam =
+        ExtraParam(Cow::Borrowed("enable_granular_consent"));
+
+    /// Language of the consent screen, as BCP 47 language tag.
+    pub const HL: ExtraParam = ExtraParam(Cow::Borrowed("hl"));
+
+    /// How the consent screen is displayed.
+    pub const DISPLAY: ExtraParam = ExtraParam(Cow::Borrowed("display"));
+
+    /// Which credentials the authorization endpoint returns.
+    pub const RESPONSE_TYPE: ExtraParam =
+        ExtraParam(Cow::Borrowed("response_type"));
+
+    /// Maximum time in seconds since the user last authenticated.
+    pub const MAX_AGE: ExtraParam = ExtraParam(Cow::Borrowed("max_age"));
+
+    /// Creates a parameter that has no typed representation, see
+    /// [`CustomParams`](super::CustomParams).
+    pub(super) fn custom(name: String) -> ExtraParam {
+        ExtraParam(Cow::Owned(name))
+    }
+
+    /// Converts the parameter name into a `Cow<str>`.
     pub fn into_cow<'a>(&self) -> Cow<'a, str> {
-        Cow::Borrowed(self.0)
+        match &self.0 {
+            Cow::Borrowed(name) => Cow::Borrowed(name),
+            Cow::Owned(name) => Cow::Owned(name.clone()),
+        }
     }
 }
 
@@ -49,3 +83,39 @@ pub trait ToExtraParams<'a> {
     /// suitable for use as authorization query parameters.
     fn to_extra_params(&self) -> Vec<(ExtraParam, Cow<'a, str>)>;
 }
+
+/// Deserializes a boolean from `true`/`false` or their string forms, as
+/// sent in query strings.
+pub(super) fn deserialize_bool<'de, D>(
+    deserializer: D,
+) -> Result<bool, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    struct StringOrBoolVisitor;
+
+    impl Visitor<'_> for StringOrBoolVisitor {
+        type Value = bool;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str(r#""true", "false", true, or false"#)
+        }
+
+        fn visit_bool<E>(self, v: bool) -> Result<Self::Value, E> {
+            Ok(v)
+        }
+
+        fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+        where
+            E: de::Error,
+        {
+            match v {
+                "true" => Ok(true),
+                "false" => Ok(false),
+                _ => Err(de::Error::unknown_variant(v, &["true", "false"])),
+            }
+        }
+    }
+
+    deserializer.deserialize_any(StringOrBoolVisitor)
+}
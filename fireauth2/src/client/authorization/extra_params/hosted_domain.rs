@@ -0,0 +1,35 @@
+use std::borrow::Cow;
+use std::ops::Deref;
+
+use serde::{Deserialize, Serialize};
+
+use super::extra_param::{ExtraParam, IntoExtraParam};
+
+/// A newtype struct for the `hd` extra param.
+///
+/// Streamlines the sign-in flow for accounts of the given Google Workspace
+/// domain. This is only a UI hint; ID tokens must still be checked for the
+/// `hd` claim.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(transparent)]
+pub struct HostedDomain(String);
+
+impl HostedDomain {
+    /// Creates a hint for the given domain, or `*` for any Workspace domain.
+    pub fn new(domain: impl Into<String>) -> Self {
+        HostedDomain(domain.into())
+    }
+}
+
+impl Deref for HostedDomain {
+    type Target = str;
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<'a> IntoExtraParam<'a> for HostedDomain {
+    fn into_extra_param(self) -> (ExtraParam, Cow<'a, str>) {
+        (ExtraParam::HD, Cow::Owned(self.0))
+    }
+}
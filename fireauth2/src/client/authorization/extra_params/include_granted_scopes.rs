@@ -1,10 +1,10 @@
 use std::borrow::Cow;
 use std::ops::Deref;
 
-use serde::de::{Deserializer, Visitor};
+use serde::de::Deserializer;
 use serde::{Deserialize, Serialize};
 
-use super::extra_param::{ExtraParam, IntoExtraParam};
+use super::extra_param::{ExtraParam, IntoExtraParam, deserialize_bool};
 
 /// A newtype struct for the `include_granted_scope` extra param.
 #[derive(Debug, Clone, Serialize, PartialEq, Eq)]
@@ -28,38 +28,7 @@ impl<'de> Deserialize<'de> for IncludeGrantedScopes {
     where
         D: Deserializer<'de>,
     {
-        struct StringOrBoolVisitor;
-
-        impl Visitor<'_> for StringOrBoolVisitor {
-            type Value = IncludeGrantedScopes;
-
-            fn expecting(
-                &self,
-                formatter: &mut std::fmt::Formatter,
-            ) -> std::fmt::Result {
-                formatter.write_str(r#""true", "false", true, or false"#)
-            }
-
-            fn visit_bool<E>(self, v: bool) -> Result<Self::Value, E> {
-                Ok(IncludeGrantedScopes(v))
-            }
-
-            fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
-            where
-                E: serde::de::Error,
-            {
-                match v {
-                    "true" => Ok(IncludeGrantedScopes(true)),
-                    "false" => Ok(IncludeGrantedScopes(false)),
-                    _ => Err(serde::de::Error::unknown_variant(
-                        v,
-                        &["true", "false"],
-                    )),
-                }
-            }
-        }
-
-        deserializer.deserialize_any(StringOrBoolVisitor)
+        deserialize_bool(deserializer).map(IncludeGrantedScopes)
     }
 }
 
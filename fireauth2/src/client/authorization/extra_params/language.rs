@@ -0,0 +1,34 @@
+use std::borrow::Cow;
+use std::ops::Deref;
+
+use serde::{Deserialize, Serialize};
+
+use super::extra_param::{ExtraParam, IntoExtraParam};
+
+/// A newtype struct for the `hl` extra param.
+///
+/// A BCP 47 language tag (e.g. `en` or `pt-BR`) for the sign-in and
+/// consent screens.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(transparent)]
+pub struct Language(String);
+
+impl Language {
+    /// Creates the parameter from a BCP 47 language tag.
+    pub fn new(tag: impl Into<String>) -> Self {
+        Language(tag.into())
+    }
+}
+
+impl Deref for Language {
+    type Target = str;
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<'a> IntoExtraParam<'a> for Language {
+    fn into_extra_param(self) -> (ExtraParam, Cow<'a, str>) {
+        (ExtraParam::HL, Cow::Owned(self.0))
+    }
+}
@@ -0,0 +1,83 @@
+use std::borrow::Cow;
+use std::fmt;
+use std::ops::Deref;
+use std::time::Duration;
+
+use serde::de::{self, Deserializer, Visitor};
+use serde::{Deserialize, Serialize};
+
+use super::extra_param::{ExtraParam, IntoExtraParam};
+
+/// A newtype struct for the `max_age` extra param.
+///
+/// The maximum time in seconds since the user last actively authenticated
+/// with Google. If exceeded, the user is asked to sign in again.
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+pub struct MaxAge(u64);
+
+impl MaxAge {
+    /// Creates the parameter from a number of seconds.
+    pub fn new(seconds: u64) -> Self {
+        MaxAge(seconds)
+    }
+}
+
+impl From<Duration> for MaxAge {
+    fn from(duration: Duration) -> Self {
+        MaxAge(duration.as_secs())
+    }
+}
+
+impl Deref for MaxAge {
+    type Target = u64;
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<'de> Deserialize<'de> for MaxAge {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct StringOrNumberVisitor;
+
+        impl Visitor<'_> for StringOrNumberVisitor {
+            type Value = MaxAge;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a non-negative number of seconds")
+            }
+
+            fn visit_u64<E>(self, v: u64) -> Result<Self::Value, E> {
+                Ok(MaxAge(v))
+            }
+
+            fn visit_i64<E>(self, v: i64) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                u64::try_from(v).map(MaxAge).map_err(|_| {
+                    E::invalid_value(de::Unexpected::Signed(v), &self)
+                })
+            }
+
+            fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                v.trim().parse().map(MaxAge).map_err(|_| {
+                    E::invalid_value(de::Unexpected::Str(v), &self)
+                })
+            }
+        }
+
+        deserializer.deserialize_any(StringOrNumberVisitor)
+    }
+}
+
+impl<'a> IntoExtraParam<'a> for MaxAge {
+    fn into_extra_param(self) -> (ExtraParam, Cow<'a, str>) {
+        (ExtraParam::MAX_AGE, Cow::Owned(self.0.to_string()))
+    }
+}
@@ -1,9 +1,25 @@
 mod access_type;
+mod custom;
+mod display;
+mod enable_granular_consent;
 mod extra_param;
+mod hosted_domain;
 mod include_granted_scopes;
+mod language;
+mod max_age;
+mod nonce;
 mod prompt;
+mod response_type;
 
 pub use access_type::*;
+pub use custom::*;
+pub use display::*;
+pub use enable_granular_consent::*;
 pub use extra_param::*;
+pub use hosted_domain::*;
 pub use include_granted_scopes::*;
+pub use language::*;
+pub use max_age::*;
+pub use nonce::*;
 pub use prompt::*;
+pub use response_type::*;
@@ -0,0 +1,40 @@
+use std::borrow::Cow;
+use std::ops::Deref;
+
+use oauth2::CsrfToken;
+use serde::{Deserialize, Serialize};
+
+use super::extra_param::{ExtraParam, IntoExtraParam};
+
+/// A newtype struct for the `nonce` extra param.
+///
+/// Google echoes the value in the ID token's `nonce` claim, binding the
+/// token to the authorization request.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(transparent)]
+pub struct Nonce(String);
+
+impl Nonce {
+    /// Creates a nonce from the given value.
+    pub fn new(value: impl Into<String>) -> Self {
+        Nonce(value.into())
+    }
+
+    /// Creates a random, URL-safe nonce.
+    pub fn new_random() -> Self {
+        Nonce(CsrfToken::new_random().into_secret())
+    }
+}
+
+impl Deref for Nonce {
+    type Target = str;
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<'a> IntoExtraParam<'a> for Nonce {
+    fn into_extra_param(self) -> (ExtraParam, Cow<'a, str>) {
+        (ExtraParam::NONCE, Cow::Owned(self.0))
+    }
+}
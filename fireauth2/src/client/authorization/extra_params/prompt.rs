@@ -6,7 +6,7 @@ use super::extra_param::{ExtraParam, IntoExtraParam};
 
 /// A space-delimited list of string values that specifies whether the
 /// authorization server prompts the user for reauthentication and consent.
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
 #[serde(rename_all = "snake_case")]
 pub enum Prompt {
     /// The authorization server does not display any authentication or user consent screens;
@@ -65,15 +65,13 @@ impl<'a> IntoExtraParam<'a> for PromptList {
 }
 
 /// A newtype struct for the `prompt` extra param that wraps a list of [Prompts][Prompt].
-#[derive(Debug, Clone)]
+///
+/// Empty by default, in which case `prompt` is omitted and Google only
+/// prompts when necessary. `prompt=none` is never implied since it fails
+/// unless the user is signed in and has already consented.
+#[derive(Debug, Clone, Default)]
 pub struct PromptList(pub Vec<Prompt>);
 
-impl Default for PromptList {
-    fn default() -> Self {
-        PromptList(vec![Prompt::None])
-    }
-}
-
 impl<'de> Deserialize<'de> for PromptList {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
@@ -0,0 +1,91 @@
+use std::{borrow::Cow, fmt, str::FromStr};
+
+use serde::{Deserialize, Serialize, de};
+
+use super::extra_param::{ExtraParam, IntoExtraParam};
+
+/// Specifies which credentials the authorization endpoint returns.
+///
+/// All variants include an authorization code, which is what the server-side
+/// flow exchanges. The hybrid variants additionally return an ID token
+/// and/or access token, and with them all parameters including the code, in
+/// the redirect URI's fragment, which never reaches the server. They are
+/// therefore only usable if the client reads the fragment itself.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum AuthorizationResponseType {
+    /// `code` (default)
+    #[default]
+    Code,
+    /// `code id_token`
+    CodeIdToken,
+    /// `code token`
+    CodeToken,
+    /// `code id_token token`
+    CodeIdTokenToken,
+}
+
+impl AuthorizationResponseType {
+    /// Whether credentials besides the code are returned, which moves the
+    /// response into the URL fragment.
+    pub fn is_hybrid(self) -> bool {
+        self != Self::Code
+    }
+}
+
+impl FromStr for AuthorizationResponseType {
+    type Err = crate::Error;
+
+    /// Parses a space-separated list of response types in any order.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.split_whitespace().collect::<Vec<_>>();
+        parts.sort_unstable();
+        parts.dedup();
+
+        match parts.as_slice() {
+            ["code"] => Ok(Self::Code),
+            ["code", "id_token"] => Ok(Self::CodeIdToken),
+            ["code", "token"] => Ok(Self::CodeToken),
+            ["code", "id_token", "token"] => Ok(Self::CodeIdTokenToken),
+            _ => Err(crate::Error::InvalidExtraParamValue {
+                name: "response_type".into(),
+                value: s.to_owned(),
+            }),
+        }
+    }
+}
+
+impl fmt::Display for AuthorizationResponseType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Code => write!(f, "code"),
+            Self::CodeIdToken => write!(f, "code id_token"),
+            Self::CodeToken => write!(f, "code token"),
+            Self::CodeIdTokenToken => write!(f, "code id_token token"),
+        }
+    }
+}
+
+impl Serialize for AuthorizationResponseType {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for AuthorizationResponseType {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        let value = String::deserialize(deserializer)?;
+        value.parse().map_err(de::Error::custom)
+    }
+}
+
+impl<'a> IntoExtraParam<'a> for AuthorizationResponseType {
+    fn into_extra_param(self) -> (ExtraParam, Cow<'a, str>) {
+        (ExtraParam::RESPONSE_TYPE, Cow::Owned(self.to_string()))
+    }
+}
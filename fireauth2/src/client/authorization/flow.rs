@@ -1,20 +1,27 @@
+use super::consent::PartialConsentPolicy;
 use super::extra_params::{
-    AccessType, ExtraParam, IncludeGrantedScopes, IntoExtraParam, PromptList,
-    ToExtraParams,
+    AccessType, AuthorizationResponseType, CustomParams, DisplayMode,
+    EnableGranularConsent, ExtraParam, HostedDomain, IncludeGrantedScopes,
+    IntoExtraParam, Language, MaxAge, Nonce, PromptList, ToExtraParams,
 };
-use super::scope::{Scope, ScopeList};
-use crate::client::google::GoogleOAuthTokenResponse;
+use super::scope::{Scope, ScopeList, ScopeSet};
+use crate::client::google::{GoogleOAuthClient, GoogleOAuthTokenResponse};
 
 use std::borrow::Cow;
 use std::fmt;
+use std::str::FromStr;
+use std::time::Duration;
 
-use oauth2::{AuthorizationCode, CsrfToken, PkceCodeVerifier, TokenResponse};
+use oauth2::{
+    AuthorizationCode, CsrfToken, PkceCodeChallenge, PkceCodeVerifier,
+    ResponseType, TokenResponse,
+};
 use serde::{Deserialize, Serialize};
 use url::Url;
 
 /// Represents optional parameters sent during the `OAuth2` authorization request.
 /// These affect the server behavior for consent, prompt, and token refreshability.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct RequestAccessTokenExtraParams {
     /// If this parameter is provided with the value true, and the authorization request
     /// is granted, the authorization will include any previous authorizations granted
@@ -39,6 +46,70 @@ pub struct RequestAccessTokenExtraParams {
 
     #[serde(default)]
     pub(crate) prompt: PromptList,
+
+    /// Streamlines sign-in for accounts of the given Google Workspace
+    /// domain (`hd`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) hd: Option<HostedDomain>,
+
+    /// Value Google echoes in the ID token's `nonce` claim.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) nonce: Option<Nonce>,
+
+    /// Opts in or out of granular (per-scope) consent.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) enable_granular_consent: Option<EnableGranularConsent>,
+
+    /// Language of the sign-in and consent screens (`hl`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) hl: Option<Language>,
+
+    /// How the sign-in and consent screens are displayed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) display: Option<DisplayMode>,
+
+    /// Which credentials the authorization endpoint returns.
+    #[serde(default)]
+    pub(crate) response_type: AuthorizationResponseType,
+
+    /// Maximum time in seconds since the user last authenticated.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) max_age: Option<MaxAge>,
+
+    /// How the callback handles scopes the user did not grant. Not sent to
+    /// Google.
+    #[serde(default)]
+    pub(crate) partial_consent: PartialConsentPolicy,
+
+    /// Scopes `partial_consent=fail` insists on; defaults to all requested
+    /// scopes. Not sent to Google.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) required_scopes: Option<ScopeList>,
+
+    /// Any other parameters, forwarded if allowlisted.
+    #[serde(flatten)]
+    pub(crate) custom: CustomParams,
+}
+
+impl RequestAccessTokenExtraParams {
+    /// Parameters without a typed representation.
+    pub fn custom(&self) -> &CustomParams {
+        &self.custom
+    }
+
+    /// Which credentials the authorization endpoint returns.
+    pub fn response_type(&self) -> AuthorizationResponseType {
+        self.response_type
+    }
+
+    /// Requires the user to have authenticated within `max_age`, unless a
+    /// stricter `max_age` is already set.
+    pub fn require_authenticated_within(&mut self, max_age: Duration) {
+        let max_age = MaxAge::from(max_age);
+        if self.max_age.is_none_or(|current| *current > *max_age) {
+            self.max_age = Some(max_age);
+        }
+    }
 }
 
 impl<'a> ToExtraParams<'a> for RequestAccessTokenExtraParams {
@@ -62,7 +133,29 @@ impl<'a> ToExtraParams<'a> for RequestAccessTokenExtraParams {
         }
 
         // prompt
-        params.push(self.prompt.clone().into_extra_param());
+        if !self.prompt.0.is_empty() {
+            params.push(self.prompt.clone().into_extra_param());
+        }
+
+        // hd, nonce, enable_granular_consent, hl, display, max_age
+        params.extend(self.hd.clone().map(IntoExtraParam::into_extra_param));
+        params.extend(self.nonce.clone().map(IntoExtraParam::into_extra_param));
+        params.extend(
+            self.enable_granular_consent
+                .map(IntoExtraParam::into_extra_param),
+        );
+        params.extend(self.hl.clone().map(IntoExtraParam::into_extra_param));
+        params.extend(self.display.map(IntoExtraParam::into_extra_param));
+        params.extend(self.max_age.map(IntoExtraParam::into_extra_param));
+
+        // custom parameters, checked against an allowlist beforehand
+        params.extend(self.custom.to_extra_params());
+
+        // `partial_consent` is handled by this crate on callback.
+
+        // `response_type` is always emitted by the `oauth2` crate and is set
+        // via `set_response_type` instead, see
+        // `RequestAccessTokenConfig::authorize_url`.
 
         params
     }
@@ -77,10 +170,16 @@ pub struct RequestAccessTokenPayload {
     /// Optional URI to which the authorization server will redirect after authorization.
     pub redirect_uri: Option<String>,
 
-    /// User defined scopes to authorize
-    #[serde(rename = "scope")]
+    /// User defined scopes to authorize. May be omitted if a `profile` is
+    /// given.
+    #[serde(rename = "scope", default)]
     pub scopes: ScopeList,
 
+    /// Name of a server-side [`ScopeProfile`](super::ScopeProfile) whose
+    /// scopes are added to the request.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub profile: Option<String>,
+
     /// Additional parameters sent along with the authorization request,
     /// flattened into the top-level JSON object for convenience.
     #[serde(flatten)]
@@ -90,9 +189,11 @@ pub struct RequestAccessTokenPayload {
 /// Represents configuration for an authorization request.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RequestAccessTokenConfig {
-    scopes: Vec<Scope>,
+    pub(crate) scopes: Vec<Scope>,
+
+    pub(crate) profile: Option<String>,
 
-    extra_params: RequestAccessTokenExtraParams,
+    pub(crate) extra_params: RequestAccessTokenExtraParams,
 }
 
 impl RequestAccessTokenConfig {
@@ -101,16 +202,124 @@ impl RequestAccessTokenConfig {
         self.scopes.as_slice()
     }
 
+    /// Name of the requested scope profile, if any.
+    pub fn profile(&self) -> Option<&str> {
+        self.profile.as_deref()
+    }
+
     /// Additional parameters sent along with the authorization request.
     pub fn extra_params(&self) -> &RequestAccessTokenExtraParams {
         &self.extra_params
     }
+
+    /// Builds the authorization URL for the given client with a PKCE
+    /// challenge and CSRF token.
+    pub(crate) fn authorize_url(
+        &self,
+        client: &GoogleOAuthClient,
+    ) -> RequestAccessTokenResponse {
+        let (pkce_challenge, pkce_verifier) =
+            PkceCodeChallenge::new_random_sha256();
+        let response_type =
+            ResponseType::new(self.extra_params.response_type.to_string());
+
+        let mut request = client
+            .authorize_url(CsrfToken::new_random)
+            .set_pkce_challenge(pkce_challenge)
+            .set_response_type(&response_type)
+            .add_scopes(self.scopes.clone());
+
+        for (name, value) in self.extra_params.to_extra_params() {
+            request = request.add_extra_param(name.into_cow(), value);
+        }
+
+        let (auth_url, csrf_token) = request.url();
+        RequestAccessTokenResponse::new(pkce_verifier, csrf_token, auth_url)
+            .with_nonce(self.extra_params.nonce.clone())
+            .with_scopes(self.scopes.clone())
+    }
+
+    /// Whether the `openid` scope is requested.
+    pub fn has_openid_scope(&self) -> bool {
+        self.scopes
+            .iter()
+            .any(|scope| scope.as_str() == OpenIdScopePolicy::OPENID_SCOPE)
+    }
+
+    /// Generates a random nonce for ID tokens unless one was given.
+    pub(crate) fn ensure_nonce(&mut self) {
+        if self.has_openid_scope() && self.extra_params.nonce.is_none() {
+            self.extra_params.nonce = Some(Nonce::new_random());
+        }
+    }
+
+    /// Adds or requires the `openid` scope according to `policy`.
+    pub fn apply_openid_policy(
+        &mut self,
+        policy: OpenIdScopePolicy,
+    ) -> crate::Result<()> {
+        if self.has_openid_scope() {
+            return Ok(());
+        }
+
+        match policy {
+            OpenIdScopePolicy::Add => {
+                self.scopes.insert(
+                    0,
+                    Scope::new(OpenIdScopePolicy::OPENID_SCOPE.into()),
+                );
+                Ok(())
+            }
+            OpenIdScopePolicy::Require => {
+                Err(crate::Error::OpenIdScopeRequired)
+            }
+            OpenIdScopePolicy::Optional => Ok(()),
+        }
+    }
+}
+
+/// Determines how authorization requests without the `openid` scope are
+/// handled.
+///
+/// Without `openid`, Google issues no ID token and the user is identified
+/// via the `tokeninfo` endpoint instead, which requires the `email` or
+/// `profile` scope to persist the grant.
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum OpenIdScopePolicy {
+    /// Adds `openid` to requests that lack it (default).
+    #[default]
+    Add,
+    /// Rejects requests without `openid`.
+    Require,
+    /// Sends requests as-is.
+    Optional,
+}
+
+impl OpenIdScopePolicy {
+    const OPENID_SCOPE: &'static str = "openid";
+}
+
+impl FromStr for OpenIdScopePolicy {
+    type Err = crate::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "add" => Ok(Self::Add),
+            "require" => Ok(Self::Require),
+            "optional" => Ok(Self::Optional),
+            other => Err(crate::Error::InvalidOpenIdScopePolicy(other.into())),
+        }
+    }
 }
 
 impl From<&RequestAccessTokenPayload> for RequestAccessTokenConfig {
     fn from(payload: &RequestAccessTokenPayload) -> Self {
         RequestAccessTokenConfig {
             scopes: payload.scopes.to_vec(),
+            profile: payload.profile.clone(),
             extra_params: payload.extra_params.clone(),
         }
     }
@@ -123,6 +332,8 @@ impl From<&RequestAccessTokenPayload> for RequestAccessTokenConfig {
 pub struct RequestAccessTokenResponse {
     pkce_verifier: PkceCodeVerifier,
     csrf_token: CsrfToken,
+    nonce: Option<Nonce>,
+    scopes: Vec<Scope>,
     url: Url,
 }
 
@@ -141,10 +352,26 @@ impl RequestAccessTokenResponse {
         RequestAccessTokenResponse {
             pkce_verifier,
             csrf_token,
+            nonce: None,
+            scopes: Vec::new(),
             url,
         }
     }
 
+    /// Sets the nonce sent with the authorization request.
+    #[must_use]
+    pub fn with_nonce(mut self, nonce: Option<Nonce>) -> Self {
+        self.nonce = nonce;
+        self
+    }
+
+    /// Sets the scopes requested by the authorization request.
+    #[must_use]
+    pub fn with_scopes(mut self, scopes: Vec<Scope>) -> Self {
+        self.scopes = scopes;
+        self
+    }
+
     /// Returns a reference to the PKCE code verifier.
     pub fn pkce_verifier(&self) -> &PkceCodeVerifier {
         &self.pkce_verifier
@@ -155,6 +382,23 @@ impl RequestAccessTokenResponse {
         &self.csrf_token
     }
 
+    /// Returns the nonce sent with the authorization request, if any.
+    ///
+    /// It must be passed to
+    /// [`ExchangeAuthorizationCodeConfigBuilder::nonce`] on callback.
+    pub fn nonce(&self) -> Option<&Nonce> {
+        self.nonce.as_ref()
+    }
+
+    /// Returns the scopes requested after applying the server's scope
+    /// policies.
+    ///
+    /// They should be passed to
+    /// [`ExchangeAuthorizationCodeConfigBuilder::scopes`] on callback.
+    pub fn scopes(&self) -> &[Scope] {
+        &self.scopes
+    }
+
     /// Returns a reference to the authorization URL.
     pub fn url(&self) -> &Url {
         &self.url
@@ -187,6 +431,8 @@ pub struct ExchangeAuthorizationCodeConfig {
     pub(crate) redirect_to: Url,
     pub(crate) csrf_token: String,
     pub(crate) state: String,
+    pub(crate) nonce: Option<Nonce>,
+    pub(crate) scopes: Vec<Scope>,
 }
 
 /// Builder type for [`ExchangeAuthorizationCodeConfig`] to aid ergonomic construction.
@@ -198,6 +444,8 @@ pub struct ExchangeAuthorizationCodeConfigBuilder {
     redirect_to: Option<Url>,
     csrf_token: Option<String>,
     state: Option<String>,
+    nonce: Option<Nonce>,
+    scopes: Vec<Scope>,
     revoke_existing_tokens: bool,
 }
 
@@ -256,6 +504,23 @@ impl ExchangeAuthorizationCodeConfigBuilder {
         self
     }
 
+    /// Sets the nonce sent with the authorization request, which the ID
+    /// token's `nonce` claim must match.
+    #[must_use]
+    pub fn nonce(mut self, nonce: Option<Nonce>) -> Self {
+        self.nonce = nonce;
+        self
+    }
+
+    /// Sets the scopes sent with the authorization request.
+    ///
+    /// Stored as granted if Google's token response omits its `scope`.
+    #[must_use]
+    pub fn scopes(mut self, scopes: impl IntoIterator<Item = Scope>) -> Self {
+        self.scopes = scopes.into_iter().collect();
+        self
+    }
+
     /// Finalizes the builder, returning an error if any required field is missing.
     pub fn build(self) -> crate::Result<ExchangeAuthorizationCodeConfig> {
         Ok(ExchangeAuthorizationCodeConfig {
@@ -276,6 +541,8 @@ impl ExchangeAuthorizationCodeConfigBuilder {
             state: self
                 .state
                 .ok_or(crate::Error::MissingConfigField("state"))?,
+            nonce: self.nonce,
+            scopes: self.scopes,
             revoke_existing_tokens: self.revoke_existing_tokens,
         })
     }
@@ -289,8 +556,9 @@ impl ExchangeAuthorizationCodeConfigBuilder {
 pub struct ExchangeRefreshTokenResponse {
     /// The new access token string.
     pub(crate) access_token: String,
-    /// The `OpenID` Connect ID token string.
-    pub(crate) id_token: String,
+    /// The `OpenID` Connect ID token string, if the `openid` scope was granted.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) id_token: Option<String>,
     /// The UNIX timestamp when the token was issued.
     pub(crate) issued_at: i64,
     /// Token lifetime in seconds.
@@ -304,7 +572,7 @@ impl From<GoogleOAuthTokenResponse> for ExchangeRefreshTokenResponse {
         let access_token = value.access_token().clone();
         Self {
             access_token: access_token.into_secret(),
-            id_token: value.extra_fields().id_token().to_owned(),
+            id_token: value.extra_fields().id_token().map(str::to_owned),
             issued_at,
             expires_in,
         }
@@ -323,6 +591,9 @@ pub enum AuthorizationResponse {
         url: Url,
         /// The error message explaining why authorization failed.
         error: String,
+        /// Requested scopes the user did not grant.
+        #[serde(default)]
+        missing_scopes: ScopeSet,
     },
     /// Represents a successful authorization redirect.
     Success {
@@ -330,6 +601,9 @@ pub enum AuthorizationResponse {
         url: Url,
         /// The OAuth token response received upon successful authorization.
         token: GoogleOAuthTokenResponse,
+        /// Requested scopes the user did not grant.
+        #[serde(default)]
+        missing_scopes: ScopeSet,
     },
 }
 
@@ -339,34 +613,259 @@ impl AuthorizationResponse {
         AuthorizationResponse::Error {
             url,
             error: error.as_ref().to_owned(),
+            missing_scopes: ScopeSet::default(),
         }
     }
 
     /// Creates a new success variant with the given URL and OAuth token response.
     pub fn new_success(url: Url, token: GoogleOAuthTokenResponse) -> Self {
-        AuthorizationResponse::Success { url, token }
+        AuthorizationResponse::Success {
+            url,
+            token,
+            missing_scopes: ScopeSet::default(),
+        }
+    }
+
+    /// Sets the requested scopes the user did not grant, reported as
+    /// space-separated `missing_scopes` in the URL fragment.
+    #[must_use]
+    pub fn with_missing_scopes(mut self, scopes: ScopeSet) -> Self {
+        match &mut self {
+            AuthorizationResponse::Error { missing_scopes, .. }
+            | AuthorizationResponse::Success { missing_scopes, .. } => {
+                *missing_scopes = scopes;
+            }
+        }
+        self
+    }
+
+    /// Returns the requested scopes the user did not grant.
+    pub fn missing_scopes(&self) -> &ScopeSet {
+        match self {
+            AuthorizationResponse::Error { missing_scopes, .. }
+            | AuthorizationResponse::Success { missing_scopes, .. } => {
+                missing_scopes
+            }
+        }
     }
 }
 
 impl fmt::Display for AuthorizationResponse {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            AuthorizationResponse::Error { url, error } => {
-                write!(f, "{}#error={}", url, urlencoding::encode(error))
+            AuthorizationResponse::Error { url, error, .. } => {
+                write!(f, "{}#error={}", url, urlencoding::encode(error))?;
             }
-            AuthorizationResponse::Success { url, token } => {
+            AuthorizationResponse::Success { url, token, .. } => {
                 let issued_at = chrono::Utc::now().timestamp();
                 let expires_in = token.expires_in().map_or(0, |d| d.as_secs());
                 write!(
                     f,
-                    "{}#access_token={}&id_token={}&expires_in={}&issued_at={}",
+                    "{}#access_token={}",
                     url,
                     token.access_token().secret(),
-                    token.extra_fields().id_token(),
-                    expires_in,
-                    issued_at
-                )
+                )?;
+                if let Some(id_token) = token.extra_fields().id_token() {
+                    write!(f, "&id_token={id_token}")?;
+                }
+                write!(f, "&expires_in={expires_in}&issued_at={issued_at}")?;
             }
         }
+
+        let missing_scopes = self.missing_scopes();
+        if !missing_scopes.is_empty() {
+            let missing_scopes = missing_scopes.to_string();
+            write!(
+                f,
+                "&missing_scopes={}",
+                urlencoding::encode(&missing_scopes)
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn config(scope: &str) -> RequestAccessTokenConfig {
+        let payload: RequestAccessTokenPayload =
+            serde_json::from_value(json!({ "scope": scope })).unwrap();
+        RequestAccessTokenConfig::from(&payload)
+    }
+
+    fn client() -> GoogleOAuthClient {
+        let json = json!({
+            "web": {
+                "client_id": "test-client.apps.googleusercontent.com",
+                "project_id": "test-project",
+                "auth_uri": "https://accounts.google.com/o/oauth2/auth",
+                "token_uri": "https://oauth2.googleapis.com/token",
+                "auth_provider_x509_cert_url": "https://www.googleapis.com/oauth2/v1/certs",
+                "client_secret": "test-secret"
+            }
+        });
+        crate::GoogleOAuthClientConfig::from_slice(json.to_string().as_bytes())
+            .unwrap()
+            .oauth_client()
+            .unwrap()
+    }
+
+    fn query_pairs(url: &Url) -> Vec<(String, String)> {
+        url.query_pairs().into_owned().collect()
+    }
+
+    #[test]
+    fn test_authorize_url_includes_typed_params_from_query() {
+        let payload: RequestAccessTokenPayload = serde_urlencoded::from_str(
+            "scope=openid%20email&hd=example.com&nonce=n-0S6_WzA2Mj\
+             &enable_granular_consent=false&hl=pt-BR&display=popup\
+             &response_type=id_token%20code&max_age=300",
+        )
+        .unwrap();
+        let config = RequestAccessTokenConfig::from(&payload);
+
+        let response = config.authorize_url(&client());
+        let query = query_pairs(response.url());
+        let param = |name: &str| {
+            query
+                .iter()
+                .filter(|(key, _)| key == name)
+                .map(|(_, value)| value.as_str())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(param("hd"), ["example.com"]);
+        assert_eq!(param("nonce"), ["n-0S6_WzA2Mj"]);
+        assert_eq!(param("enable_granular_consent"), ["false"]);
+        assert_eq!(param("hl"), ["pt-BR"]);
+        assert_eq!(param("display"), ["popup"]);
+        assert_eq!(param("response_type"), ["code id_token"]);
+        assert_eq!(param("max_age"), ["300"]);
+    }
+
+    #[test]
+    fn test_authorize_url_omits_unset_params() {
+        let response = config("openid").authorize_url(&client());
+        let names = query_pairs(response.url())
+            .into_iter()
+            .map(|(name, _)| name)
+            .collect::<Vec<_>>();
+
+        assert!(names.contains(&"response_type".to_owned()));
+        for name in [
+            "hd",
+            "nonce",
+            "enable_granular_consent",
+            "hl",
+            "display",
+            "prompt",
+        ] {
+            assert!(!names.contains(&name.to_owned()), "{name}");
+        }
+        assert_eq!(response.scopes(), [Scope::new("openid".into())]);
+    }
+
+    #[test]
+    fn test_unknown_query_params_are_collected_as_custom_params() {
+        let payload: RequestAccessTokenPayload = serde_urlencoded::from_str(
+            "scope=openid&prompt=consent&ack_oob_shutdown=2022-10-03",
+        )
+        .unwrap();
+        let custom = payload.extra_params.custom();
+        assert_eq!(
+            custom.iter().collect::<Vec<_>>(),
+            [("ack_oob_shutdown", "2022-10-03")]
+        );
+
+        let config = RequestAccessTokenConfig::from(&payload);
+        let query = query_pairs(config.authorize_url(&client()).url());
+        assert!(query.contains(&(
+            "ack_oob_shutdown".to_owned(),
+            "2022-10-03".to_owned()
+        )));
+        assert!(
+            serde_urlencoded::from_str::<RequestAccessTokenPayload>(
+                "scope=openid&client_id=attacker"
+            )
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn test_invalid_params_are_rejected() {
+        for query in [
+            "scope=openid&response_type=token",
+            "scope=openid&display=fullscreen",
+            "scope=openid&max_age=-1",
+        ] {
+            assert!(
+                serde_urlencoded::from_str::<RequestAccessTokenPayload>(query)
+                    .is_err(),
+                "{query}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_apply_openid_policy() {
+        let drive = "https://www.googleapis.com/auth/drive.readonly";
+
+        let mut added = config(drive);
+        added.apply_openid_policy(OpenIdScopePolicy::Add).unwrap();
+        assert!(added.has_openid_scope());
+        assert_eq!(added.scopes().len(), 2);
+
+        let mut required = config(drive);
+        assert!(
+            required
+                .apply_openid_policy(OpenIdScopePolicy::Require)
+                .is_err()
+        );
+        let mut required = config(&format!("openid {drive}"));
+        required
+            .apply_openid_policy(OpenIdScopePolicy::Require)
+            .unwrap();
+
+        let mut optional = config(drive);
+        optional
+            .apply_openid_policy(OpenIdScopePolicy::Optional)
+            .unwrap();
+        assert!(!optional.has_openid_scope());
+    }
+
+    #[test]
+    fn test_ensure_nonce_is_sent_and_returned() {
+        let mut config = config("openid email");
+        config.ensure_nonce();
+        let response = config.authorize_url(&client());
+
+        let nonce = response.nonce().unwrap();
+        assert!(
+            query_pairs(response.url())
+                .contains(&("nonce".to_owned(), nonce.to_string()))
+        );
+
+        let mut config = self::config("email");
+        config.ensure_nonce();
+        assert!(config.authorize_url(&client()).nonce().is_none());
+    }
+
+    #[test]
+    fn test_error_response_reports_missing_scopes() {
+        let url = Url::parse("https://example.com/").unwrap();
+        let response =
+            AuthorizationResponse::new_error(url, "scopes_not_granted")
+                .with_missing_scopes(ScopeSet::new([
+                    "https://www.googleapis.com/auth/drive.file",
+                ]));
+
+        assert_eq!(
+            response.to_string(),
+            "https://example.com/#error=scopes_not_granted&missing_scopes=\
+             https%3A%2F%2Fwww.googleapis.com%2Fauth%2Fdrive.file"
+        );
     }
 }
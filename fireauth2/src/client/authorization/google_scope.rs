@@ -0,0 +1,377 @@
+use std::fmt;
+use std::str::FromStr;
+
+use serde::Serialize;
+
+use super::scope::Scope;
+
+/// Prefix shared by the URLs of Google API scopes.
+const SCOPE_URL_PREFIX: &str = "https://www.googleapis.com/auth/";
+
+/// Google's verification classification of a scope.
+///
+/// See <https://developers.google.com/identity/protocols/oauth2/production-readiness/sensitive-scope-verification>.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum ScopeSensitivity {
+    /// Basic sign-in and app-specific data, no verification required.
+    NonSensitive,
+    /// Access to private user data, requires app verification.
+    Sensitive,
+    /// Broad access to Gmail or Drive data, requires a security assessment.
+    Restricted,
+}
+
+/// A commonly used Google API scope.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum GoogleScope {
+    /// `openid`
+    OpenId,
+    /// `email`, `https://www.googleapis.com/auth/userinfo.email`
+    Email,
+    /// `profile`, `https://www.googleapis.com/auth/userinfo.profile`
+    Profile,
+    /// `https://www.googleapis.com/auth/calendar`
+    Calendar,
+    /// `https://www.googleapis.com/auth/calendar.readonly`
+    CalendarReadonly,
+    /// `https://www.googleapis.com/auth/calendar.events`
+    CalendarEvents,
+    /// `https://www.googleapis.com/auth/drive`
+    Drive,
+    /// `https://www.googleapis.com/auth/drive.readonly`
+    DriveReadonly,
+    /// `https://www.googleapis.com/auth/drive.file`
+    DriveFile,
+    /// `https://www.googleapis.com/auth/drive.appdata`
+    DriveAppData,
+    /// `https://mail.google.com/`
+    Gmail,
+    /// `https://www.googleapis.com/auth/gmail.readonly`
+    GmailReadonly,
+    /// `https://www.googleapis.com/auth/gmail.send`
+    GmailSend,
+    /// `https://www.googleapis.com/auth/gmail.modify`
+    GmailModify,
+    /// `https://www.googleapis.com/auth/contacts`
+    Contacts,
+    /// `https://www.googleapis.com/auth/contacts.readonly`
+    ContactsReadonly,
+    /// `https://www.googleapis.com/auth/spreadsheets`
+    Spreadsheets,
+    /// `https://www.googleapis.com/auth/spreadsheets.readonly`
+    SpreadsheetsReadonly,
+    /// `https://www.googleapis.com/auth/documents`
+    Documents,
+    /// `https://www.googleapis.com/auth/documents.readonly`
+    DocumentsReadonly,
+    /// `https://www.googleapis.com/auth/tasks`
+    Tasks,
+    /// `https://www.googleapis.com/auth/tasks.readonly`
+    TasksReadonly,
+    /// `https://www.googleapis.com/auth/youtube.readonly`
+    YouTubeReadonly,
+    /// `https://www.googleapis.com/auth/cloud-platform`
+    CloudPlatform,
+}
+
+/// Static details of a [`GoogleScope`].
+struct ScopeInfo {
+    /// Canonical scope, relative to [`SCOPE_URL_PREFIX`] unless absolute.
+    name: &'static str,
+    alias: Option<&'static str>,
+    sensitivity: ScopeSensitivity,
+    description: &'static str,
+}
+
+impl GoogleScope {
+    /// All scopes of the catalogue.
+    pub const ALL: [GoogleScope; 24] = [
+        Self::OpenId,
+        Self::Email,
+        Self::Profile,
+        Self::Calendar,
+        Self::CalendarReadonly,
+        Self::CalendarEvents,
+        Self::Drive,
+        Self::DriveReadonly,
+        Self::DriveFile,
+        Self::DriveAppData,
+        Self::Gmail,
+        Self::GmailReadonly,
+        Self::GmailSend,
+        Self::GmailModify,
+        Self::Contacts,
+        Self::ContactsReadonly,
+        Self::Spreadsheets,
+        Self::SpreadsheetsReadonly,
+        Self::Documents,
+        Self::DocumentsReadonly,
+        Self::Tasks,
+        Self::TasksReadonly,
+        Self::YouTubeReadonly,
+        Self::CloudPlatform,
+    ];
+
+    #[expect(clippy::too_many_lines)]
+    fn info(self) -> ScopeInfo {
+        use ScopeSensitivity::{NonSensitive, Restricted, Sensitive};
+
+        let (name, alias, sensitivity, description) = match self {
+            Self::OpenId => (
+                "openid",
+                None,
+                NonSensitive,
+                "Associate you with your personal info on Google",
+            ),
+            Self::Email => (
+                "userinfo.email",
+                Some("email"),
+                NonSensitive,
+                "See your primary Google Account email address",
+            ),
+            Self::Profile => (
+                "userinfo.profile",
+                Some("profile"),
+                NonSensitive,
+                "See your personal info, including any personal info you've \
+                 made publicly available",
+            ),
+            Self::Calendar => (
+                "calendar",
+                None,
+                Sensitive,
+                "See, edit, share, and permanently delete all the calendars \
+                 you can access using Google Calendar",
+            ),
+            Self::CalendarReadonly => (
+                "calendar.readonly",
+                None,
+                Sensitive,
+                "See and download any calendar you can access using your \
+                 Google Calendar",
+            ),
+            Self::CalendarEvents => (
+                "calendar.events",
+                None,
+                Sensitive,
+                "View and edit events on all your calendars",
+            ),
+            Self::Drive => (
+                "drive",
+                None,
+                Restricted,
+                "See, edit, create, and delete all of your Google Drive files",
+            ),
+            Self::DriveReadonly => (
+                "drive.readonly",
+                None,
+                Restricted,
+                "See and download all your Google Drive files",
+            ),
+            Self::DriveFile => (
+                "drive.file",
+                None,
+                NonSensitive,
+                "See, edit, create, and delete only the specific Google Drive \
+                 files you use with this app",
+            ),
+            Self::DriveAppData => (
+                "drive.appdata",
+                None,
+                NonSensitive,
+                "See, create, and delete its own configuration data in your \
+                 Google Drive",
+            ),
+            Self::Gmail => (
+                "https://mail.google.com/",
+                None,
+                Restricted,
+                "Read, compose, send, and permanently delete all your email \
+                 from Gmail",
+            ),
+            Self::GmailReadonly => (
+                "gmail.readonly",
+                None,
+                Restricted,
+                "View your email messages and settings",
+            ),
+            Self::GmailSend => {
+                ("gmail.send", None, Sensitive, "Send email on your behalf")
+            }
+            Self::GmailModify => (
+                "gmail.modify",
+                None,
+                Restricted,
+                "Read, compose, and send emails from your Gmail account",
+            ),
+            Self::Contacts => (
+                "contacts",
+                None,
+                Sensitive,
+                "See, edit, download, and permanently delete your contacts",
+            ),
+            Self::ContactsReadonly => (
+                "contacts.readonly",
+                None,
+                Sensitive,
+                "See and download your contacts",
+            ),
+            Self::Spreadsheets => (
+                "spreadsheets",
+                None,
+                Sensitive,
+                "See, edit, create, and delete all your Google Sheets \
+                 spreadsheets",
+            ),
+            Self::SpreadsheetsReadonly => (
+                "spreadsheets.readonly",
+                None,
+                Sensitive,
+                "See all your Google Sheets spreadsheets",
+            ),
+            Self::Documents => (
+                "documents",
+                None,
+                Sensitive,
+                "See, edit, create, and delete all your Google Docs documents",
+            ),
+            Self::DocumentsReadonly => (
+                "documents.readonly",
+                None,
+                Sensitive,
+                "See all your Google Docs documents",
+            ),
+            Self::Tasks => (
+                "tasks",
+                None,
+                Sensitive,
+                "Create, edit, organize, and delete all your tasks",
+            ),
+            Self::TasksReadonly => {
+                ("tasks.readonly", None, Sensitive, "View your tasks")
+            }
+            Self::YouTubeReadonly => (
+                "youtube.readonly",
+                None,
+                Sensitive,
+                "View your YouTube account",
+            ),
+            Self::CloudPlatform => (
+                "cloud-platform",
+                None,
+                Sensitive,
+                "See, edit, configure, and delete your Google Cloud data and \
+                 see the email address for your Google Account",
+            ),
+        };
+
+        ScopeInfo {
+            name,
+            alias,
+            sensitivity,
+            description,
+        }
+    }
+
+    /// Returns the canonical scope string, e.g.
+    /// `https://www.googleapis.com/auth/userinfo.email` for
+    /// [`GoogleScope::Email`].
+    pub fn url(self) -> String {
+        let name = self.info().name;
+        if self == Self::OpenId || name.starts_with("https://") {
+            name.to_owned()
+        } else {
+            format!("{SCOPE_URL_PREFIX}{name}")
+        }
+    }
+
+    /// Returns the short alias Google also accepts, e.g. `email`.
+    pub fn alias(self) -> Option<&'static str> {
+        self.info().alias
+    }
+
+    /// Returns Google's verification classification of the scope.
+    pub fn sensitivity(self) -> ScopeSensitivity {
+        self.info().sensitivity
+    }
+
+    /// Returns the description Google shows on the consent screen.
+    pub fn description(self) -> &'static str {
+        self.info().description
+    }
+
+    /// Returns the canonical form of the given scope if it is part of the
+    /// catalogue, e.g. `email` becomes
+    /// `https://www.googleapis.com/auth/userinfo.email`. Other scopes are
+    /// returned unchanged.
+    pub fn normalize(scope: &str) -> String {
+        scope
+            .parse::<GoogleScope>()
+            .map_or_else(|_| scope.to_owned(), GoogleScope::url)
+    }
+}
+
+impl FromStr for GoogleScope {
+    type Err = crate::Error;
+
+    /// Parses a canonical scope URL or alias.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let relative = s.strip_prefix(SCOPE_URL_PREFIX);
+        Self::ALL
+            .into_iter()
+            .find(|scope| {
+                let info = scope.info();
+                info.alias == Some(s)
+                    || (*scope == Self::OpenId && s == info.name)
+                    || (info.name.starts_with("https://") && s == info.name)
+                    || relative == Some(info.name)
+            })
+            .ok_or_else(|| crate::Error::UnknownGoogleScope(s.into()))
+    }
+}
+
+impl fmt::Display for GoogleScope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.url())
+    }
+}
+
+impl From<GoogleScope> for Scope {
+    fn from(scope: GoogleScope) -> Self {
+        Scope::new(scope.url())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_and_normalize() {
+        for scope in GoogleScope::ALL {
+            assert_eq!(scope.url().parse::<GoogleScope>().unwrap(), scope);
+            if let Some(alias) = scope.alias() {
+                assert_eq!(alias.parse::<GoogleScope>().unwrap(), scope);
+            }
+        }
+
+        assert_eq!(
+            GoogleScope::normalize("email"),
+            "https://www.googleapis.com/auth/userinfo.email"
+        );
+        assert_eq!(GoogleScope::normalize("openid"), "openid");
+        assert_eq!(
+            GoogleScope::normalize("https://example.com/scope"),
+            "https://example.com/scope"
+        );
+        assert!("calendar".parse::<GoogleScope>().is_err());
+        assert_eq!(
+            GoogleScope::Gmail.sensitivity(),
+            ScopeSensitivity::Restricted
+        );
+    }
+}
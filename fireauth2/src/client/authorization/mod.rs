@@ -1,7 +1,17 @@
+mod code_exchange;
+mod consent;
 mod extra_params;
 mod flow;
+mod google_scope;
 mod scope;
+mod scope_policy;
+mod validation;
 
+pub use code_exchange::*;
+pub use consent::*;
 pub use extra_params::*;
 pub use flow::*;
+pub use google_scope::*;
 pub use scope::*;
+pub use scope_policy::*;
+pub use validation::*;
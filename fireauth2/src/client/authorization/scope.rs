@@ -1,10 +1,13 @@
+use std::collections::BTreeSet;
 use std::{fmt, ops::Deref};
 
 pub use oauth2::Scope;
 use serde::{Deserialize, Serialize, de};
 
+use super::google_scope::GoogleScope;
+
 /// A newtype struct for the `scope` param that wraps a list of [Scopes][Scope].
-#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Default, Serialize, PartialEq, Eq)]
 pub struct ScopeList(pub Vec<Scope>);
 
 impl ScopeList {
@@ -78,6 +81,91 @@ impl<'de> Deserialize<'de> for ScopeList {
     }
 }
 
+/// A set of scopes in their canonical form, so that aliases such as `email`
+/// and `https://www.googleapis.com/auth/userinfo.email` compare equal.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ScopeSet(BTreeSet<String>);
+
+impl ScopeSet {
+    /// Creates a set from the given scopes, normalizing known aliases via
+    /// [`GoogleScope::normalize`].
+    pub fn new(scopes: impl IntoIterator<Item = impl AsRef<str>>) -> Self {
+        ScopeSet(
+            scopes
+                .into_iter()
+                .map(|scope| GoogleScope::normalize(scope.as_ref()))
+                .collect(),
+        )
+    }
+
+    /// Whether the set contains the given scope or one of its aliases.
+    pub fn contains(&self, scope: &str) -> bool {
+        self.0.contains(&GoogleScope::normalize(scope))
+    }
+
+    /// Whether the set contains every scope of `other`.
+    pub fn is_superset(&self, other: &ScopeSet) -> bool {
+        self.0.is_superset(&other.0)
+    }
+
+    /// Whether every scope of the set is contained in `other`.
+    pub fn is_subset(&self, other: &ScopeSet) -> bool {
+        self.0.is_subset(&other.0)
+    }
+
+    /// Returns the scopes of the set that are not in `other`.
+    #[must_use]
+    pub fn difference(&self, other: &ScopeSet) -> ScopeSet {
+        ScopeSet(self.0.difference(&other.0).cloned().collect())
+    }
+
+    /// Returns the scopes contained in both sets.
+    #[must_use]
+    pub fn intersection(&self, other: &ScopeSet) -> ScopeSet {
+        ScopeSet(self.0.intersection(&other.0).cloned().collect())
+    }
+
+    /// Returns the scopes contained in either set.
+    #[must_use]
+    pub fn union(&self, other: &ScopeSet) -> ScopeSet {
+        ScopeSet(self.0.union(&other.0).cloned().collect())
+    }
+
+    /// Whether the set contains no scopes.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Returns the canonical scopes in lexicographic order.
+    pub fn iter(&self) -> impl Iterator<Item = &str> {
+        self.0.iter().map(String::as_str)
+    }
+
+    /// Converts the set into a list of [Scopes][Scope].
+    pub fn to_scopes(&self) -> Vec<Scope> {
+        self.0.iter().cloned().map(Scope::new).collect()
+    }
+}
+
+impl fmt::Display for ScopeSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, scope) in self.iter().enumerate() {
+            if i > 0 {
+                f.write_str(" ")?;
+            }
+            f.write_str(scope)?;
+        }
+        Ok(())
+    }
+}
+
+impl From<&[Scope]> for ScopeSet {
+    fn from(scopes: &[Scope]) -> Self {
+        ScopeSet::new(scopes.iter().map(|scope| scope.as_str()))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -140,4 +228,26 @@ mod tests {
                 .contains("at least one scope")
         );
     }
+
+    #[test]
+    fn test_scope_set_normalizes_aliases() {
+        let requested = ScopeSet::new([
+            "openid",
+            "email",
+            "https://www.googleapis.com/auth/drive.file",
+        ]);
+        let granted = ScopeSet::new([
+            "openid",
+            "https://www.googleapis.com/auth/userinfo.email",
+        ]);
+
+        assert!(requested.is_superset(&granted));
+        assert!(granted.is_subset(&requested));
+        assert!(granted.contains("email"));
+        assert_eq!(
+            requested.difference(&granted).iter().collect::<Vec<_>>(),
+            ["https://www.googleapis.com/auth/drive.file"]
+        );
+        assert_eq!(requested.union(&granted), requested);
+    }
 }
@@ -0,0 +1,267 @@
+use std::collections::{HashMap, HashSet};
+
+use serde::{Deserialize, Deserializer};
+
+use super::extra_params::{AccessType, PromptList};
+use super::flow::RequestAccessTokenConfig;
+use super::google_scope::GoogleScope;
+use super::scope::{Scope, ScopeList};
+
+/// A named set of scopes clients request via the `profile` parameter
+/// instead of listing raw scopes, e.g. `calendar-read`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScopeProfile {
+    scopes: ScopeList,
+    #[serde(default)]
+    access_type: Option<AccessType>,
+    #[serde(default)]
+    prompt: Option<PromptList>,
+}
+
+impl ScopeProfile {
+    /// Creates a profile requesting the given scopes.
+    pub fn new(scopes: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        Self {
+            scopes: ScopeList::new(
+                scopes.into_iter().map(|s| Scope::new(s.into())).collect(),
+            ),
+            access_type: None,
+            prompt: None,
+        }
+    }
+
+    /// Sets the `access_type` used if the request keeps the default
+    /// (`online`).
+    #[must_use]
+    pub fn with_access_type(mut self, access_type: AccessType) -> Self {
+        self.access_type = Some(access_type);
+        self
+    }
+
+    /// Sets the `prompt` used if the request keeps the default.
+    #[must_use]
+    pub fn with_prompt(mut self, prompt: PromptList) -> Self {
+        self.prompt = Some(prompt);
+        self
+    }
+
+    /// Returns the scopes of the profile.
+    pub fn scopes(&self) -> &[Scope] {
+        &self.scopes
+    }
+}
+
+/// Server-side restrictions on the scopes of authorization requests.
+///
+/// Resolves named [`ScopeProfile`]s and rejects raw scopes that are not
+/// allowlisted. Scopes of a profile are always permitted, as is `openid`.
+/// An empty allowlist permits any raw scope. Scopes are compared in their
+/// canonical form, so `email` also permits
+/// `https://www.googleapis.com/auth/userinfo.email`. Deserializes from
+/// camelCase JSON, e.g.
+/// `{"profiles": {"calendar-read": {"scopes": "..."}}, "allowedScopes": []}`.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ScopePolicy {
+    profiles: HashMap<String, ScopeProfile>,
+    #[serde(deserialize_with = "deserialize_normalized_scopes")]
+    allowed_scopes: HashSet<String>,
+}
+
+/// Deserializes a list of scopes into their canonical form.
+fn deserialize_normalized_scopes<'de, D>(
+    deserializer: D,
+) -> Result<HashSet<String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let scopes = Vec::<String>::deserialize(deserializer)?;
+    Ok(scopes.iter().map(|s| GoogleScope::normalize(s)).collect())
+}
+
+impl ScopePolicy {
+    /// Registers a named scope profile.
+    #[must_use]
+    pub fn with_profile(
+        mut self,
+        name: impl Into<String>,
+        profile: ScopeProfile,
+    ) -> Self {
+        self.profiles.insert(name.into(), profile);
+        self
+    }
+
+    /// Only permits the given raw scopes.
+    #[must_use]
+    pub fn with_allowed_scopes(
+        mut self,
+        scopes: impl IntoIterator<Item = impl Into<String>>,
+    ) -> Self {
+        self.allowed_scopes = scopes
+            .into_iter()
+            .map(|scope| GoogleScope::normalize(&scope.into()))
+            .collect();
+        self
+    }
+
+    /// Returns the profile registered under `name`.
+    pub fn profile(&self, name: &str) -> Option<&ScopeProfile> {
+        self.profiles.get(name)
+    }
+
+    /// Checks the raw scopes of `config` against the allowlist, then adds the
+    /// scopes and defaults of the requested profile, if any.
+    pub fn apply(
+        &self,
+        config: &mut RequestAccessTokenConfig,
+    ) -> crate::Result<()> {
+        let profile =
+            self.resolve_scopes(&mut config.scopes, config.profile.as_deref())?;
+
+        if let Some(profile) = profile {
+            let params = &mut config.extra_params;
+            if let Some(access_type) = &profile.access_type {
+                if matches!(params.access_type, AccessType::Online) {
+                    params.access_type = access_type.clone();
+                }
+            }
+            if let Some(prompt) = &profile.prompt {
+                if params.prompt.0 == PromptList::default().0 {
+                    params.prompt = prompt.clone();
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks the raw `scopes` against the allowlist, then adds the scopes
+    /// of the profile named `profile`, if any, and returns that profile.
+    ///
+    /// Fails if no scope remains.
+    pub fn resolve_scopes(
+        &self,
+        scopes: &mut Vec<Scope>,
+        profile: Option<&str>,
+    ) -> crate::Result<Option<&ScopeProfile>> {
+        if !self.allowed_scopes.is_empty() {
+            if let Some(scope) = scopes.iter().find(|scope| {
+                scope.as_str() != "openid"
+                    && !self
+                        .allowed_scopes
+                        .contains(&GoogleScope::normalize(scope.as_str()))
+            }) {
+                return Err(crate::Error::DisallowedScope(
+                    scope.as_str().into(),
+                ));
+            }
+        }
+
+        let profile = profile
+            .map(|name| {
+                self.profile(name).ok_or_else(|| {
+                    crate::Error::UnknownScopeProfile(name.into())
+                })
+            })
+            .transpose()?;
+        for scope in profile.iter().flat_map(|profile| profile.scopes()) {
+            if !scopes.contains(scope) {
+                scopes.push(scope.clone());
+            }
+        }
+
+        if scopes.is_empty() {
+            return Err(crate::Error::MissingScope);
+        }
+        Ok(profile)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::authorization::{Prompt, RequestAccessTokenPayload};
+    use serde_json::json;
+
+    const CALENDAR: &str = "https://www.googleapis.com/auth/calendar.readonly";
+
+    fn policy() -> ScopePolicy {
+        serde_json::from_value(json!({
+            "profiles": {
+                "calendar-read": {
+                    "scopes": CALENDAR,
+                    "accessType": "offline",
+                    "prompt": "consent",
+                },
+            },
+            "allowedScopes": ["email", "profile"],
+        }))
+        .unwrap()
+    }
+
+    fn apply(
+        payload: serde_json::Value,
+    ) -> crate::Result<RequestAccessTokenConfig> {
+        let payload: RequestAccessTokenPayload =
+            serde_json::from_value(payload).unwrap();
+        let mut config = RequestAccessTokenConfig::from(&payload);
+        policy().apply(&mut config)?;
+        Ok(config)
+    }
+
+    #[test]
+    fn test_apply_resolves_profile() {
+        let config = apply(json!({
+            "scope": "openid email",
+            "profile": "calendar-read",
+        }))
+        .unwrap();
+
+        let scopes: Vec<_> =
+            config.scopes().iter().map(|s| s.as_str()).collect();
+        assert_eq!(scopes, ["openid", "email", CALENDAR]);
+        let params = config.extra_params();
+        assert!(matches!(params.access_type, AccessType::Offline));
+        assert_eq!(params.prompt.0, [Prompt::Consent]);
+
+        let config = apply(json!({
+            "profile": "calendar-read",
+            "access_type": "online",
+            "prompt": "select_account",
+        }))
+        .unwrap();
+        assert_eq!(config.extra_params().prompt.0, [Prompt::SelectAccount]);
+    }
+
+    #[test]
+    fn test_apply_rejects_unknown_scopes_and_profiles() {
+        assert!(matches!(
+            apply(json!({ "scope": "email https://mail.google.com/" })),
+            Err(crate::Error::DisallowedScope(scope))
+                if scope == "https://mail.google.com/"
+        ));
+        assert!(matches!(
+            apply(json!({ "profile": "drive" })),
+            Err(crate::Error::UnknownScopeProfile(_))
+        ));
+        assert!(matches!(apply(json!({})), Err(crate::Error::MissingScope)));
+    }
+
+    #[test]
+    fn test_apply_compares_normalized_scopes() {
+        const EMAIL: &str = "https://www.googleapis.com/auth/userinfo.email";
+
+        let config = apply(json!({ "scope": format!("openid {EMAIL}") }));
+        assert!(config.is_ok());
+
+        let policy = ScopePolicy::default().with_allowed_scopes([EMAIL]);
+        let payload: RequestAccessTokenPayload =
+            serde_json::from_value(json!({ "scope": "email profile" }))
+                .unwrap();
+        let mut config = RequestAccessTokenConfig::from(&payload);
+        assert!(matches!(
+            policy.apply(&mut config),
+            Err(crate::Error::DisallowedScope(scope)) if scope == "profile"
+        ));
+    }
+}
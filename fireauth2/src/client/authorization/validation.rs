@@ -0,0 +1,291 @@
+use std::collections::HashSet;
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use super::extra_params::{AccessType, AuthorizationResponseType, Prompt};
+use super::flow::RequestAccessTokenConfig;
+use super::google_scope::GoogleScope;
+
+/// Determines how authorization requests failing validation are handled.
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum RequestValidationMode {
+    /// Logs all issues and sends the request as-is (default).
+    #[default]
+    Warn,
+    /// Rejects requests with at least one error-level issue.
+    Reject,
+}
+
+impl FromStr for RequestValidationMode {
+    type Err = crate::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "warn" => Ok(Self::Warn),
+            "reject" => Ok(Self::Reject),
+            other => {
+                Err(crate::Error::InvalidRequestValidationMode(other.into()))
+            }
+        }
+    }
+}
+
+/// Severity of a [`ValidationIssue`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ValidationSeverity {
+    /// Google accepts the request, but it likely does not do what was
+    /// intended.
+    Warning,
+    /// Google rejects the request.
+    Error,
+}
+
+/// A single problem found in an authorization request.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ValidationIssue {
+    code: &'static str,
+    severity: ValidationSeverity,
+    message: String,
+}
+
+impl ValidationIssue {
+    fn error(code: &'static str, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            severity: ValidationSeverity::Error,
+            message: message.into(),
+        }
+    }
+
+    fn warning(code: &'static str, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            severity: ValidationSeverity::Warning,
+            message: message.into(),
+        }
+    }
+
+    /// Machine-readable identifier of the issue, e.g. `conflicting_prompt`.
+    pub fn code(&self) -> &str {
+        self.code
+    }
+
+    /// Returns the severity of the issue.
+    pub fn severity(&self) -> ValidationSeverity {
+        self.severity
+    }
+
+    /// Returns a human-readable description of the issue.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+impl fmt::Display for ValidationIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.code, self.message)
+    }
+}
+
+/// All issues found by [`RequestAccessTokenConfig::validate`].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(transparent)]
+pub struct ValidationReport(Vec<ValidationIssue>);
+
+impl ValidationReport {
+    /// Returns all issues.
+    pub fn issues(&self) -> &[ValidationIssue] {
+        &self.0
+    }
+
+    /// Whether no error-level issue was found.
+    pub fn is_valid(&self) -> bool {
+        self.errors().next().is_none()
+    }
+
+    /// Returns the error-level issues.
+    pub fn errors(&self) -> impl Iterator<Item = &ValidationIssue> {
+        self.with_severity(ValidationSeverity::Error)
+    }
+
+    /// Returns the warning-level issues.
+    pub fn warnings(&self) -> impl Iterator<Item = &ValidationIssue> {
+        self.with_severity(ValidationSeverity::Warning)
+    }
+
+    fn with_severity(
+        &self,
+        severity: ValidationSeverity,
+    ) -> impl Iterator<Item = &ValidationIssue> {
+        self.0
+            .iter()
+            .filter(move |issue| issue.severity == severity)
+    }
+}
+
+impl fmt::Display for ValidationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, issue) in self.0.iter().enumerate() {
+            if i > 0 {
+                f.write_str("; ")?;
+            }
+            write!(f, "{issue}")?;
+        }
+        Ok(())
+    }
+}
+
+impl RequestAccessTokenConfig {
+    /// Checks the request for parameter combinations and scopes that Google
+    /// rejects or that silently do the wrong thing.
+    pub fn validate(&self) -> ValidationReport {
+        let mut issues = vec![];
+        let params = self.extra_params();
+        let prompts = &params.prompt.0;
+
+        if prompts.contains(&Prompt::None) && prompts.len() > 1 {
+            issues.push(ValidationIssue::error(
+                "conflicting_prompt",
+                "`prompt=none` cannot be combined with other prompt values",
+            ));
+        }
+
+        if matches!(params.access_type, AccessType::Offline)
+            && !prompts.contains(&Prompt::Consent)
+        {
+            issues.push(ValidationIssue::warning(
+                "offline_without_consent",
+                "`access_type=offline` without `prompt=consent` only yields \
+                 a refresh token on the user's first authorization",
+            ));
+        }
+
+        let mut seen = HashSet::new();
+        for scope in self.scopes() {
+            let scope = scope.as_str();
+            if !seen.insert(scope) {
+                issues.push(ValidationIssue::warning(
+                    "duplicate_scope",
+                    format!("scope `{scope}` is requested more than once"),
+                ));
+            } else if scope.parse::<GoogleScope>().is_err() {
+                // Scopes outside the catalogue may still be valid API URLs.
+                issues.push(if scope.starts_with("https://") {
+                    ValidationIssue::warning(
+                        "uncatalogued_scope",
+                        format!(
+                            "`{scope}` is not in the Google scope catalogue; \
+                             make sure the API is enabled for the project"
+                        ),
+                    )
+                } else {
+                    ValidationIssue::error(
+                        "unknown_scope",
+                        format!("`{scope}` is not a known Google scope"),
+                    )
+                });
+            }
+        }
+
+        if !self.has_openid_scope() {
+            if params.response_type != AuthorizationResponseType::Code
+                && params.response_type != AuthorizationResponseType::CodeToken
+            {
+                issues.push(ValidationIssue::error(
+                    "missing_openid_scope",
+                    "`response_type` includes `id_token`, which requires \
+                     the `openid` scope",
+                ));
+            } else {
+                issues.push(ValidationIssue::warning(
+                    "missing_openid_scope",
+                    "without the `openid` scope, no ID token is issued",
+                ));
+            }
+        }
+
+        ValidationReport(issues)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::authorization::RequestAccessTokenPayload;
+    use serde_json::json;
+
+    fn validate(payload: serde_json::Value) -> ValidationReport {
+        let payload: RequestAccessTokenPayload =
+            serde_json::from_value(payload).unwrap();
+        RequestAccessTokenConfig::from(&payload).validate()
+    }
+
+    fn codes(report: &ValidationReport) -> Vec<&str> {
+        report.issues().iter().map(ValidationIssue::code).collect()
+    }
+
+    #[test]
+    fn test_validate_collects_all_issues() {
+        let report = validate(json!({
+            "scope": "email calendar",
+            "prompt": "none,consent",
+            "access_type": "offline",
+        }));
+
+        assert!(!report.is_valid());
+        assert_eq!(
+            codes(&report),
+            [
+                "conflicting_prompt",
+                "unknown_scope",
+                "missing_openid_scope"
+            ]
+        );
+        assert_eq!(report.errors().count(), 2);
+    }
+
+    #[test]
+    fn test_validate_warns_about_offline_without_consent() {
+        let report = validate(json!({
+            "scope": "openid https://www.googleapis.com/auth/drive.file",
+            "access_type": "offline",
+        }));
+
+        assert!(report.is_valid());
+        assert_eq!(codes(&report), ["offline_without_consent"]);
+
+        let report = validate(json!({
+            "scope": "openid email",
+            "access_type": "offline",
+            "prompt": "consent",
+        }));
+        assert!(report.issues().is_empty());
+    }
+
+    #[test]
+    fn test_validate_warns_about_uncatalogued_scope() {
+        let report = validate(json!({
+            "scope": "openid https://www.googleapis.com/auth/unknown.api",
+        }));
+        assert!(report.is_valid());
+        assert_eq!(codes(&report), ["uncatalogued_scope"]);
+    }
+
+    #[test]
+    fn test_default_prompt_is_not_none() {
+        let report = validate(json!({ "scope": "openid email" }));
+        assert!(report.issues().is_empty());
+
+        let payload: crate::client::authorization::RequestAccessTokenPayload =
+            serde_json::from_value(json!({ "scope": "openid" })).unwrap();
+        let config = RequestAccessTokenConfig::from(&payload);
+        assert!(config.extra_params().prompt.0.is_empty());
+    }
+}
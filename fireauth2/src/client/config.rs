@@ -2,9 +2,12 @@ use base64::Engine;
 use oauth2::{AuthUrl, ClientId, ClientSecret, RevocationUrl, TokenUrl};
 use serde::Deserialize;
 
+use crate::client::google::GoogleOAuthClient;
+
+/// Credentials and endpoints of a Google OAuth 2.0 client.
 #[derive(Clone, Deserialize)]
 #[serde(rename_all = "snake_case")]
-pub struct GoogleOAuthWebClientConfig {
+pub struct GoogleOAuthClientSecrets {
     client_id: String,
     project_id: String,
     auth_uri: url::Url,
@@ -12,50 +15,95 @@ pub struct GoogleOAuthWebClientConfig {
     #[expect(unused)]
     auth_provider_x509_cert_url: String,
     client_secret: String,
+    #[serde(default)]
     #[expect(unused)]
-    redirect_uris: Vec<url::Url>,
+    redirect_uris: Vec<String>,
+    #[serde(default)]
     javascript_origins: Vec<url::Url>,
 }
 
+/// Google OAuth 2.0 client secrets, as downloaded from the Google Cloud
+/// console.
+///
+/// Both "Web application" (`web`) and "Desktop app" (`installed`) clients
+/// are supported.
 #[derive(Clone, Deserialize)]
-pub struct GoogleOAuthClientConfig {
-    web: GoogleOAuthWebClientConfig,
+#[serde(rename_all = "snake_case")]
+pub enum GoogleOAuthClientConfig {
+    /// A "Web application" client.
+    Web(GoogleOAuthClientSecrets),
+    /// A "Desktop app" client, used with the loopback redirect flow.
+    Installed(GoogleOAuthClientSecrets),
 }
 
 impl GoogleOAuthClientConfig {
     const CLIENT_CONFIG_VAR: &'static str = "GOOGLE_OAUTH_CLIENT_CONFIG";
     const REVOCATION_URL: &'static str = "https://oauth2.googleapis.com/revoke";
 
+    fn secrets(&self) -> &GoogleOAuthClientSecrets {
+        match self {
+            GoogleOAuthClientConfig::Web(secrets)
+            | GoogleOAuthClientConfig::Installed(secrets) => secrets,
+        }
+    }
+
+    /// Returns whether this is a "Desktop app" client.
+    pub fn is_installed(&self) -> bool {
+        matches!(self, GoogleOAuthClientConfig::Installed(_))
+    }
+
+    /// Returns the token endpoint.
     pub fn token_uri(&self) -> crate::Result<TokenUrl> {
-        let url = TokenUrl::new(self.web.token_uri.to_string())?;
+        let url = TokenUrl::new(self.secrets().token_uri.to_string())?;
         Ok(url)
     }
 
+    /// Returns the authorization endpoint.
     pub fn auth_uri(&self) -> crate::Result<AuthUrl> {
-        let url = AuthUrl::new(self.web.auth_uri.to_string())?;
+        let url = AuthUrl::new(self.secrets().auth_uri.to_string())?;
         Ok(url)
     }
 
+    /// Returns Google's token revocation endpoint.
     pub fn revocation_url() -> crate::Result<RevocationUrl> {
         let url = RevocationUrl::new(Self::REVOCATION_URL.to_owned())?;
         Ok(url)
     }
 
+    /// Returns the Google Cloud project ID.
     pub fn project_id(&self) -> &str {
-        &self.web.project_id
+        &self.secrets().project_id
     }
 
+    /// Returns the allowed JavaScript origins. Empty for installed clients.
     pub fn allowed_origins(&self) -> &Vec<url::Url> {
-        self.web.javascript_origins.as_ref()
+        self.secrets().javascript_origins.as_ref()
     }
 
+    /// Returns the OAuth client ID.
     pub fn client_id(&self) -> ClientId {
-        ClientId::new(self.web.client_id.clone())
+        ClientId::new(self.secrets().client_id.clone())
     }
 
     // (todo): Redact the secret to prevent accidentally exposing it
     pub(crate) fn client_secret(&self) -> ClientSecret {
-        ClientSecret::new(self.web.client_secret.clone())
+        ClientSecret::new(self.secrets().client_secret.clone())
+    }
+
+    /// Creates an `OAuth2` client for these secrets.
+    pub(crate) fn oauth_client(&self) -> crate::Result<GoogleOAuthClient> {
+        let client = oauth2::Client::new(self.client_id())
+            .set_auth_type(oauth2::AuthType::BasicAuth)
+            .set_token_uri(self.token_uri()?)
+            .set_auth_uri(self.auth_uri()?)
+            .set_client_secret(self.client_secret())
+            .set_revocation_url(Self::revocation_url()?);
+        Ok(client)
+    }
+
+    /// Parses client secrets JSON.
+    pub fn from_slice(json: &[u8]) -> crate::Result<Self> {
+        Ok(serde_json::from_slice(json)?)
     }
 
     /// Parses Google OAuth 2.0 JSON from an base64-encoded string
@@ -63,7 +111,6 @@ impl GoogleOAuthClientConfig {
         let encoded = std::env::var(Self::CLIENT_CONFIG_VAR)?;
         let decoded =
             base64::engine::general_purpose::STANDARD.decode(encoded)?;
-        let data: GoogleOAuthClientConfig = serde_json::from_slice(&decoded)?;
-        Ok(data)
+        Self::from_slice(&decoded)
     }
 }
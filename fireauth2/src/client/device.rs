@@ -0,0 +1,98 @@
+use super::authorization::ScopeList;
+
+use oauth2::{ClientId, ClientSecret};
+use serde::{Deserialize, Serialize};
+
+/// OAuth client used for the device authorization grant.
+///
+/// Google only issues device codes to clients of type
+/// "TVs and Limited Input devices", so this is separate from the web client.
+#[derive(Clone)]
+pub(crate) struct DeviceClient {
+    pub(crate) client_id: ClientId,
+    pub(crate) client_secret: ClientSecret,
+}
+
+/// JSON body starting a device authorization.
+#[derive(Debug, Clone, Deserialize)]
+pub struct DeviceAuthorizationPayload {
+    /// Scopes to authorize. May be omitted if a `profile` is given.
+    #[serde(default)]
+    pub scope: ScopeList,
+
+    /// Name of a server-side [`ScopeProfile`](super::authorization::ScopeProfile)
+    /// whose scopes are added to the request.
+    #[serde(default)]
+    pub profile: Option<String>,
+}
+
+/// Response of the device authorization endpoint ([RFC 8628, section 3.2]).
+///
+/// The device displays `user_code` and `verification_url` to the user and
+/// then polls for tokens using `device_code`.
+///
+/// [RFC 8628, section 3.2]: https://datatracker.ietf.org/doc/html/rfc8628#section-3.2
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all(serialize = "camelCase"))]
+pub struct DeviceAuthorizationResponse {
+    /// Code the device uses to poll for tokens.
+    pub device_code: String,
+    /// Code the user enters at `verification_url`.
+    pub user_code: String,
+    /// URL the user visits on another device.
+    #[serde(alias = "verification_uri")]
+    pub verification_url: String,
+    /// Lifetime of `device_code` and `user_code` in seconds.
+    pub expires_in: u64,
+    /// Minimum number of seconds between polling requests.
+    #[serde(default = "DeviceAuthorizationResponse::default_interval")]
+    pub interval: u64,
+}
+
+impl DeviceAuthorizationResponse {
+    const DEFAULT_INTERVAL: u64 = 5; // in seconds
+
+    fn default_interval() -> u64 {
+        Self::DEFAULT_INTERVAL
+    }
+}
+
+/// JSON body polling for the tokens of a device authorization.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeviceTokenPayload {
+    /// The `device_code` returned when starting the authorization.
+    pub device_code: String,
+}
+
+/// Outcome of a single device token poll.
+#[derive(Debug, Clone)]
+pub enum DeviceTokenPoll {
+    /// The user has not completed the authorization yet.
+    AuthorizationPending,
+    /// The device polls too fast and must increase its interval by 5 seconds.
+    SlowDown,
+    /// The user granted access. The refresh token, if any, has been stored.
+    Complete(super::authorization::ExchangeCodeResponse),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_device_authorization_response_accepts_rfc_field_names() {
+        let response: DeviceAuthorizationResponse =
+            serde_json::from_value(json!({
+                "device_code": "device-code",
+                "user_code": "GQVQ-JKEC",
+                "verification_uri": "https://www.google.com/device",
+                "expires_in": 1800
+            }))
+            .unwrap();
+
+        assert_eq!(response.verification_url, "https://www.google.com/device");
+        assert_eq!(response.interval, 5);
+    }
+}
@@ -0,0 +1,29 @@
+use serde::Deserialize;
+
+/// Form payload `POST`ed by Google Identity Services (One Tap or the
+/// "Sign In With Google" button) to the configured login URI.
+///
+/// See <https://developers.google.com/identity/gsi/web/reference/html-reference#server-side>.
+#[derive(Debug, Clone, Deserialize)]
+pub struct GisCredentialPayload {
+    /// The Google-issued ID token.
+    credential: String,
+
+    /// Double-submit CSRF token, also set as the `g_csrf_token` cookie.
+    g_csrf_token: String,
+}
+
+impl GisCredentialPayload {
+    /// Name of the cookie (and form field) carrying the double-submit CSRF token.
+    pub const CSRF_TOKEN_NAME: &'static str = "g_csrf_token";
+
+    /// Returns the Google-issued ID token.
+    pub fn credential(&self) -> &str {
+        &self.credential
+    }
+
+    /// Returns the CSRF token submitted in the form body.
+    pub fn csrf_token(&self) -> &str {
+        &self.g_csrf_token
+    }
+}
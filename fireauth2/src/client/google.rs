@@ -9,26 +9,28 @@ use serde::{Deserialize, Serialize};
 /// Represents additional fields returned in Google's `OAuth2` token response.
 ///
 /// Specifically, this struct captures the `id_token` field, which contains
-/// a JWT used to validate and extract user identity information.
+/// a JWT used to validate and extract user identity information. Google only
+/// returns it if the `openid` scope was requested.
 ///
 /// Implements `ExtraTokenFields` to integrate with the `oauth2` crate's token
 /// response deserialization.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GoogleOAuthExtraTokenFields {
-    /// The `OpenID` Connect ID token returned by Google.
-    id_token: String,
+    /// The `OpenID` Connect ID token returned by Google, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    id_token: Option<String>,
 }
 
 impl ExtraTokenFields for GoogleOAuthExtraTokenFields {}
 
 impl GoogleOAuthExtraTokenFields {
-    /// Returns a reference to the ID token string.
-    pub fn id_token(&self) -> &str {
-        &self.id_token
+    /// Returns a reference to the ID token string, if present.
+    pub fn id_token(&self) -> Option<&str> {
+        self.id_token.as_deref()
     }
 }
 
-/// Type alias for Google's token response which includes `id_token` as an extra field.
+/// Type alias for Google's token response which includes the optional `id_token` as an extra field.
 pub(crate) type GoogleOAuthTokenResponse =
     StandardTokenResponse<GoogleOAuthExtraTokenFields, BasicTokenType>;
 
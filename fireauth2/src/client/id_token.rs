@@ -0,0 +1,192 @@
+use std::time::Duration;
+
+use base64::Engine;
+use jsonwebtoken::Algorithm;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256, Sha384, Sha512};
+
+/// Claims of a verified Google ID token.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IdTokenClaims {
+    /// Issuer, `https://accounts.google.com` or `accounts.google.com`.
+    pub iss: String,
+    /// The client ID the token was issued to.
+    pub aud: String,
+    /// The Google user ID.
+    pub sub: String,
+    /// Expiration time (UNIX timestamp).
+    pub exp: i64,
+    /// Issued-at time (UNIX timestamp).
+    pub iat: i64,
+    /// Time the user last actively authenticated (UNIX timestamp), if
+    /// reported.
+    #[serde(default)]
+    pub auth_time: Option<i64>,
+    /// The client ID that requested the token, if different from `aud`.
+    #[serde(default)]
+    pub azp: Option<String>,
+    /// Hash of the access token issued alongside the ID token.
+    #[serde(default)]
+    pub at_hash: Option<String>,
+    /// The nonce sent with the authorization request.
+    #[serde(default)]
+    pub nonce: Option<String>,
+    /// The Google Workspace domain of the user, if any.
+    #[serde(default)]
+    pub hd: Option<String>,
+    /// The user's email address, if the `email` scope was granted.
+    #[serde(default)]
+    pub email: Option<String>,
+    /// Whether Google verified the email address.
+    #[serde(default)]
+    pub email_verified: Option<bool>,
+    /// The user's display name, if the `profile` scope was granted.
+    #[serde(default)]
+    pub name: Option<String>,
+    /// The user's given name.
+    #[serde(default)]
+    pub given_name: Option<String>,
+    /// The user's family name.
+    #[serde(default)]
+    pub family_name: Option<String>,
+    /// URL of the user's profile picture.
+    #[serde(default)]
+    pub picture: Option<String>,
+    /// The user's locale as a BCP 47 language tag.
+    #[serde(default)]
+    pub locale: Option<String>,
+}
+
+impl IdTokenClaims {
+    /// Ensures the user actively authenticated within `max_age`, allowing
+    /// for `leeway` of clock skew.
+    ///
+    /// Fails if the token carries no `auth_time`, which Google only reports
+    /// if `max_age` was sent with the authorization request.
+    pub fn ensure_authenticated_within(
+        &self,
+        max_age: Duration,
+        leeway: Duration,
+    ) -> crate::Result<()> {
+        let auth_time = self
+            .auth_time
+            .ok_or(crate::Error::AuthenticationTooOld { auth_time: None })?;
+
+        let elapsed = chrono::Utc::now().timestamp() - auth_time;
+        let allowed = max_age.saturating_add(leeway).as_secs();
+        if u64::try_from(elapsed).is_ok_and(|elapsed| elapsed > allowed) {
+            return Err(crate::Error::AuthenticationTooOld {
+                auth_time: Some(auth_time),
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Verifies that the ID token's `nonce` claim matches the nonce sent with
+/// the authorization request.
+pub(crate) fn verify_nonce(
+    claims: &IdTokenClaims,
+    expected: &str,
+) -> crate::Result<()> {
+    if claims.nonce.as_deref() == Some(expected) {
+        Ok(())
+    } else {
+        Err(crate::Error::NonceMismatch)
+    }
+}
+
+/// Verifies the ID token's `at_hash` claim against the access token issued
+/// alongside it.
+///
+/// `at_hash` is the base64url-encoded left half of the access token's hash,
+/// using the hash function of the ID token's signing algorithm.
+/// See <https://openid.net/specs/openid-connect-core-1_0.html#CodeIDToken>.
+pub(crate) fn verify_at_hash(
+    id_token: &str,
+    at_hash: &str,
+    access_token: &str,
+) -> crate::Result<()> {
+    let header = jsonwebtoken::decode_header(id_token)?;
+    let digest = match header.alg {
+        Algorithm::RS256 | Algorithm::PS256 | Algorithm::ES256 => {
+            Sha256::digest(access_token).to_vec()
+        }
+        Algorithm::RS384 | Algorithm::PS384 | Algorithm::ES384 => {
+            Sha384::digest(access_token).to_vec()
+        }
+        Algorithm::RS512 | Algorithm::PS512 => {
+            Sha512::digest(access_token).to_vec()
+        }
+        alg => {
+            return Err(crate::Error::InvalidToken {
+                because: format!("unsupported ID token algorithm {alg:?}"),
+            });
+        }
+    };
+
+    let expected = base64::engine::general_purpose::URL_SAFE_NO_PAD
+        .encode(&digest[..digest.len() / 2]);
+    if expected == at_hash {
+        Ok(())
+    } else {
+        Err(crate::Error::AccessTokenHashMismatch)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    // Header `{"alg":"RS256","typ":"JWT"}`, the signature is not checked.
+    const ID_TOKEN: &str =
+        "eyJhbGciOiJSUzI1NiIsInR5cCI6IkpXVCJ9.e30.c2lnbmF0dXJl";
+
+    #[test]
+    fn test_verify_at_hash() {
+        // Example from the OIDC Core 1.0 specification, appendix A.3.
+        let access_token = "jHkWEdUXMU1BwAsC4vtUsZwnNvTIxEl0z9K3vx5KF0Y";
+        let at_hash = "77QmUPtjPfzWtF2AnpK9RQ";
+
+        assert!(verify_at_hash(ID_TOKEN, at_hash, access_token).is_ok());
+        assert!(matches!(
+            verify_at_hash(ID_TOKEN, at_hash, "other-access-token"),
+            Err(crate::Error::AccessTokenHashMismatch)
+        ));
+    }
+
+    #[test]
+    fn test_ensure_authenticated_within() {
+        let now = chrono::Utc::now().timestamp();
+        let claims = |auth_time: Option<i64>| -> IdTokenClaims {
+            serde_json::from_value(json!({
+                "iss": "https://accounts.google.com",
+                "aud": "web-client",
+                "sub": "1234567890",
+                "iat": now,
+                "exp": now + 3600,
+                "auth_time": auth_time,
+            }))
+            .unwrap()
+        };
+        let max_age = Duration::from_secs(300);
+        let leeway = Duration::from_secs(60);
+
+        let recent = claims(Some(now - 330));
+        assert!(recent.ensure_authenticated_within(max_age, leeway).is_ok());
+
+        let stale = claims(Some(now - 400));
+        assert!(matches!(
+            stale.ensure_authenticated_within(max_age, leeway),
+            Err(crate::Error::AuthenticationTooOld { auth_time: Some(_) })
+        ));
+
+        let unknown = claims(None);
+        assert!(
+            unknown
+                .ensure_authenticated_within(max_age, leeway)
+                .is_err()
+        );
+    }
+}
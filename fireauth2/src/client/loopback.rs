@@ -0,0 +1,460 @@
+use super::authorization::RequestAccessTokenConfig;
+use super::config::GoogleOAuthClientConfig;
+use super::google::{GoogleOAuthClient, GoogleOAuthTokenResponse};
+
+use std::net::Ipv4Addr;
+use std::time::Duration;
+
+use oauth2::{
+    AuthorizationCode, PkceCodeVerifier, RedirectUrl, TokenResponse, reqwest,
+};
+use serde::Serialize;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use url::Url;
+
+/// Tokens returned by [`LoopbackFlow::run`].
+///
+/// Installed applications usually persist the refresh token themselves,
+/// so unlike the server-side flows it is returned to the caller.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LoopbackTokenResponse {
+    access_token: String,
+    refresh_token: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    id_token: Option<String>,
+    scope: Vec<String>,
+    issued_at: i64,
+    expires_in: u64,
+}
+
+impl LoopbackTokenResponse {
+    /// Returns the access token.
+    pub fn access_token(&self) -> &str {
+        &self.access_token
+    }
+
+    /// Returns the refresh token, if Google issued one.
+    pub fn refresh_token(&self) -> Option<&str> {
+        self.refresh_token.as_deref()
+    }
+
+    /// Returns the `OpenID` Connect ID token, if the `openid` scope was
+    /// granted.
+    pub fn id_token(&self) -> Option<&str> {
+        self.id_token.as_deref()
+    }
+
+    /// Returns the scopes granted by the user.
+    pub fn scope(&self) -> &[String] {
+        &self.scope
+    }
+
+    /// Returns the UNIX timestamp when the tokens were issued.
+    pub fn issued_at(&self) -> i64 {
+        self.issued_at
+    }
+
+    /// Returns the access token lifetime in seconds.
+    pub fn expires_in(&self) -> u64 {
+        self.expires_in
+    }
+}
+
+impl From<GoogleOAuthTokenResponse> for LoopbackTokenResponse {
+    fn from(value: GoogleOAuthTokenResponse) -> Self {
+        let scope = value
+            .scopes()
+            .map(|scopes| scopes.iter().map(|s| s.to_string()).collect())
+            .unwrap_or_default();
+        Self {
+            access_token: value.access_token().secret().to_owned(),
+            refresh_token: value
+                .refresh_token()
+                .map(|token| token.secret().to_owned()),
+            id_token: value.extra_fields().id_token().map(str::to_owned),
+            scope,
+            issued_at: chrono::Utc::now().timestamp(),
+            expires_in: value.expires_in().map_or(0, |d| d.as_secs()),
+        }
+    }
+}
+
+/// Authorization code flow for installed ("Desktop app") clients using a
+/// loopback redirect.
+///
+/// [`LoopbackFlow::run`] binds an ephemeral port on `127.0.0.1`, hands the
+/// authorization URL to the caller to open or print, receives the
+/// authorization code on the local listener and exchanges it using PKCE.
+///
+/// See <https://developers.google.com/identity/protocols/oauth2/native-app#redirect-uri_loopback>.
+pub struct LoopbackFlow {
+    client: GoogleOAuthClient,
+    http_client: reqwest::Client,
+    timeout: Duration,
+}
+
+impl LoopbackFlow {
+    const DEFAULT_TIMEOUT: Duration = Duration::from_secs(5 * 60);
+    const MAX_REQUEST_SIZE: usize = 8 * 1024;
+    const READ_TIMEOUT: Duration = Duration::from_secs(2);
+
+    /// Creates a loopback flow for the given installed client.
+    pub fn new(config: &GoogleOAuthClientConfig) -> crate::Result<Self> {
+        if !config.is_installed() {
+            return Err(crate::Error::LoopbackFlowFailed {
+                because: "the loopback flow requires an `installed` client"
+                    .into(),
+            });
+        }
+
+        // Explicitly disable redirects to avoid SSRF attack surface.
+        let http_client = reqwest::ClientBuilder::new()
+            .redirect(reqwest::redirect::Policy::none())
+            .build()?;
+
+        Ok(Self {
+            client: config.oauth_client()?,
+            http_client,
+            timeout: Self::DEFAULT_TIMEOUT,
+        })
+    }
+
+    /// Sets how long to wait for the user to complete the authorization.
+    ///
+    /// Defaults to 5 minutes.
+    #[must_use]
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Runs the flow and returns the issued tokens.
+    ///
+    /// `open` receives the authorization URL and is expected to open it in
+    /// the user's browser or print it.
+    pub async fn run(
+        &self,
+        config: &RequestAccessTokenConfig,
+        open: impl FnOnce(&Url),
+    ) -> crate::Result<LoopbackTokenResponse> {
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).await?;
+        let port = listener.local_addr()?.port();
+        let redirect_uri =
+            RedirectUrl::new(format!("http://127.0.0.1:{port}"))?;
+        let client = self.client.clone().set_redirect_uri(redirect_uri);
+
+        let request = config.authorize_url(&client);
+        open(request.url());
+
+        let state = request.csrf_token().secret();
+        let redirect = tokio::time::timeout(
+            self.timeout,
+            Self::receive_redirect(&listener, state),
+        )
+        .await
+        .map_err(|_| crate::Error::LoopbackFlowFailed {
+            because: "timed out waiting for the authorization response".into(),
+        })??;
+
+        if let Some(error) = redirect.error {
+            return Err(crate::Error::LoopbackFlowFailed { because: error });
+        }
+
+        let code = redirect.code.ok_or(crate::Error::LoopbackFlowFailed {
+            because: "authorization response is missing the code".into(),
+        })?;
+
+        let response = client
+            .exchange_code(AuthorizationCode::new(code))
+            .set_pkce_verifier(PkceCodeVerifier::new(
+                request.pkce_verifier().secret().clone(),
+            ))
+            .request_async(&self.http_client)
+            .await
+            .map_err(|err| crate::Error::TokenExchangeFailed {
+                because: err.to_string(),
+            })?;
+
+        Ok(LoopbackTokenResponse::from(response))
+    }
+
+    /// Accepts connections until one carries the authorization response for
+    /// `state`.
+    ///
+    /// Other requests, e.g. browser preconnects or requests of other local
+    /// processes, are answered or dropped without ending the flow.
+    async fn receive_redirect(
+        listener: &TcpListener,
+        state: &str,
+    ) -> crate::Result<LoopbackRedirect> {
+        loop {
+            let (stream, _) = listener.accept().await?;
+            match Self::handle_connection(stream, state).await {
+                Ok(Some(redirect)) => return Ok(redirect),
+                Ok(None) => {}
+                Err(err) => log::debug!("Ignoring loopback connection: {err}"),
+            }
+        }
+    }
+
+    /// Answers a single connection, returning the authorization response
+    /// for `state` if it carries one.
+    async fn handle_connection(
+        mut stream: TcpStream,
+        state: &str,
+    ) -> crate::Result<Option<LoopbackRedirect>> {
+        let redirect = tokio::time::timeout(
+            Self::READ_TIMEOUT,
+            Self::read_redirect(&mut stream),
+        )
+        .await
+        .map_err(|_| crate::Error::LoopbackFlowFailed {
+            because: "timed out reading the request".into(),
+        })??
+        .filter(|redirect| redirect.state.as_deref() == Some(state));
+
+        let (status, body) = match &redirect {
+            Some(redirect) if redirect.error.is_none() => (
+                "200 OK",
+                "Authorization complete. You can close this window.",
+            ),
+            Some(_) => ("200 OK", "Authorization failed."),
+            None => ("404 Not Found", "Not found."),
+        };
+        let response = format!(
+            "HTTP/1.1 {status}\r\nContent-Type: text/plain; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+            body.len()
+        );
+        // The response is only informative, so the authorization response
+        // is kept even if the browser went away.
+        let written = async {
+            stream.write_all(response.as_bytes()).await?;
+            stream.shutdown().await
+        };
+        if let Err(err) = written.await {
+            log::debug!("Failed to answer loopback request: {err}");
+        }
+
+        Ok(redirect)
+    }
+
+    /// Reads the request line of a redirect, returning `None` for requests
+    /// that are not an authorization response (e.g. `/favicon.ico`).
+    async fn read_redirect(
+        stream: &mut TcpStream,
+    ) -> crate::Result<Option<LoopbackRedirect>> {
+        let mut buf = Vec::new();
+        let mut chunk = [0; 1024];
+        while !buf.windows(4).any(|w| w == b"\r\n\r\n") {
+            let read = stream.read(&mut chunk).await?;
+            if read == 0 || buf.len() + read > Self::MAX_REQUEST_SIZE {
+                break;
+            }
+            buf.extend_from_slice(&chunk[..read]);
+        }
+
+        let request = String::from_utf8_lossy(&buf);
+        let Some(target) = request
+            .lines()
+            .next()
+            .and_then(|line| line.split_whitespace().nth(1))
+        else {
+            return Ok(None);
+        };
+
+        let url = Url::parse("http://127.0.0.1")?.join(target)?;
+        let mut redirect = LoopbackRedirect::default();
+        for (name, value) in url.query_pairs() {
+            match name.as_ref() {
+                "code" => redirect.code = Some(value.into_owned()),
+                "state" => redirect.state = Some(value.into_owned()),
+                "error" => redirect.error = Some(value.into_owned()),
+                _ => {}
+            }
+        }
+
+        if redirect.code.is_none() && redirect.error.is_none() {
+            return Ok(None);
+        }
+        Ok(Some(redirect))
+    }
+}
+
+#[derive(Debug, Default)]
+struct LoopbackRedirect {
+    code: Option<String>,
+    state: Option<String>,
+    error: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::authorization::RequestAccessTokenPayload;
+    use crate::testing::serve_json_once;
+    use serde_json::json;
+
+    fn token_response() -> serde_json::Value {
+        json!({
+            "access_token": "test-access-token",
+            "refresh_token": "test-refresh-token",
+            "id_token": "test-id-token",
+            "token_type": "Bearer",
+            "expires_in": 3599,
+            "scope": "openid email"
+        })
+    }
+
+    fn installed_config(token_uri: &str) -> GoogleOAuthClientConfig {
+        let json = json!({
+            "installed": {
+                "client_id": "test-client.apps.googleusercontent.com",
+                "project_id": "test-project",
+                "auth_uri": "https://accounts.google.com/o/oauth2/auth",
+                "token_uri": token_uri,
+                "auth_provider_x509_cert_url": "https://www.googleapis.com/oauth2/v1/certs",
+                "client_secret": "test-secret",
+                "redirect_uris": ["http://localhost"]
+            }
+        });
+        GoogleOAuthClientConfig::from_slice(json.to_string().as_bytes())
+            .unwrap()
+    }
+
+    fn request_config() -> RequestAccessTokenConfig {
+        let payload: RequestAccessTokenPayload =
+            serde_json::from_value(json!({ "scope": "openid email" })).unwrap();
+        RequestAccessTokenConfig::from(&payload)
+    }
+
+    /// Returns the loopback port and `state` of an authorization URL.
+    fn redirect_target(auth_url: &Url) -> (u16, String) {
+        let query = auth_url.query_pairs().collect::<Vec<_>>();
+        let param = |name: &str| {
+            query
+                .iter()
+                .find(|(key, _)| key == name)
+                .map(|(_, value)| value.to_string())
+                .unwrap()
+        };
+        let redirect_uri = Url::parse(&param("redirect_uri")).unwrap();
+        (redirect_uri.port().unwrap(), param("state"))
+    }
+
+    /// Sends a raw request line to the loopback listener and returns the
+    /// response.
+    async fn send(port: u16, target: &str) -> String {
+        let mut stream = TcpStream::connect((Ipv4Addr::LOCALHOST, port))
+            .await
+            .unwrap();
+        let request =
+            format!("GET {target} HTTP/1.1\r\nHost: 127.0.0.1\r\n\r\n");
+        stream.write_all(request.as_bytes()).await.unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        response
+    }
+
+    /// Simulates the browser following Google's redirect to the loopback
+    /// listener.
+    fn follow_redirect(auth_url: &Url) {
+        let (port, state) = redirect_target(auth_url);
+        tokio::spawn(async move {
+            let response =
+                send(port, &format!("/?state={state}&code=test-code")).await;
+            assert!(response.starts_with("HTTP/1.1 200"));
+        });
+    }
+
+    #[tokio::test]
+    async fn test_run_exchanges_code_received_on_loopback() {
+        let (base_url, request) = serve_json_once(token_response()).await;
+        let token_uri = format!("{base_url}/token");
+        let flow = LoopbackFlow::new(&installed_config(&token_uri)).unwrap();
+
+        let tokens =
+            flow.run(&request_config(), follow_redirect).await.unwrap();
+
+        assert_eq!(tokens.access_token(), "test-access-token");
+        assert_eq!(tokens.refresh_token(), Some("test-refresh-token"));
+        assert_eq!(tokens.scope(), ["openid", "email"]);
+
+        let request = request.await.unwrap();
+        assert!(request.contains("code=test-code"));
+        assert!(request.contains("code_verifier="));
+    }
+
+    #[tokio::test]
+    async fn test_run_survives_idle_preconnect() {
+        let (base_url, _) = serve_json_once(token_response()).await;
+        let token_uri = format!("{base_url}/token");
+        let flow = LoopbackFlow::new(&installed_config(&token_uri)).unwrap();
+
+        let tokens = flow
+            .run(&request_config(), |url| {
+                let (port, _) = redirect_target(url);
+                let url = url.clone();
+                tokio::spawn(async move {
+                    // Browsers open connections they may never send on.
+                    let _idle = TcpStream::connect((Ipv4Addr::LOCALHOST, port))
+                        .await
+                        .unwrap();
+                    follow_redirect(&url);
+                    tokio::time::sleep(Duration::from_secs(10)).await;
+                });
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(tokens.access_token(), "test-access-token");
+    }
+
+    #[tokio::test]
+    async fn test_run_ignores_stray_requests() {
+        let (base_url, request) = serve_json_once(token_response()).await;
+        let token_uri = format!("{base_url}/token");
+        let flow = LoopbackFlow::new(&installed_config(&token_uri)).unwrap();
+
+        let tokens = flow
+            .run(&request_config(), |url| {
+                let (port, _) = redirect_target(url);
+                let url = url.clone();
+                tokio::spawn(async move {
+                    let response =
+                        send(port, "/?state=forged&code=stray-code").await;
+                    assert!(response.starts_with("HTTP/1.1 404"));
+                    send(port, "http://[").await;
+                    follow_redirect(&url);
+                });
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(tokens.access_token(), "test-access-token");
+        assert!(request.await.unwrap().contains("code=test-code"));
+    }
+
+    #[tokio::test]
+    async fn test_run_times_out_on_state_mismatch() {
+        let config = installed_config("http://127.0.0.1:9/token");
+        let flow = LoopbackFlow::new(&config)
+            .unwrap()
+            .with_timeout(Duration::from_millis(500));
+
+        let result = flow
+            .run(&request_config(), |url| {
+                let (port, _) = redirect_target(url);
+                tokio::spawn(async move {
+                    send(port, "/?state=forged&code=test-code").await;
+                });
+            })
+            .await;
+
+        assert!(matches!(
+            result,
+            Err(crate::Error::LoopbackFlowFailed { .. })
+        ));
+    }
+}
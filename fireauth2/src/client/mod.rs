@@ -1,9 +1,22 @@
 pub(crate) mod authorization;
 pub(crate) mod config;
+pub(crate) mod device;
+pub(crate) mod gis;
 pub(crate) mod google;
+pub(crate) mod id_token;
 pub(crate) mod introspection;
+pub(crate) mod loopback;
 pub(crate) mod revocation;
+pub(crate) mod sign_in_policy;
+pub(crate) mod tokeninfo;
 
 pub use authorization::*;
+pub use config::{GoogleOAuthClientConfig, GoogleOAuthClientSecrets};
+pub use device::*;
+pub use gis::*;
+pub use id_token::IdTokenClaims;
 pub use introspection::*;
+pub use loopback::*;
 pub use revocation::*;
+pub use sign_in_policy::*;
+pub use tokeninfo::{GoogleIdentity, GoogleTokenInfo};
@@ -0,0 +1,349 @@
+use std::fmt;
+use std::sync::Arc;
+
+use serde::Deserialize;
+
+use super::tokeninfo::GoogleIdentity;
+
+/// Reason for why a [`SignInPolicy`] rejected a Google account.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SignInDenial {
+    /// The email address is missing or not verified by Google.
+    EmailNotVerified,
+    /// The email address is on the blocklist.
+    EmailBlocked,
+    /// The email domain is on the blocklist.
+    DomainBlocked,
+    /// Neither the email address nor its domain is allowlisted.
+    AccountNotAllowed,
+    /// The `hd` claim is missing or not one of the required hosted domains.
+    HostedDomainMismatch,
+    /// Rejected by a [`SignInCheck`], with a check-specific code.
+    Custom(String),
+}
+
+impl SignInDenial {
+    /// Stable, machine-readable code of the denial, e.g. `domain_blocked`.
+    ///
+    /// Used as the `error` of the redirect back to the client.
+    pub fn code(&self) -> &str {
+        match self {
+            Self::EmailNotVerified => "email_not_verified",
+            Self::EmailBlocked => "email_blocked",
+            Self::DomainBlocked => "domain_blocked",
+            Self::AccountNotAllowed => "account_not_allowed",
+            Self::HostedDomainMismatch => "hosted_domain_mismatch",
+            Self::Custom(code) => code,
+        }
+    }
+}
+
+impl fmt::Display for SignInDenial {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.code())
+    }
+}
+
+/// A custom, possibly asynchronous check run by a [`SignInPolicy`] after
+/// its built-in rules passed, e.g. a lookup in the application's user
+/// database.
+#[async_trait::async_trait]
+pub trait SignInCheck: Send + Sync {
+    /// Inspects the signed-in Google account.
+    ///
+    /// Return [`crate::Error::SignInDenied`] to reject the sign-in with a
+    /// stable code. Any other error aborts the sign-in as well.
+    async fn check(&self, identity: &GoogleIdentity) -> crate::Result<()>;
+}
+
+/// Restricts which Google accounts may complete a sign-in.
+///
+/// Evaluated after the ID token was validated and before any refresh token
+/// is persisted. Emails and domains are compared case-insensitively, and
+/// empty lists impose no restriction. Deserializes from camelCase JSON, e.g.
+/// `{"allowedDomains": ["example.com"], "requireVerifiedEmail": true}`.
+#[derive(Clone, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct SignInPolicy {
+    allowed_domains: Vec<String>,
+    blocked_domains: Vec<String>,
+    hosted_domains: Vec<String>,
+    allowed_emails: Vec<String>,
+    blocked_emails: Vec<String>,
+    require_verified_email: bool,
+    #[serde(skip)]
+    checks: Vec<Arc<dyn SignInCheck>>,
+}
+
+impl fmt::Debug for SignInPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SignInPolicy")
+            .field("allowed_domains", &self.allowed_domains)
+            .field("blocked_domains", &self.blocked_domains)
+            .field("hosted_domains", &self.hosted_domains)
+            .field("allowed_emails", &self.allowed_emails)
+            .field("blocked_emails", &self.blocked_emails)
+            .field("require_verified_email", &self.require_verified_email)
+            .field("checks", &self.checks.len())
+            .finish()
+    }
+}
+
+impl SignInPolicy {
+    /// Only admits email addresses of the given domains, unless the address
+    /// itself is allowlisted. Either way, the address must be verified by
+    /// Google.
+    #[must_use]
+    pub fn with_allowed_domains(
+        mut self,
+        domains: impl IntoIterator<Item = impl Into<String>>,
+    ) -> Self {
+        self.allowed_domains = domains.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Rejects email addresses of the given domains.
+    #[must_use]
+    pub fn with_blocked_domains(
+        mut self,
+        domains: impl IntoIterator<Item = impl Into<String>>,
+    ) -> Self {
+        self.blocked_domains = domains.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Requires the ID token's `hd` claim to be one of the given Google
+    /// Workspace domains.
+    #[must_use]
+    pub fn with_hosted_domains(
+        mut self,
+        domains: impl IntoIterator<Item = impl Into<String>>,
+    ) -> Self {
+        self.hosted_domains = domains.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Admits the given email addresses regardless of the domain lists, as
+    /// long as they are verified by Google.
+    #[must_use]
+    pub fn with_allowed_emails(
+        mut self,
+        emails: impl IntoIterator<Item = impl Into<String>>,
+    ) -> Self {
+        self.allowed_emails = emails.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Rejects the given email addresses.
+    #[must_use]
+    pub fn with_blocked_emails(
+        mut self,
+        emails: impl IntoIterator<Item = impl Into<String>>,
+    ) -> Self {
+        self.blocked_emails = emails.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Requires an email address verified by Google, even if no allowlist
+    /// applies.
+    #[must_use]
+    pub fn with_required_verified_email(mut self, required: bool) -> Self {
+        self.require_verified_email = required;
+        self
+    }
+
+    /// Adds a custom check, run in order after the built-in rules.
+    #[must_use]
+    pub fn with_check(mut self, check: Arc<dyn SignInCheck>) -> Self {
+        self.checks.push(check);
+        self
+    }
+
+    /// Evaluates the policy for the given Google account.
+    ///
+    /// Fails with [`crate::Error::SignInDenied`] if the account is rejected.
+    pub async fn evaluate(
+        &self,
+        identity: &GoogleIdentity,
+    ) -> crate::Result<()> {
+        self.evaluate_rules(identity)
+            .map_err(crate::Error::SignInDenied)?;
+
+        for check in &self.checks {
+            check.check(identity).await?;
+        }
+        Ok(())
+    }
+
+    fn evaluate_rules(
+        &self,
+        identity: &GoogleIdentity,
+    ) -> Result<(), SignInDenial> {
+        let email = identity.email().map(str::to_ascii_lowercase);
+
+        if self.require_verified_email
+            && (email.is_none() || identity.email_verified() != Some(true))
+        {
+            return Err(SignInDenial::EmailNotVerified);
+        }
+
+        if !self.hosted_domains.is_empty()
+            && !identity
+                .hosted_domain()
+                .is_some_and(|hd| contains(&self.hosted_domains, hd))
+        {
+            return Err(SignInDenial::HostedDomainMismatch);
+        }
+
+        let restricts_email = !self.allowed_emails.is_empty()
+            || !self.blocked_emails.is_empty()
+            || !self.allowed_domains.is_empty()
+            || !self.blocked_domains.is_empty();
+        if !restricts_email {
+            return Ok(());
+        }
+
+        let Some(email) = email else {
+            return Err(SignInDenial::AccountNotAllowed);
+        };
+        if contains(&self.blocked_emails, &email) {
+            return Err(SignInDenial::EmailBlocked);
+        }
+        // Anyone can put an address they do not own on an unverified
+        // account, so only verified addresses are admitted by allowlists.
+        let admit = || match identity.email_verified() {
+            Some(true) => Ok(()),
+            _ => Err(SignInDenial::EmailNotVerified),
+        };
+        if contains(&self.allowed_emails, &email) {
+            return admit();
+        }
+
+        let domain = email.rsplit_once('@').map_or("", |(_, domain)| domain);
+        if contains(&self.blocked_domains, domain) {
+            return Err(SignInDenial::DomainBlocked);
+        }
+        if !self.allowed_domains.is_empty() {
+            if !contains(&self.allowed_domains, domain) {
+                return Err(SignInDenial::AccountNotAllowed);
+            }
+            return admit();
+        }
+        if !self.allowed_emails.is_empty() {
+            return Err(SignInDenial::AccountNotAllowed);
+        }
+
+        Ok(())
+    }
+}
+
+fn contains(values: &[String], value: &str) -> bool {
+    values.iter().any(|v| v.eq_ignore_ascii_case(value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn identity(email: &str, hd: Option<&str>) -> GoogleIdentity {
+        GoogleIdentity {
+            sub: "1234567890".into(),
+            email: Some(email.into()),
+            email_verified: Some(true),
+            hd: hd.map(Into::into),
+            aud: "client-id".into(),
+            azp: None,
+            auth_time: None,
+        }
+    }
+
+    fn denial(policy: &SignInPolicy, identity: &GoogleIdentity) -> String {
+        policy
+            .evaluate_rules(identity)
+            .map_or_else(|denial| denial.code().to_owned(), |()| "ok".into())
+    }
+
+    #[test]
+    fn test_evaluate_rules() {
+        let policy: SignInPolicy = serde_json::from_value(serde_json::json!({
+            "allowedDomains": ["example.com"],
+            "blockedEmails": ["mallory@example.com"],
+            "allowedEmails": ["Contractor@Gmail.com"],
+            "requireVerifiedEmail": true,
+        }))
+        .unwrap();
+
+        let cases = [
+            ("alice@EXAMPLE.com", "ok"),
+            ("mallory@example.com", "email_blocked"),
+            ("contractor@gmail.com", "ok"),
+            ("bob@gmail.com", "account_not_allowed"),
+        ];
+        for (email, expected) in cases {
+            assert_eq!(denial(&policy, &identity(email, None)), expected);
+        }
+
+        let mut unverified = identity("alice@example.com", None);
+        unverified.email_verified = Some(false);
+        assert_eq!(denial(&policy, &unverified), "email_not_verified");
+
+        // Allowlists only admit verified addresses, even without
+        // `requireVerifiedEmail`.
+        let policy = SignInPolicy::default()
+            .with_allowed_domains(["example.com"])
+            .with_allowed_emails(["contractor@gmail.com"]);
+        for email in ["alice@example.com", "contractor@gmail.com"] {
+            let mut unverified = identity(email, None);
+            unverified.email_verified = None;
+            assert_eq!(denial(&policy, &unverified), "email_not_verified");
+            unverified.email_verified = Some(false);
+            assert_eq!(denial(&policy, &unverified), "email_not_verified");
+        }
+        let mut unverified = identity("bob@gmail.com", None);
+        unverified.email_verified = Some(false);
+        assert_eq!(denial(&policy, &unverified), "account_not_allowed");
+
+        // Blocklists alone admit unverified addresses.
+        let policy =
+            SignInPolicy::default().with_blocked_domains(["example.org"]);
+        assert_eq!(denial(&policy, &unverified), "ok");
+
+        let policy = SignInPolicy::default()
+            .with_hosted_domains(["example.com"])
+            .with_blocked_domains(["example.org"]);
+        let cases = [
+            (identity("alice@example.com", Some("example.com")), "ok"),
+            (identity("bob@gmail.com", None), "hosted_domain_mismatch"),
+            (
+                identity("eve@example.org", Some("example.com")),
+                "domain_blocked",
+            ),
+        ];
+        for (identity, expected) in cases {
+            assert_eq!(denial(&policy, &identity), expected);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_evaluate_runs_custom_checks() {
+        struct DenyAll;
+
+        #[async_trait::async_trait]
+        impl SignInCheck for DenyAll {
+            async fn check(&self, _: &GoogleIdentity) -> crate::Result<()> {
+                Err(crate::Error::SignInDenied(SignInDenial::Custom(
+                    "unknown_user".into(),
+                )))
+            }
+        }
+
+        let policy = SignInPolicy::default().with_check(Arc::new(DenyAll));
+        let result =
+            policy.evaluate(&identity("alice@example.com", None)).await;
+        assert!(matches!(
+            result,
+            Err(crate::Error::SignInDenied(denial))
+                if denial.code() == "unknown_user"
+        ));
+    }
+}
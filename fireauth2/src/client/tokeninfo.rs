@@ -0,0 +1,182 @@
+use super::id_token::IdTokenClaims;
+
+use serde::{Deserialize, Deserializer};
+
+/// Access token metadata returned by Google's `tokeninfo` endpoint.
+///
+/// Used to identify grants without an ID token, i.e. when the `openid`
+/// scope was not requested. `sub` and `email` are only reported if the
+/// token carries a scope that identifies the user (`openid`, `email` or
+/// `profile`).
+#[derive(Debug, Clone, Deserialize)]
+pub struct GoogleTokenInfo {
+    /// The client ID the token was issued to.
+    pub aud: String,
+    /// The client ID that requested the token, if different from `aud`.
+    #[serde(default)]
+    pub azp: Option<String>,
+    /// The Google user ID, if the granted scopes identify the user.
+    #[serde(default)]
+    pub sub: Option<String>,
+    /// The user's email address, if the `email` scope was granted.
+    #[serde(default)]
+    pub email: Option<String>,
+    /// Whether Google verified the email address.
+    #[serde(default, deserialize_with = "deserialize_email_verified")]
+    pub email_verified: Option<bool>,
+    /// The scopes granted to the token.
+    #[serde(default, deserialize_with = "deserialize_scope")]
+    pub scope: Vec<String>,
+}
+
+fn deserialize_scope<'de, D>(deserializer: D) -> Result<Vec<String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let scope = String::deserialize(deserializer)?;
+    Ok(scope.split_whitespace().map(str::to_owned).collect())
+}
+
+/// `tokeninfo` reports `email_verified` as a string.
+fn deserialize_email_verified<'de, D>(
+    deserializer: D,
+) -> Result<Option<bool>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum BoolOrString {
+        Bool(bool),
+        String(String),
+    }
+
+    Ok(match BoolOrString::deserialize(deserializer)? {
+        BoolOrString::Bool(value) => Some(value),
+        BoolOrString::String(value) => value.parse().ok(),
+    })
+}
+
+/// The Google user a grant was issued for, resolved either from the ID
+/// token or from `tokeninfo`.
+#[derive(Debug, Clone)]
+pub struct GoogleIdentity {
+    pub(crate) sub: String,
+    pub(crate) email: Option<String>,
+    pub(crate) email_verified: Option<bool>,
+    pub(crate) hd: Option<String>,
+    pub(crate) aud: String,
+    pub(crate) azp: Option<String>,
+    pub(crate) auth_time: Option<i64>,
+}
+
+impl GoogleIdentity {
+    /// Returns the Google user ID.
+    pub fn sub(&self) -> &str {
+        &self.sub
+    }
+
+    /// Returns the user's email address, if known.
+    pub fn email(&self) -> Option<&str> {
+        self.email.as_deref()
+    }
+
+    /// Returns whether Google verified the email address, if known.
+    pub fn email_verified(&self) -> Option<bool> {
+        self.email_verified
+    }
+
+    /// Returns the user's Google Workspace domain (`hd`), if known.
+    ///
+    /// Only reported in ID tokens.
+    pub fn hosted_domain(&self) -> Option<&str> {
+        self.hd.as_deref()
+    }
+
+    /// Returns the client ID the tokens were issued to.
+    pub fn audience(&self) -> &str {
+        &self.aud
+    }
+
+    /// Returns the client ID that requested the tokens.
+    pub fn authorized_party(&self) -> Option<&str> {
+        self.azp.as_deref()
+    }
+
+    /// Returns when the user last actively authenticated with Google.
+    ///
+    /// Only reported in ID tokens of requests sending `max_age`.
+    pub fn auth_time(&self) -> Option<i64> {
+        self.auth_time
+    }
+}
+
+impl From<IdTokenClaims> for GoogleIdentity {
+    fn from(claims: IdTokenClaims) -> Self {
+        Self {
+            sub: claims.sub,
+            email: claims.email,
+            email_verified: claims.email_verified,
+            hd: claims.hd,
+            azp: claims.azp.or_else(|| Some(claims.aud.clone())),
+            aud: claims.aud,
+            auth_time: claims.auth_time,
+        }
+    }
+}
+
+impl TryFrom<GoogleTokenInfo> for GoogleIdentity {
+    type Error = crate::Error;
+
+    fn try_from(info: GoogleTokenInfo) -> crate::Result<Self> {
+        let sub = info.sub.ok_or_else(|| crate::Error::InvalidToken {
+            because: "the granted scopes do not identify the Google user; \
+                      request `openid`, `email` or `profile`"
+                .into(),
+        })?;
+
+        Ok(Self {
+            sub,
+            email: info.email,
+            email_verified: info.email_verified,
+            hd: None,
+            azp: info.azp.or_else(|| Some(info.aud.clone())),
+            aud: info.aud,
+            auth_time: None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_identity_requires_identifying_scope() {
+        let info: GoogleTokenInfo = serde_json::from_value(json!({
+            "aud": "web-client",
+            "scope": "https://www.googleapis.com/auth/drive.readonly",
+            "expires_in": "3599"
+        }))
+        .unwrap();
+        assert_eq!(
+            info.scope,
+            ["https://www.googleapis.com/auth/drive.readonly"]
+        );
+        assert!(GoogleIdentity::try_from(info).is_err());
+
+        let info: GoogleTokenInfo = serde_json::from_value(json!({
+            "aud": "web-client",
+            "sub": "1234567890",
+            "email": "user@example.com",
+            "email_verified": "true",
+            "scope": "openid https://www.googleapis.com/auth/userinfo.email"
+        }))
+        .unwrap();
+        let identity = GoogleIdentity::try_from(info).unwrap();
+        assert_eq!(identity.sub, "1234567890");
+        assert_eq!(identity.azp.as_deref(), Some("web-client"));
+        assert_eq!(identity.email_verified(), Some(true));
+    }
+}
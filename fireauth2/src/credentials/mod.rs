@@ -0,0 +1,119 @@
+mod service_account;
+
+pub use service_account::*;
+
+use chrono::{DateTime, TimeDelta, Utc};
+use tokio::sync::Mutex;
+
+use crate::fireauth::FireAuthClient;
+
+/// A Google access token and its expiry.
+#[derive(Debug, Clone)]
+pub struct AccessToken {
+    token: String,
+    expires_at: DateTime<Utc>,
+}
+
+impl AccessToken {
+    /// Tokens expiring within this margin are treated as expired.
+    const EXPIRY_MARGIN: TimeDelta = TimeDelta::seconds(60);
+
+    /// Creates a token that expires `expires_in` seconds from now.
+    pub fn new(token: impl Into<String>, expires_in: u64) -> Self {
+        let expires_in = i64::try_from(expires_in).unwrap_or(i64::MAX);
+        Self {
+            token: token.into(),
+            expires_at: Utc::now() + TimeDelta::seconds(expires_in),
+        }
+    }
+
+    /// Returns the access token, e.g. for an `Authorization: Bearer` header.
+    pub fn token(&self) -> &str {
+        &self.token
+    }
+
+    /// Returns when the token expires.
+    pub fn expires_at(&self) -> DateTime<Utc> {
+        self.expires_at
+    }
+
+    /// Returns whether the token is expired or about to expire.
+    pub fn is_expired(&self) -> bool {
+        self.expires_at - Self::EXPIRY_MARGIN <= Utc::now()
+    }
+}
+
+/// Source of Google access tokens for calling Google APIs.
+///
+/// Implemented for stored user grants ([`GoogleUserTokenSource`]) and
+/// service accounts ([`ServiceAccountTokenSource`]), so callers can mix both.
+#[async_trait::async_trait]
+pub trait AccessTokenSource: Send + Sync {
+    /// Returns a valid access token, fetching a new one if necessary.
+    async fn access_token(&self) -> crate::Result<AccessToken>;
+}
+
+/// Caches an access token until shortly before it expires.
+pub(crate) struct AccessTokenCache {
+    token: Mutex<Option<AccessToken>>,
+}
+
+impl AccessTokenCache {
+    pub(crate) fn new() -> Self {
+        Self {
+            token: Mutex::new(None),
+        }
+    }
+
+    /// Returns the cached token, calling `fetch` if it is missing or expired.
+    pub(crate) async fn get<F>(&self, fetch: F) -> crate::Result<AccessToken>
+    where
+        F: Future<Output = crate::Result<AccessToken>>,
+    {
+        let mut token = self.token.lock().await;
+        if let Some(token) = token.as_ref().filter(|t| !t.is_expired()) {
+            return Ok(token.clone());
+        }
+
+        let fetched = fetch.await?;
+        *token = Some(fetched.clone());
+        Ok(fetched)
+    }
+}
+
+/// Access tokens of a Google user, obtained with the refresh token stored
+/// by [`FireAuthClient`].
+pub struct GoogleUserTokenSource {
+    client: FireAuthClient,
+    google_user_id: String,
+    cache: AccessTokenCache,
+}
+
+impl GoogleUserTokenSource {
+    /// Creates a token source for the given Google user.
+    pub fn new(
+        client: FireAuthClient,
+        google_user_id: impl Into<String>,
+    ) -> Self {
+        Self {
+            client,
+            google_user_id: google_user_id.into(),
+            cache: AccessTokenCache::new(),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl AccessTokenSource for GoogleUserTokenSource {
+    async fn access_token(&self) -> crate::Result<AccessToken> {
+        self.cache
+            .get(async {
+                let response = self
+                    .client
+                    .exchange_refresh_token(&self.google_user_id)
+                    .await?;
+                Ok(AccessToken::new(response.access_token, response.expires_in))
+            })
+            .await
+    }
+}
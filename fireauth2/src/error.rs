@@ -14,6 +14,10 @@ pub enum Error {
     #[error(transparent)]
     Env(#[from] std::env::VarError),
 
+    /// I/O operation failed.
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+
     /// Error decoding Base64 strings.
     #[error(transparent)]
     Base64(#[from] base64::DecodeError),
@@ -28,9 +32,88 @@ pub enum Error {
     Http(#[from] oauth2::reqwest::Error),
 
     // --- OAuth-specific Errors ---
-    /// Token verification error from Google OAuth.
+    /// JWT decoding or validation error.
+    #[error(transparent)]
+    Jwt(#[from] jsonwebtoken::errors::Error),
+
+    /// Invalid regular expression.
     #[error(transparent)]
-    TokenVerification(#[from] google_oauth::Error),
+    Regex(#[from] regex::Error),
+
+    /// A JWT is structurally valid but cannot be accepted.
+    #[error("Invalid token: {because}")]
+    InvalidToken {
+        /// The reason for why the token was rejected.
+        because: String,
+    },
+
+    /// The ID token's `nonce` does not match the authorization request.
+    #[error("ID token nonce does not match the authorization request")]
+    NonceMismatch,
+
+    /// The ID token's `at_hash` does not match the access token.
+    #[error("ID token `at_hash` does not match the access token")]
+    AccessTokenHashMismatch,
+
+    /// The ID token was requested by a client other than the web client.
+    #[error("ID token was authorized by unexpected client `{0}`")]
+    AuthorizedPartyMismatch(String),
+
+    /// The user did not authenticate recently enough.
+    #[error("User authentication is too old or its time is unknown")]
+    AuthenticationTooOld {
+        /// Time the user last authenticated (UNIX timestamp), if known.
+        auth_time: Option<i64>,
+    },
+
+    /// The Google account was rejected by the [`crate::SignInPolicy`].
+    #[error("Sign-in denied: {0}")]
+    SignInDenied(crate::SignInDenial),
+
+    /// The user did not grant all required scopes and
+    /// [`crate::PartialConsentPolicy::Fail`] applies. Carries the missing
+    /// required scopes.
+    #[error("Requested scopes were not granted: {0}")]
+    ScopesNotGranted(crate::ScopeSet),
+
+    /// No public key matches the `kid` of a JWT.
+    #[error("No signing key found for kid `{0}`")]
+    SigningKeyNotFound(String),
+
+    /// Device authorization was denied, expired or failed.
+    #[error("Device authorization failed: {because}")]
+    DeviceAuthorizationFailed {
+        /// The reason for why the device authorization failed.
+        because: String,
+    },
+
+    /// The loopback authorization flow failed.
+    #[error("Loopback flow failed: {because}")]
+    LoopbackFlowFailed {
+        /// The reason for why the loopback flow failed.
+        because: String,
+    },
+
+    /// Fetching or validating an OIDC discovery document failed.
+    #[error("Provider discovery failed: {because}")]
+    ProviderDiscoveryFailed {
+        /// The reason for why discovery failed.
+        because: String,
+    },
+
+    /// No identity provider is configured under the given ID.
+    #[error("Unknown identity provider `{0}`")]
+    UnknownProvider(String),
+
+    /// An identity provider is already registered under the given ID or
+    /// storage namespace.
+    #[error("Identity provider `{0}` is already registered")]
+    DuplicateProvider(String),
+
+    /// The given provider ID or its storage namespace is reserved for
+    /// Google grants.
+    #[error("Identity provider ID `{0}` is reserved")]
+    ReservedProviderId(String),
 
     /// `OAuth2` configuration error.
     #[error(transparent)]
@@ -41,6 +124,13 @@ pub enum Error {
     #[error(transparent)]
     Firestore(#[from] firestore::errors::FirestoreError),
 
+    /// Firebase Admin (Identity Toolkit) API request failed.
+    #[error("Firebase Admin API request failed: {because}")]
+    FirebaseAdmin {
+        /// The reason for why the request failed.
+        because: String,
+    },
+
     /// No Google user found in the expected context.
     #[error("No Google user found")]
     UserNotFound,
@@ -64,6 +154,53 @@ pub enum Error {
         because: String,
     },
 
+    /// Provided authorization parameter value is invalid.
+    #[error("Invalid `{name}` value: {value}")]
+    InvalidExtraParamValue {
+        /// The parameter name.
+        name: String,
+        /// The rejected value.
+        value: String,
+    },
+
+    /// Custom authorization parameter is reserved or not allowlisted.
+    #[error("Authorization parameter `{0}` is not allowed")]
+    DisallowedExtraParam(String),
+
+    /// The authorization request lacks the `openid` scope required by
+    /// [`crate::OpenIdScopePolicy::Require`].
+    #[error("The `openid` scope is required")]
+    OpenIdScopeRequired,
+
+    /// A raw scope is not permitted by the [`crate::ScopePolicy`].
+    #[error("Scope `{0}` is not allowed")]
+    DisallowedScope(String),
+
+    /// The scope is not part of the [`crate::GoogleScope`] catalogue.
+    #[error("Unknown Google scope `{0}`")]
+    UnknownGoogleScope(String),
+
+    /// No [`crate::ScopeProfile`] is configured under the given name.
+    #[error("Unknown scope profile `{0}`")]
+    UnknownScopeProfile(String),
+
+    /// The authorization request names neither a scope nor a scope profile.
+    #[error("No scope or scope profile requested")]
+    MissingScope,
+
+    /// Invalid `openid` scope policy.
+    #[error("Invalid openid scope policy: {0}")]
+    InvalidOpenIdScopePolicy(String),
+
+    /// The authorization request failed validation with
+    /// [`crate::RequestValidationMode::Reject`].
+    #[error("Invalid authorization request: {0}")]
+    InvalidAuthorizationRequest(crate::ValidationReport),
+
+    /// Invalid request validation mode.
+    #[error("Invalid request validation mode: {0}")]
+    InvalidRequestValidationMode(String),
+
     /// Required configuration field is missing.
     #[error("Missing required config field `{0}`")]
     MissingConfigField(&'static str),
@@ -1,26 +1,103 @@
+use crate::admin::ScopeClaimsSync;
 use crate::client::authorization::{
-    AuthorizationResponse, ExchangeAuthorizationCodeConfig,
-    ExchangeRefreshTokenResponse, RequestAccessTokenConfig,
-    RequestAccessTokenResponse, ToExtraParams,
+    AuthorizationResponse, CustomParamsAllowlist,
+    ExchangeAuthorizationCodeConfig, ExchangeCodeConfig, ExchangeCodeResponse,
+    ExchangeRefreshTokenResponse, MaxAge, OpenIdScopePolicy,
+    PartialConsentPolicy, RequestAccessTokenConfig, RequestAccessTokenResponse,
+    RequestValidationMode, ScopePolicy, ScopeSet, ToExtraParams,
 };
 use crate::client::config::GoogleOAuthClientConfig;
+use crate::client::device::{
+    DeviceAuthorizationPayload, DeviceAuthorizationResponse, DeviceClient,
+    DeviceTokenPoll,
+};
+use crate::client::id_token;
+use crate::client::id_token::IdTokenClaims;
 use crate::client::revocation::TokenRevocationConfig;
+use crate::client::sign_in_policy::SignInPolicy;
+use crate::client::tokeninfo::{GoogleIdentity, GoogleTokenInfo};
+use crate::keys::{JwksKeySource, KeySource, verify_jwt};
 use crate::models::GoogleUser;
+use crate::oidc::{OidcProvider, OidcProviderConfig};
+use crate::providers::{Provider, ProviderClient};
 use crate::repositories::GoogleUserRepository;
 
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
 use firestore::FirestoreDb;
-use google_oauth::{GoogleAccessTokenPayload, GooglePayload};
+use jsonwebtoken::{Algorithm, Validation};
+use oauth2::basic::BasicErrorResponseType;
 use oauth2::{
-    AuthType, Client, CsrfToken, PkceCodeChallenge, RedirectUrl, RefreshToken,
-    StandardRevocableToken, TokenResponse, reqwest,
+    AuthorizationCode, ClientId, ClientSecret, RedirectUrl, RefreshToken,
+    RequestTokenError, Scope, StandardRevocableToken, TokenResponse, reqwest,
 };
 
+/// Redirect URI Google Identity Services popup-mode codes are issued for.
+const POSTMESSAGE_REDIRECT_URI: &str = "postmessage";
+
+/// Google's public keys for verifying ID tokens.
+const GOOGLE_JWKS_URL: &str = "https://www.googleapis.com/oauth2/v3/certs";
+
+/// Issuers of Google ID tokens.
+const GOOGLE_ISSUERS: [&str; 2] =
+    ["https://accounts.google.com", "accounts.google.com"];
+
+/// Default clock skew tolerated when validating ID tokens.
+const DEFAULT_CLOCK_SKEW: Duration = Duration::from_secs(60);
+
+/// Google's endpoint for inspecting access tokens.
+const TOKENINFO_URL: &str = "https://oauth2.googleapis.com/tokeninfo";
+
+/// Google's device authorization endpoint.
+const DEVICE_AUTHORIZATION_URL: &str =
+    "https://oauth2.googleapis.com/device/code";
+
+/// Grant type for polling device authorizations.
+const DEVICE_CODE_GRANT_TYPE: &str =
+    "urn:ietf:params:oauth:grant-type:device_code";
+
+/// Interval increase required after a `slow_down` response.
+const DEVICE_SLOW_DOWN_INCREMENT: Duration = Duration::from_secs(5);
+
+/// Provider ID reserved for Google itself.
+const RESERVED_PROVIDER_ID: &str = "google";
+
+/// Firestore collection of grants issued to the device client.
+const DEVICE_GRANTS_COLLECTION: &str = "googleDeviceUsers";
+
 type FireAuthClientInner = crate::client::google::GoogleOAuthClient;
 
 /// Type alias for Google's token response which includes `id_token` as an extra field.
 pub type FireAuthTokenResponse =
     crate::client::google::GoogleOAuthTokenResponse;
 
+/// OAuth client a grant was issued to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum GrantClient {
+    /// The web client, whose grants are stored in the main collection.
+    Web,
+    /// The device client, whose grants are stored apart from web grants so
+    /// that each refresh token is only refreshed by the client it was issued
+    /// to.
+    Device,
+}
+
+/// How a code exchange relates to previous grants of the same user.
+struct GrantContext<'a> {
+    /// Client the grant was issued to.
+    client: GrantClient,
+    /// Scopes sent with the authorization request, if known.
+    requested_scopes: &'a [Scope],
+    /// Whether the grant includes previously granted scopes.
+    include_granted_scopes: bool,
+    /// Whether previously stored tokens are revoked.
+    revoke_existing_tokens: bool,
+    /// When the user actively authenticated, if verified against `max_age`.
+    authenticated_at: Option<i64>,
+}
+
 /// A high-level `OAuth2` client tailored for Google, with support for ID token verification
 /// and Firebase compatibility.
 #[derive(Clone)]
@@ -28,8 +105,22 @@ pub struct FireAuthClient {
     client: FireAuthClientInner,
     config: GoogleOAuthClientConfig,
     http_client: reqwest::Client,
+    audiences: Vec<String>,
+    authorized_parties: Vec<String>,
+    clock_skew: Duration,
+    custom_params_allowlist: CustomParamsAllowlist,
+    device_authorization_url: String,
+    device_client: Option<DeviceClient>,
+    id_token_keys: Arc<dyn KeySource>,
+    openid_policy: OpenIdScopePolicy,
+    platform_client_ids: Vec<String>,
+    providers: HashMap<String, ProviderClient>,
     repository: GoogleUserRepository,
-    token_verifier: google_oauth::AsyncClient,
+    request_validation: RequestValidationMode,
+    scope_claims_sync: Option<ScopeClaimsSync>,
+    scope_policy: ScopePolicy,
+    sign_in_policy: SignInPolicy,
+    tokeninfo_url: String,
 }
 
 impl FireAuthClient {
@@ -37,30 +128,44 @@ impl FireAuthClient {
     /// Verifies configuration presence and sets up the internal OAuth client and verifier.
     pub async fn new() -> crate::Result<Self> {
         let config = GoogleOAuthClientConfig::from_env()?;
-        let client_id = config.client_id();
-
-        let token_verifier = google_oauth::AsyncClient::new(client_id.as_str());
+        let firestore = FirestoreDb::new(config.project_id()).await?;
+        let repository = GoogleUserRepository::new(firestore, "googleUsers");
+        Self::with_repository(config, repository)
+    }
 
-        let client = Client::new(client_id)
-            .set_auth_type(AuthType::BasicAuth)
-            .set_token_uri(config.token_uri()?)
-            .set_auth_uri(config.auth_uri()?)
-            .set_client_secret(config.client_secret())
-            .set_revocation_url(GoogleOAuthClientConfig::revocation_url()?);
+    /// Creates a client for the given configuration, persisting Google user
+    /// grants in `repository`.
+    fn with_repository(
+        config: GoogleOAuthClientConfig,
+        repository: GoogleUserRepository,
+    ) -> crate::Result<Self> {
+        let client = config.oauth_client()?;
 
         // Explicitly disable redirects to avoid SSRF attack surface.
         let http_client = reqwest::ClientBuilder::new()
             .redirect(reqwest::redirect::Policy::none())
             .build()?;
-        let firestore = FirestoreDb::new(config.project_id()).await?;
-        let repository = GoogleUserRepository::new(firestore, "googleUsers");
 
         Ok(Self {
             client,
             config,
             http_client,
+            audiences: Vec::new(),
+            authorized_parties: Vec::new(),
+            clock_skew: DEFAULT_CLOCK_SKEW,
+            custom_params_allowlist: CustomParamsAllowlist::default(),
+            device_authorization_url: DEVICE_AUTHORIZATION_URL.to_owned(),
+            device_client: None,
+            id_token_keys: Arc::new(JwksKeySource::new(GOOGLE_JWKS_URL)?),
+            openid_policy: OpenIdScopePolicy::default(),
+            platform_client_ids: Vec::new(),
+            providers: HashMap::new(),
             repository,
-            token_verifier,
+            request_validation: RequestValidationMode::default(),
+            scope_claims_sync: None,
+            scope_policy: ScopePolicy::default(),
+            sign_in_policy: SignInPolicy::default(),
+            tokeninfo_url: TOKENINFO_URL.to_owned(),
         })
     }
 
@@ -88,28 +193,111 @@ impl FireAuthClient {
             .await?
             .ok_or(crate::Error::UserNotFound)?;
 
+        let client_id = self.config.client_id();
+        if google_user
+            .client_id
+            .as_ref()
+            .is_some_and(|id| id != client_id.as_str())
+        {
+            return Err(crate::Error::TokenExchangeFailed {
+                because: "Refresh token was issued to another client".into(),
+            });
+        }
+
         let refresh_token = google_user
             .refresh_token
+            .clone()
             .ok_or(crate::Error::TokenExchangeFailed {
                 because: "No refresh token found for user".into(),
             })
             .map(RefreshToken::new)?;
 
-        let token_result = self
+        let token_result = match self
             .client
             .exchange_refresh_token(&refresh_token)
             .request_async(&self.http_client)
             .await
-            .map_err(|err| crate::Error::TokenExchangeFailed {
-                because: err.to_string(),
-            })?;
+        {
+            Ok(token) => token,
+            Err(err) => {
+                // An `invalid_grant` error means the user revoked access or the
+                // refresh token expired. The stored token is unusable from now on.
+                if let RequestTokenError::ServerResponse(response) = &err {
+                    if *response.error() == BasicErrorResponseType::InvalidGrant
+                    {
+                        self.clear_google_user_grant(
+                            GrantClient::Web,
+                            google_user,
+                        )
+                        .await;
+                    }
+                }
+
+                return Err(crate::Error::TokenExchangeFailed {
+                    because: err.to_string(),
+                });
+            }
+        };
 
         let response = ExchangeRefreshTokenResponse::from(token_result);
         Ok(response)
     }
 
+    /// Exchanges a refresh token obtained via the device authorization grant
+    /// for an access token, using the client set via
+    /// [`FireAuthClient::with_device_client`].
+    ///
+    /// Device grants are stored apart from web grants, so this does not
+    /// affect the refresh token used by [`FireAuthClient::exchange_refresh_token`].
+    pub async fn exchange_device_refresh_token(
+        &self,
+        google_user_id: impl AsRef<str>,
+    ) -> crate::Result<ExchangeRefreshTokenResponse> {
+        let device_client = self.device_client()?;
+        let google_user = self
+            .device_grants()
+            .get(google_user_id)
+            .await?
+            .ok_or(crate::Error::UserNotFound)?;
+
+        let refresh_token = google_user.refresh_token.clone().ok_or(
+            crate::Error::TokenExchangeFailed {
+                because: "No refresh token found for user".into(),
+            },
+        )?;
+        let params = [
+            ("grant_type", "refresh_token"),
+            ("refresh_token", refresh_token.as_str()),
+            ("client_id", device_client.client_id.as_str()),
+            ("client_secret", device_client.client_secret.secret()),
+        ];
+
+        let http_response = self
+            .http_client
+            .post(self.config.token_uri()?.url().clone())
+            .form(&params)
+            .send()
+            .await?;
+
+        if !http_response.status().is_success() {
+            let body = http_response.text().await?;
+            // See `exchange_refresh_token`.
+            if oauth_error_code(&body).as_deref() == Some("invalid_grant") {
+                self.clear_google_user_grant(GrantClient::Device, google_user)
+                    .await;
+            }
+            return Err(crate::Error::TokenExchangeFailed { because: body });
+        }
+
+        let response = http_response.json::<FireAuthTokenResponse>().await?;
+        Ok(ExchangeRefreshTokenResponse::from(response))
+    }
+
     /// Exchanges an authorization code for an access token.
     /// This method also applies the PKCE verifier and any additional parameters.
+    ///
+    /// The refresh token, if any, is persisted for the Google user; the
+    /// exchange fails if it cannot be stored, like provider exchanges do.
     pub async fn exchange_authorization_code(
         &self,
         config: ExchangeAuthorizationCodeConfig,
@@ -132,16 +320,12 @@ impl FireAuthClient {
             client = client.add_extra_param(name.into_cow(), value);
         }
 
-        let token_result = client
-            .request_async(&self.http_client)
-            .await
-            .map_err(|err| crate::Error::TokenExchangeFailed {
-                because: err.to_string(),
-            });
-
-        let response = match token_result {
+        let response = match client.request_async(&self.http_client).await {
             Ok(token) => token,
             Err(err) => {
+                let err = crate::Error::TokenExchangeFailed {
+                    because: err.to_string(),
+                };
                 let response = AuthorizationResponse::new_error(
                     config.redirect_to,
                     err.to_string(),
@@ -150,10 +334,16 @@ impl FireAuthClient {
             }
         };
 
-        // Verify the ID token to confirm issuer and audience.
-        let id_token = response.extra_fields().id_token();
-        let id_token_payload = match self.validate_id_token(id_token).await {
-            Ok(token) => token,
+        let identity = match self
+            .resolve_google_identity(
+                &response,
+                config.nonce.as_deref(),
+                config.params.max_age,
+            )
+            .await
+            .and_then(|identity| self.ensure_authorized_party(identity))
+        {
+            Ok(identity) => identity,
             Err(err) => {
                 let response = AuthorizationResponse::new_error(
                     config.redirect_to,
@@ -163,75 +353,302 @@ impl FireAuthClient {
             }
         };
 
-        if let Some(token) = response.refresh_token() {
-            // Persist authentication metadata to Firestore ONLY if a `refresh_token` is present.
-            //
-            // When the original authentication request uses `access_type=online`, Google will NOT
-            // return a new `refresh_token`. The refresh token is critical for session continuity
-            // and may have already been stored during a previous successful authentication.
-            //
-            // Overwriting an existing user record without a new `refresh_token` would result in
-            // unintentionally nullifying the stored token.
-
-            let google_user_id = id_token_payload.sub.clone();
-
-            if config.revoke_existing_tokens {
-                self.revoke_existing_tokens(&google_user_id).await;
-            }
-
-            let refresh_token = token.to_owned().into_secret();
-            let scope =
-                response.scopes().map(Vec::to_owned).unwrap_or_default();
-
-            let google_user = GoogleUser {
-                id: google_user_id, // Note: this field is not saved to Firestore
-                refresh_token: Some(refresh_token),
-                email: id_token_payload.email,
-                scope,
+        // Rejected accounts are redirected with the denial's stable code, and
+        // their refresh token is never persisted.
+        if let Err(err) = self.sign_in_policy.evaluate(&identity).await {
+            let error = match &err {
+                crate::Error::SignInDenied(denial) => denial.code().to_owned(),
+                _ => err.to_string(),
             };
+            let response =
+                AuthorizationResponse::new_error(config.redirect_to, error);
+            return Ok(response);
+        }
 
-            if let Err(err) = self.repository.update(&google_user).await {
-                // TODO: Maybe return an error
-                log::debug!(
-                    "Failed to update Google user: {}",
-                    &err.to_string()
-                );
-                // let response = AuthRedirectResponse::new_error(config.redirect_to, err.to_string());
-                // return Ok(response);
+        let required_scopes = config.params.required_scopes.as_deref();
+        let missing_scopes = match self
+            .check_consent(
+                &response,
+                &identity.sub,
+                config.params.partial_consent,
+                &config.scopes,
+                required_scopes.map_or(&[], Vec::as_slice),
+            )
+            .await
+        {
+            Ok(missing_scopes) => missing_scopes,
+            Err(crate::Error::ScopesNotGranted(missing_scopes)) => {
+                let response = AuthorizationResponse::new_error(
+                    config.redirect_to,
+                    "scopes_not_granted",
+                )
+                .with_missing_scopes(missing_scopes);
+                return Ok(response);
             }
+            Err(err) => return Err(err),
+        };
+
+        let grant = GrantContext {
+            client: GrantClient::Web,
+            requested_scopes: &config.scopes,
+            include_granted_scopes: *config.params.include_granted_scopes,
+            revoke_existing_tokens: config.revoke_existing_tokens,
+            authenticated_at: config.params.max_age.and(identity.auth_time),
+        };
+        if let Err(err) = self
+            .store_google_user_grant(&response, identity, grant)
+            .await
+        {
+            let response = AuthorizationResponse::new_error(
+                config.redirect_to,
+                err.to_string(),
+            );
+            return Ok(response);
         }
 
         let redirect_response =
-            AuthorizationResponse::new_success(config.redirect_to, response);
+            AuthorizationResponse::new_success(config.redirect_to, response)
+                .with_missing_scopes(missing_scopes);
 
         Ok(redirect_response)
     }
 
+    /// Exchanges an authorization code obtained by a Google Identity Services
+    /// code client in popup mode for tokens.
+    ///
+    /// Such codes are bound to the special `postmessage` redirect URI and are
+    /// not preceded by a PKCE challenge or a server-side CSRF session, so callers
+    /// must authenticate the request themselves. The refresh token, if any,
+    /// is persisted just like in [`FireAuthClient::exchange_authorization_code`].
+    pub async fn exchange_postmessage_code(
+        &self,
+        config: ExchangeCodeConfig,
+    ) -> crate::Result<ExchangeCodeResponse> {
+        let response = self
+            .request_token_without_pkce(&config.code, POSTMESSAGE_REDIRECT_URI)
+            .await?;
+
+        let identity =
+            self.resolve_google_identity(&response, None, None).await?;
+        self.complete_code_exchange(&response, identity, &config)
+            .await
+    }
+
+    /// Exchanges a `serverAuthCode` obtained by native Google Sign-In on
+    /// Android or iOS for tokens.
+    ///
+    /// The ID token must be issued for the web client (`aud`) and authorized
+    /// by one of the platform client IDs configured via
+    /// [`FireAuthClient::with_platform_client_ids`] (`azp`). The refresh token,
+    /// if any, is persisted just like in
+    /// [`FireAuthClient::exchange_authorization_code`].
+    pub async fn exchange_server_auth_code(
+        &self,
+        config: ExchangeCodeConfig,
+    ) -> crate::Result<ExchangeCodeResponse> {
+        if self.platform_client_ids.is_empty() {
+            return Err(crate::Error::MissingConfigField(
+                "platform_client_ids",
+            ));
+        }
+
+        let response =
+            self.request_token_without_pkce(&config.code, "").await?;
+
+        let identity =
+            self.resolve_google_identity(&response, None, None).await?;
+
+        // Server auth codes are issued to the web client on behalf of the
+        // platform client.
+        if identity.aud != self.config.client_id().as_str() {
+            return Err(crate::Error::InvalidToken {
+                because: "ID token was not issued for the web client".into(),
+            });
+        }
+        let authorized_party = identity.azp.as_deref().unwrap_or_default();
+        if !self
+            .platform_client_ids
+            .iter()
+            .any(|client_id| client_id == authorized_party)
+        {
+            return Err(crate::Error::InvalidToken {
+                because:
+                    "ID token was not authorized by a known platform client"
+                        .into(),
+            });
+        }
+
+        self.complete_code_exchange(&response, identity, &config)
+            .await
+    }
+
+    /// Starts an OAuth 2.0 device authorization ([RFC 8628]) for the given
+    /// scopes, using the client set via [`FireAuthClient::with_device_client`].
+    ///
+    /// Like authorization requests, the scopes are checked against the
+    /// allowlist and the requested profile is resolved via the policy set
+    /// with [`FireAuthClient::with_scope_policy`].
+    ///
+    /// [RFC 8628]: https://datatracker.ietf.org/doc/html/rfc8628
+    pub async fn start_device_authorization(
+        &self,
+        payload: &DeviceAuthorizationPayload,
+    ) -> crate::Result<DeviceAuthorizationResponse> {
+        let device_client = self.device_client()?;
+        let mut scopes = payload.scope.to_vec();
+        self.scope_policy
+            .resolve_scopes(&mut scopes, payload.profile.as_deref())?;
+        let scope = scopes
+            .iter()
+            .map(|scope| scope.as_str())
+            .collect::<Vec<_>>()
+            .join(" ");
+        let params = [
+            ("client_id", device_client.client_id.as_str()),
+            ("scope", scope.as_str()),
+        ];
+
+        let http_response = self
+            .http_client
+            .post(&self.device_authorization_url)
+            .form(&params)
+            .send()
+            .await?;
+
+        if !http_response.status().is_success() {
+            return Err(crate::Error::DeviceAuthorizationFailed {
+                because: http_response.text().await?,
+            });
+        }
+
+        Ok(http_response.json().await?)
+    }
+
+    /// Polls the token endpoint once for the tokens of a device authorization.
+    ///
+    /// Once the user granted access, the ID token is validated and the
+    /// refresh token is persisted like in
+    /// [`FireAuthClient::exchange_authorization_code`], but apart from web
+    /// grants. It is refreshed via
+    /// [`FireAuthClient::exchange_device_refresh_token`].
+    pub async fn poll_device_token(
+        &self,
+        device_code: &str,
+    ) -> crate::Result<DeviceTokenPoll> {
+        let device_client = self.device_client()?;
+        let params = [
+            ("grant_type", DEVICE_CODE_GRANT_TYPE),
+            ("device_code", device_code),
+            ("client_id", device_client.client_id.as_str()),
+            ("client_secret", device_client.client_secret.secret()),
+        ];
+
+        let http_response = self
+            .http_client
+            .post(self.config.token_uri()?.url().clone())
+            .form(&params)
+            .send()
+            .await?;
+
+        if !http_response.status().is_success() {
+            let body = http_response.text().await?;
+            return match oauth_error_code(&body).as_deref() {
+                Some("authorization_pending") => {
+                    Ok(DeviceTokenPoll::AuthorizationPending)
+                }
+                Some("slow_down") => Ok(DeviceTokenPoll::SlowDown),
+                _ => Err(crate::Error::DeviceAuthorizationFailed {
+                    because: body,
+                }),
+            };
+        }
+
+        let response = http_response.json::<FireAuthTokenResponse>().await?;
+
+        let identity =
+            self.resolve_google_identity(&response, None, None).await?;
+        self.sign_in_policy.evaluate(&identity).await?;
+        // The device flow does not support incremental authorization.
+        let grant = GrantContext {
+            client: GrantClient::Device,
+            requested_scopes: &[],
+            include_granted_scopes: false,
+            revoke_existing_tokens: false,
+            authenticated_at: None,
+        };
+        self.store_google_user_grant(&response, identity, grant)
+            .await?;
+
+        Ok(DeviceTokenPoll::Complete(ExchangeCodeResponse::from(
+            &response,
+        )))
+    }
+
+    /// Polls for the tokens of a device authorization until the user grants
+    /// or denies access, or the device code expires.
+    ///
+    /// Honors the polling `interval` and backs off by 5 seconds on every
+    /// `slow_down` response.
+    pub async fn wait_for_device_token(
+        &self,
+        authorization: &DeviceAuthorizationResponse,
+    ) -> crate::Result<ExchangeCodeResponse> {
+        let deadline = tokio::time::Instant::now()
+            + Duration::from_secs(authorization.expires_in);
+        let mut interval = Duration::from_secs(authorization.interval);
+
+        loop {
+            tokio::time::sleep(interval).await;
+            if tokio::time::Instant::now() >= deadline {
+                return Err(crate::Error::DeviceAuthorizationFailed {
+                    because: "device code expired".into(),
+                });
+            }
+
+            match self.poll_device_token(&authorization.device_code).await? {
+                DeviceTokenPoll::AuthorizationPending => {}
+                DeviceTokenPoll::SlowDown => {
+                    interval += DEVICE_SLOW_DOWN_INCREMENT;
+                }
+                DeviceTokenPoll::Complete(response) => return Ok(response),
+            }
+        }
+    }
+
     /// Generates an authorization URL with a PKCE challenge and CSRF token.
     /// Returns the verifier, URL to redirect the user to, and the CSRF token to validate later.
+    ///
+    /// Scope profiles are resolved and raw scopes checked against the
+    /// [`FireAuthClient::with_scope_policy`] allowlist first. The `openid`
+    /// scope is then added or required according to the policy set
+    /// via [`FireAuthClient::with_openid_policy`]. Custom parameters must be
+    /// permitted by [`FireAuthClient::with_custom_params_allowlist`].
+    ///
+    /// The request is then validated; issues are logged or, with
+    /// [`RequestValidationMode::Reject`], errors reject the request.
     pub fn request_access_token(
         &self,
         config: &RequestAccessTokenConfig,
-    ) -> RequestAccessTokenResponse {
-        let (pkce_challenge, pkce_verifier) =
-            PkceCodeChallenge::new_random_sha256();
+    ) -> crate::Result<RequestAccessTokenResponse> {
+        self.custom_params_allowlist
+            .check(config.extra_params().custom())?;
 
-        let scopes = config.scopes();
-        let extra_params = config.extra_params().to_extra_params();
-
-        let mut client = self
-            .client
-            .authorize_url(CsrfToken::new_random)
-            .set_pkce_challenge(pkce_challenge)
-            .add_scopes(scopes.to_vec());
+        let mut config = config.clone();
+        self.scope_policy.apply(&mut config)?;
+        config.apply_openid_policy(self.openid_policy)?;
+        config.ensure_nonce();
 
-        for (name, value) in extra_params {
-            client = client.add_extra_param(name.into_cow(), value);
+        let report = config.validate();
+        if self.request_validation == RequestValidationMode::Reject
+            && !report.is_valid()
+        {
+            return Err(crate::Error::InvalidAuthorizationRequest(report));
+        }
+        for issue in report.issues() {
+            log::warn!("Authorization request issue: {issue}");
         }
 
-        let (auth_url, csrf_token) = client.url();
-
-        RequestAccessTokenResponse::new(pkce_verifier, csrf_token, auth_url)
+        Ok(config.authorize_url(&self.client))
     }
 
     /// Revokes a Google-issued `access_token` or `refresh_token`.
@@ -247,115 +664,1609 @@ impl FireAuthClient {
         if config.revoke_refresh_token() {
             let google_user = self.repository.get(config.user_id()).await?;
 
-            let refresh_token = google_user
-                .and_then(|user| user.refresh_token)
-                .map(RefreshToken::new)
-                .map(StandardRevocableToken::RefreshToken);
-
-            if let Some(token) = refresh_token {
-                self.revoke_revocable_token(token).await?;
+            if let Some(user) = google_user {
+                if let Some(token) = user.refresh_token.clone() {
+                    let token = StandardRevocableToken::RefreshToken(
+                        RefreshToken::new(token),
+                    );
+                    self.revoke_revocable_token(token).await?;
+                    self.clear_google_user_grant(GrantClient::Web, user).await;
+                }
             }
         }
 
         Ok(())
     }
 
+    /// Returns the Unix time the Google user last actively authenticated,
+    /// as verified by [`FireAuthClient::exchange_authorization_code`] for
+    /// requests sending `max_age`.
+    ///
+    /// Returns `None` if no such authentication was recorded.
+    pub async fn google_authenticated_at(
+        &self,
+        google_user_id: &str,
+    ) -> crate::Result<Option<i64>> {
+        let user = self.repository.get(google_user_id).await?;
+        Ok(user.and_then(|user| user.authenticated_at))
+    }
+
     /// Validates a Google-issued `id_token` using Google's public keys.
-    /// Returns the parsed payload if successful.
+    /// Returns the parsed claims if successful.
+    ///
+    /// The token's `aud` must be the web client ID or one of the audiences
+    /// added via [`FireAuthClient::with_audiences`]. The matched client is
+    /// reported in the claims' `aud` (and `azp`) field. Expiration is checked
+    /// with the leeway set via [`FireAuthClient::with_clock_skew`].
     pub async fn validate_id_token<T: AsRef<str>>(
         &self,
         id_token: T,
-    ) -> crate::Result<GooglePayload> {
-        let payload = self.token_verifier.validate_id_token(id_token).await?;
+    ) -> crate::Result<IdTokenClaims> {
+        let payload: IdTokenClaims = verify_jwt(
+            id_token.as_ref(),
+            self.id_token_keys.as_ref(),
+            &self.id_token_validation(),
+        )
+        .await?;
+
+        if !self.authorized_parties.is_empty() {
+            // Tokens without `azp` were requested by the audience itself.
+            let authorized_party =
+                payload.azp.as_deref().unwrap_or(&payload.aud);
+            if !self
+                .authorized_parties
+                .iter()
+                .any(|id| id == authorized_party)
+            {
+                return Err(crate::Error::InvalidToken {
+                    because: format!(
+                        "ID token was authorized by unexpected client `{authorized_party}`"
+                    ),
+                });
+            }
+        }
+
         Ok(payload)
     }
 
-    /// Validates a Google-issued `access_token` using Google's public keys.
-    /// Returns the parsed payload if successful.
+    /// Validates a Google-issued `id_token` like
+    /// [`FireAuthClient::validate_id_token`] and additionally requires the
+    /// user to have actively authenticated within `max_age`.
+    pub async fn validate_recent_id_token<T: AsRef<str>>(
+        &self,
+        id_token: T,
+        max_age: Duration,
+    ) -> crate::Result<IdTokenClaims> {
+        let claims = self.validate_id_token(id_token).await?;
+        claims.ensure_authenticated_within(max_age, self.clock_skew)?;
+        Ok(claims)
+    }
+
+    /// Verifies the ID token of a Google Identity Services credential (One
+    /// Tap or the "Sign In With Google" button) like
+    /// [`FireAuthClient::validate_id_token`] and evaluates the sign-in
+    /// policy.
+    ///
+    /// Fails with [`crate::Error::SignInDenied`] if the account is rejected,
+    /// so no tokens must be issued for it.
+    pub async fn verify_gis_credential<T: AsRef<str>>(
+        &self,
+        credential: T,
+    ) -> crate::Result<IdTokenClaims> {
+        let claims = self.validate_id_token(credential).await?;
+        self.sign_in_policy
+            .evaluate(&GoogleIdentity::from(claims.clone()))
+            .await?;
+        Ok(claims)
+    }
+
+    /// Validates a Google-issued `access_token` via
+    /// [`FireAuthClient::token_info`].
+    ///
+    /// Access tokens are opaque, so unlike ID tokens they cannot be verified
+    /// locally with Google's public keys.
     pub async fn validate_access_token<T: AsRef<str>>(
         &self,
         access_token: T,
-    ) -> crate::Result<GoogleAccessTokenPayload> {
-        let payload = self
-            .token_verifier
-            .validate_access_token(access_token)
+    ) -> crate::Result<GoogleTokenInfo> {
+        self.token_info(access_token.as_ref()).await
+    }
+
+    /// Looks up a Google-issued `access_token` at the `tokeninfo` endpoint.
+    ///
+    /// The token must be issued to the web client or one of the audiences
+    /// added via [`FireAuthClient::with_audiences`].
+    pub async fn token_info(
+        &self,
+        access_token: &str,
+    ) -> crate::Result<GoogleTokenInfo> {
+        let http_response = self
+            .http_client
+            .post(&self.tokeninfo_url)
+            .form(&[("access_token", access_token)])
+            .send()
             .await?;
-        Ok(payload)
+
+        if !http_response.status().is_success() {
+            return Err(crate::Error::InvalidToken {
+                because: http_response.text().await?,
+            });
+        }
+
+        let info = http_response.json::<GoogleTokenInfo>().await?;
+        let client_id = self.config.client_id();
+        if info.aud != client_id.as_str() && !self.audiences.contains(&info.aud)
+        {
+            return Err(crate::Error::InvalidToken {
+                because: format!(
+                    "access token was issued to unexpected client `{}`",
+                    info.aud
+                ),
+            });
+        }
+
+        Ok(info)
     }
 
-    /// Sets the redirect URI for the `OAuth2` client.
+    /// Sets the endpoint used by [`FireAuthClient::token_info`].
     ///
-    /// # Parameters
-    /// - `uri`: The URL to be used as the redirect URI in the `OAuth2` flow.
+    /// Defaults to `https://oauth2.googleapis.com/tokeninfo`.
+    #[must_use]
+    pub fn with_tokeninfo_url(mut self, url: impl Into<String>) -> Self {
+        self.tokeninfo_url = url.into();
+        self
+    }
+
+    /// Enables syncing of granted Google scopes into Firebase custom claims.
     ///
-    /// # Returns
-    /// The updated builder instance with the redirect URI set.
+    /// Once set, every change to the scopes stored for a Google user
+    /// (authorization code exchange, refresh token revocation, `invalid_grant`
+    /// cleanup) is mirrored into the configured custom claim. Grants of the
+    /// device client are not synced.
     #[must_use]
-    pub fn with_redirect_uri(mut self, uri: url::Url) -> Self {
-        let redirect_uri = RedirectUrl::from_url(uri);
-        self.client = self.client.set_redirect_uri(redirect_uri);
+    pub fn with_scope_claims_sync(mut self, sync: ScopeClaimsSync) -> Self {
+        self.scope_claims_sync = Some(sync);
         self
     }
 
-    async fn revoke_existing_tokens(&self, user_id: &str) {
-        // Revoke existing refresh token, if any
-        let existing_google_user_result = self.repository.get(user_id).await;
-        match existing_google_user_result {
-            Err(err) => {
-                // TODO: Maybe return an error
-                // return Err(err);
-                log::debug!(
-                    "Failed to update Google user: {}",
-                    &err.to_string()
-                );
-            }
-            Ok(None) => {
-                // No User record
-                log::debug!(
-                    "Skipping token revocation. No record found for Google User ID"
-                );
-            }
-            Ok(Some(user)) => {
-                if user.refresh_token.is_none() {
-                    log::debug!(
-                        "Skipping token revocation. User record is missing refresh token"
-                    );
-                }
+    /// Accepts ID tokens issued to the given OAuth client IDs (e.g. Android,
+    /// iOS or Chrome extension clients) in addition to the web client.
+    #[must_use]
+    pub fn with_audiences(
+        mut self,
+        client_ids: impl IntoIterator<Item = impl Into<String>>,
+    ) -> Self {
+        self.audiences
+            .extend(client_ids.into_iter().map(Into::into));
+        self
+    }
 
-                if let Some(token) = user.refresh_token {
-                    // Revoke refresh token
-                    let refresh_token = RefreshToken::new(token);
-                    let revocable_token =
-                        StandardRevocableToken::RefreshToken(refresh_token);
+    /// Requires the `azp` claim of validated ID tokens to be one of the
+    /// given OAuth client IDs. Tokens without `azp` are checked by `aud`.
+    ///
+    /// All authorized parties are accepted if unset.
+    #[must_use]
+    pub fn with_authorized_parties(
+        mut self,
+        client_ids: impl IntoIterator<Item = impl Into<String>>,
+    ) -> Self {
+        self.authorized_parties =
+            client_ids.into_iter().map(Into::into).collect();
+        self
+    }
 
-                    log::debug!("Preparing for token revocation");
-                    match self.revoke_revocable_token(revocable_token).await {
-                        Err(err) => {
-                            // TODO: Maybe return an error
-                            log::debug!(
-                                "Token revocation failed: {}",
-                                &err.to_string()
-                            );
-                        }
-                        Ok(()) => {
-                            log::debug!("Token successfully revoked");
-                        }
-                    }
-                }
-            }
-        }
+    /// Enables the device authorization grant using the given OAuth client
+    /// of type "TVs and Limited Input devices".
+    ///
+    /// Device codes are requested from Google's device authorization
+    /// endpoint unless overridden via
+    /// [`FireAuthClient::with_device_authorization_url`]. ID tokens issued to
+    /// this client are accepted by [`FireAuthClient::validate_id_token`].
+    #[must_use]
+    pub fn with_device_client(
+        mut self,
+        client_id: impl Into<String>,
+        client_secret: impl Into<String>,
+    ) -> Self {
+        let client_id = client_id.into();
+        self.audiences.push(client_id.clone());
+        self.device_client = Some(DeviceClient {
+            client_id: ClientId::new(client_id),
+            client_secret: ClientSecret::new(client_secret.into()),
+        });
+        self
     }
 
-    async fn revoke_revocable_token(
-        &self,
-        token: StandardRevocableToken,
-    ) -> crate::Result<()> {
-        self.client
-            .revoke_token(token)?
-            .request_async(&self.http_client)
-            .await
-            .map_err(|err| crate::Error::TokenRevocationFailed {
+    /// Sets the device authorization endpoint used with the client set via
+    /// [`FireAuthClient::with_device_client`].
+    ///
+    /// Defaults to `https://oauth2.googleapis.com/device/code`.
+    #[must_use]
+    pub fn with_device_authorization_url(
+        mut self,
+        url: impl Into<String>,
+    ) -> Self {
+        self.device_authorization_url = url.into();
+        self
+    }
+
+    /// Permits forwarding the given custom authorization parameters.
+    /// By default, all custom parameters are rejected.
+    #[must_use]
+    pub fn with_custom_params_allowlist(
+        mut self,
+        allowlist: CustomParamsAllowlist,
+    ) -> Self {
+        self.custom_params_allowlist = allowlist;
+        self
+    }
+
+    /// Sets how authorization requests without the `openid` scope are
+    /// handled. Defaults to [`OpenIdScopePolicy::Add`].
+    #[must_use]
+    pub fn with_openid_policy(mut self, policy: OpenIdScopePolicy) -> Self {
+        self.openid_policy = policy;
+        self
+    }
+
+    /// Sets the clock skew tolerated when checking the time-based claims of
+    /// ID tokens. Defaults to 60 seconds.
+    #[must_use]
+    pub fn with_clock_skew(mut self, leeway: Duration) -> Self {
+        self.clock_skew = leeway;
+        self
+    }
+
+    /// Verifies ID tokens with the given key source instead of Google's
+    /// published JWKS, e.g. a [`crate::StaticKeySource`] in tests.
+    #[must_use]
+    pub fn with_id_token_key_source(
+        mut self,
+        keys: Arc<dyn KeySource>,
+    ) -> Self {
+        self.id_token_keys = keys;
+        self
+    }
+
+    /// Returns the key source used to verify ID tokens, e.g. to refresh it
+    /// in the background via [`crate::spawn_key_refresh`] or to read its
+    /// [metrics](KeySource::metrics).
+    pub fn id_token_key_source(&self) -> Arc<dyn KeySource> {
+        Arc::clone(&self.id_token_keys)
+    }
+
+    /// Sets how authorization requests failing validation are handled.
+    /// Defaults to [`RequestValidationMode::Warn`].
+    #[must_use]
+    pub fn with_request_validation(
+        mut self,
+        mode: RequestValidationMode,
+    ) -> Self {
+        self.request_validation = mode;
+        self
+    }
+
+    /// Sets the scope profiles and the raw scope allowlist applied to
+    /// authorization requests. By default, any raw scope is permitted.
+    #[must_use]
+    pub fn with_scope_policy(mut self, policy: ScopePolicy) -> Self {
+        self.scope_policy = policy;
+        self
+    }
+
+    /// Restricts which Google accounts may sign in. Evaluated on every code
+    /// exchange before the refresh token is persisted; by default, all
+    /// accounts are admitted.
+    #[must_use]
+    pub fn with_sign_in_policy(mut self, policy: SignInPolicy) -> Self {
+        self.sign_in_policy = policy;
+        self
+    }
+
+    /// Sets the Android and iOS OAuth client IDs whose `serverAuthCode`s are
+    /// accepted by [`FireAuthClient::exchange_server_auth_code`].
+    #[must_use]
+    pub fn with_platform_client_ids(
+        mut self,
+        client_ids: impl IntoIterator<Item = impl Into<String>>,
+    ) -> Self {
+        self.platform_client_ids =
+            client_ids.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Registers an additional `OAuth2` provider.
+    ///
+    /// Its refresh tokens are stored in the Firestore collection named by
+    /// [`Provider::storage_namespace`]. Fails if a provider with the same ID
+    /// or namespace is already registered, or if the ID is `google` or the
+    /// namespace is one Google grants are stored in.
+    pub fn add_provider(
+        &mut self,
+        provider: impl Provider + 'static,
+        client_id: impl Into<String>,
+        client_secret: impl Into<String>,
+    ) -> crate::Result<()> {
+        let namespace = provider.storage_namespace();
+        if provider.id() == RESERVED_PROVIDER_ID
+            || namespace == self.repository.collection_name()
+            || namespace == DEVICE_GRANTS_COLLECTION
+        {
+            return Err(crate::Error::ReservedProviderId(
+                provider.id().to_owned(),
+            ));
+        }
+        if self.providers.values().any(|client| {
+            client.id() == provider.id()
+                || client.provider().storage_namespace() == namespace
+        }) {
+            return Err(crate::Error::DuplicateProvider(
+                provider.id().to_owned(),
+            ));
+        }
+
+        let repository = self.repository.with_collection(namespace);
+        let client = ProviderClient::new(provider, client_id, client_secret)?
+            .with_repository(repository);
+
+        self.providers.insert(client.id().to_owned(), client);
+        Ok(())
+    }
+
+    /// Discovers and registers an additional OIDC provider.
+    pub async fn add_oidc_provider(
+        &mut self,
+        config: OidcProviderConfig,
+    ) -> crate::Result<()> {
+        let provider = OidcProvider::discover(&config).await?;
+        self.add_provider(provider, config.client_id, config.client_secret)
+    }
+
+    /// Returns the provider registered under the given ID.
+    pub fn provider(&self, id: &str) -> crate::Result<&ProviderClient> {
+        self.providers
+            .get(id)
+            .ok_or_else(|| crate::Error::UnknownProvider(id.to_owned()))
+    }
+
+    /// Sets the redirect URI for the `OAuth2` client.
+    ///
+    /// # Parameters
+    /// - `uri`: The URL to be used as the redirect URI in the `OAuth2` flow.
+    ///
+    /// # Returns
+    /// The updated builder instance with the redirect URI set.
+    #[must_use]
+    pub fn with_redirect_uri(mut self, uri: url::Url) -> Self {
+        let redirect_uri = RedirectUrl::from_url(uri);
+        self.client = self.client.set_redirect_uri(redirect_uri);
+        self
+    }
+
+    fn id_token_validation(&self) -> Validation {
+        let web_client_id = self.config.client_id().as_str().to_owned();
+        let audiences = std::iter::once(web_client_id)
+            .chain(self.audiences.iter().cloned())
+            .collect::<Vec<_>>();
+
+        let mut validation = Validation::new(Algorithm::RS256);
+        validation.set_audience(&audiences);
+        validation.set_issuer(&GOOGLE_ISSUERS);
+        validation
+            .set_required_spec_claims(&["exp", "iat", "iss", "aud", "sub"]);
+        validation.leeway = self.clock_skew.as_secs();
+        validation
+    }
+
+    fn device_client(&self) -> crate::Result<&DeviceClient> {
+        self.device_client
+            .as_ref()
+            .ok_or(crate::Error::MissingConfigField("device_client"))
+    }
+
+    /// Redeems an authorization code that was requested without PKCE, using
+    /// the given (possibly non-URL) redirect URI.
+    async fn request_token_without_pkce(
+        &self,
+        code: &AuthorizationCode,
+        redirect_uri: &str,
+    ) -> crate::Result<FireAuthTokenResponse> {
+        let client_id = self.config.client_id();
+        let client_secret = self.config.client_secret();
+        let params = [
+            ("grant_type", "authorization_code"),
+            ("code", code.secret()),
+            ("client_id", client_id.as_str()),
+            ("client_secret", client_secret.secret()),
+            ("redirect_uri", redirect_uri),
+        ];
+
+        let http_response = self
+            .http_client
+            .post(self.config.token_uri()?.url().clone())
+            .form(&params)
+            .send()
+            .await?;
+
+        if !http_response.status().is_success() {
+            return Err(crate::Error::TokenExchangeFailed {
+                because: http_response.text().await?,
+            });
+        }
+
+        Ok(http_response.json::<FireAuthTokenResponse>().await?)
+    }
+
+    /// Resolves the Google user a token response was issued for.
+    ///
+    /// Verifies the ID token to confirm issuer and audience, or falls back to
+    /// `tokeninfo` if the `openid` scope was not granted. The ID token must
+    /// carry the given `nonce`, match the access token's hash (`at_hash`) and,
+    /// if `max_age` is given, report a recent enough `auth_time`.
+    async fn resolve_google_identity(
+        &self,
+        response: &FireAuthTokenResponse,
+        nonce: Option<&str>,
+        max_age: Option<MaxAge>,
+    ) -> crate::Result<GoogleIdentity> {
+        if let Some(id_token) = response.extra_fields().id_token() {
+            let payload = self.validate_id_token(id_token).await?;
+            if let Some(nonce) = nonce {
+                id_token::verify_nonce(&payload, nonce)?;
+            }
+            if let Some(at_hash) = &payload.at_hash {
+                id_token::verify_at_hash(
+                    id_token,
+                    at_hash,
+                    response.access_token().secret(),
+                )?;
+            }
+            if let Some(max_age) = max_age {
+                payload.ensure_authenticated_within(
+                    Duration::from_secs(*max_age),
+                    self.clock_skew,
+                )?;
+            }
+            return Ok(payload.into());
+        }
+
+        let access_token = response.access_token().secret();
+        self.token_info(access_token).await?.try_into()
+    }
+
+    /// Ensures tokens obtained by the web client were requested by the web
+    /// client itself rather than one of the other configured audiences.
+    fn ensure_authorized_party(
+        &self,
+        identity: GoogleIdentity,
+    ) -> crate::Result<GoogleIdentity> {
+        if self.audiences.is_empty() {
+            return Ok(identity);
+        }
+
+        let client_id = self.config.client_id();
+        match identity.azp.as_deref() {
+            Some(azp) if azp != client_id.as_str() => {
+                Err(crate::Error::AuthorizedPartyMismatch(azp.to_owned()))
+            }
+            _ => Ok(identity),
+        }
+    }
+
+    /// Ensures the tokens belong to the expected Google user, if any.
+    fn ensure_google_user(
+        identity: &GoogleIdentity,
+        google_user_id: Option<&str>,
+    ) -> crate::Result<()> {
+        match google_user_id {
+            Some(id) if id != identity.sub => Err(crate::Error::InvalidToken {
+                because: "tokens were issued for a different Google user"
+                    .into(),
+            }),
+            _ => Ok(()),
+        }
+    }
+
+    /// Completes a code exchange without a server-side redirect once the
+    /// Google identity is resolved: checks the user, sign-in policy and
+    /// consent, then persists the grant.
+    async fn complete_code_exchange(
+        &self,
+        response: &FireAuthTokenResponse,
+        identity: GoogleIdentity,
+        config: &ExchangeCodeConfig,
+    ) -> crate::Result<ExchangeCodeResponse> {
+        Self::ensure_google_user(&identity, config.google_user_id.as_deref())?;
+        self.sign_in_policy.evaluate(&identity).await?;
+        let missing_scopes = self
+            .check_consent(
+                response,
+                &identity.sub,
+                config.partial_consent,
+                &config.requested_scopes,
+                &config.required_scopes,
+            )
+            .await?;
+
+        // Code clients and native apps request incremental authorization by
+        // default.
+        let grant = GrantContext {
+            client: GrantClient::Web,
+            requested_scopes: &config.requested_scopes,
+            include_granted_scopes: true,
+            revoke_existing_tokens: config.revoke_existing_tokens,
+            authenticated_at: None,
+        };
+        self.store_google_user_grant(response, identity, grant)
+            .await?;
+
+        Ok(ExchangeCodeResponse::from(response)
+            .with_missing_scopes(missing_scopes))
+    }
+
+    /// Persists the refresh token and granted scopes of a successful code
+    /// exchange and updates the scope claims accordingly.
+    ///
+    /// The stored scopes are those Google reports as granted, which include
+    /// earlier grants with incremental authorization. Only if they cannot be
+    /// determined are the requested scopes merged with the stored ones.
+    /// Fails if the grant cannot be stored, since the refresh token would be
+    /// lost otherwise.
+    async fn store_google_user_grant(
+        &self,
+        response: &FireAuthTokenResponse,
+        identity: GoogleIdentity,
+        grant: GrantContext<'_>,
+    ) -> crate::Result<()> {
+        // Persist authentication metadata to Firestore ONLY if a `refresh_token` is present.
+        //
+        // When the original authentication request uses `access_type=online`, Google will NOT
+        // return a new `refresh_token`. The refresh token is critical for session continuity
+        // and may have already been stored during a previous successful authentication.
+        //
+        // Overwriting an existing user record without a new `refresh_token` would result in
+        // unintentionally nullifying the stored token.
+        let Some(token) = response.refresh_token() else {
+            return match grant.authenticated_at {
+                Some(auth_time) => {
+                    self.record_google_authentication(&identity.sub, auth_time)
+                        .await
+                }
+                None => Ok(()),
+            };
+        };
+
+        let google_user_id = identity.sub;
+        let repository = self.grant_repository(grant.client);
+
+        // Revoking the previous refresh token revokes its scopes, too.
+        let scope = match self.granted_scopes(response).await {
+            Some(granted) => granted,
+            None if grant.include_granted_scopes
+                && !grant.revoke_existing_tokens =>
+            {
+                ScopeSet::from(grant.requested_scopes).union(
+                    &Self::stored_scopes(&repository, &google_user_id).await,
+                )
+            }
+            None => ScopeSet::from(grant.requested_scopes),
+        }
+        .to_scopes();
+
+        if grant.revoke_existing_tokens {
+            self.revoke_existing_tokens(&repository, &google_user_id)
+                .await;
+        }
+
+        let refresh_token = token.secret().to_owned();
+
+        let client_id = match grant.client {
+            GrantClient::Web => self.config.client_id().to_string(),
+            GrantClient::Device => self
+                .device_client
+                .as_ref()
+                .map(|client| client.client_id.to_string())
+                .unwrap_or_default(),
+        };
+        let google_user = GoogleUser {
+            id: google_user_id, // Note: this field is not saved to Firestore
+            refresh_token: Some(refresh_token),
+            email: identity.email,
+            scope,
+            client_id: Some(client_id),
+            authenticated_at: grant.authenticated_at,
+        };
+
+        repository.update(&google_user).await?;
+        if grant.client == GrantClient::Web {
+            self.sync_scope_claims(&google_user.id, &google_user.scope)
+                .await;
+        }
+        Ok(())
+    }
+
+    /// Records when a Google user last actively authenticated without
+    /// issuing a refresh token, creating a record without one if none is
+    /// stored yet.
+    async fn record_google_authentication(
+        &self,
+        google_user_id: &str,
+        auth_time: i64,
+    ) -> crate::Result<()> {
+        let mut user = self
+            .repository
+            .get(google_user_id)
+            .await?
+            .unwrap_or_else(|| GoogleUser {
+                id: google_user_id.to_owned(),
+                email: None,
+                refresh_token: None,
+                scope: vec![],
+                client_id: None,
+                authenticated_at: None,
+            });
+        user.authenticated_at = Some(auth_time);
+        self.repository.update(&user).await
+    }
+
+    /// Returns the scopes granted by a token response.
+    ///
+    /// Google omits `scope` if the granted scopes equal the requested ones.
+    /// In that case, they are looked up via `tokeninfo`. Returns `None` if
+    /// that fails, so callers can assume the requested scopes.
+    async fn granted_scopes(
+        &self,
+        response: &FireAuthTokenResponse,
+    ) -> Option<ScopeSet> {
+        if let Some(scopes) = response.scopes() {
+            return Some(ScopeSet::from(scopes.as_slice()));
+        }
+
+        match self.token_info(response.access_token().secret()).await {
+            Ok(info) => Some(ScopeSet::new(info.scope)),
+            Err(err) => {
+                log::warn!(
+                    "Failed to look up granted scopes, assuming the \
+                     requested ones: {err}"
+                );
+                None
+            }
+        }
+    }
+
+    /// Returns the repository storing the grants of the given client.
+    fn grant_repository(&self, client: GrantClient) -> GoogleUserRepository {
+        match client {
+            GrantClient::Web => self.repository.clone(),
+            GrantClient::Device => self.device_grants(),
+        }
+    }
+
+    fn device_grants(&self) -> GoogleUserRepository {
+        self.repository.with_collection(DEVICE_GRANTS_COLLECTION)
+    }
+
+    /// Returns the scopes currently stored for the given Google user.
+    async fn stored_scopes(
+        repository: &GoogleUserRepository,
+        google_user_id: &str,
+    ) -> ScopeSet {
+        match repository.get(google_user_id).await {
+            Ok(user) => user
+                .map(|user| ScopeSet::from(user.scope.as_slice()))
+                .unwrap_or_default(),
+            Err(err) => {
+                log::debug!("Failed to get Google user: {err}");
+                ScopeSet::default()
+            }
+        }
+    }
+
+    /// Removes the stored refresh token and scopes of a Google user whose grant
+    /// is no longer valid, and updates the scope claims accordingly.
+    async fn clear_google_user_grant(
+        &self,
+        client: GrantClient,
+        mut user: GoogleUser,
+    ) {
+        user.refresh_token = None;
+        user.scope = Vec::new();
+
+        if let Err(err) = self.grant_repository(client).update(&user).await {
+            log::warn!("Failed to clear Google user grant: {err}");
+            return;
+        }
+
+        if client == GrantClient::Web {
+            self.sync_scope_claims(&user.id, &user.scope).await;
+        }
+    }
+
+    async fn sync_scope_claims(&self, google_user_id: &str, scopes: &[Scope]) {
+        let Some(sync) = &self.scope_claims_sync else {
+            return;
+        };
+
+        if let Err(err) = sync.sync(google_user_id, scopes).await {
+            log::warn!("Failed to sync scope claims: {err}");
+        }
+    }
+
+    async fn revoke_existing_tokens(
+        &self,
+        repository: &GoogleUserRepository,
+        user_id: &str,
+    ) {
+        // Revoke existing refresh token, if any
+        let existing_google_user_result = repository.get(user_id).await;
+        match existing_google_user_result {
+            Err(err) => {
+                // TODO: Maybe return an error
+                // return Err(err);
+                log::debug!(
+                    "Failed to update Google user: {}",
+                    &err.to_string()
+                );
+            }
+            Ok(None) => {
+                // No User record
+                log::debug!(
+                    "Skipping token revocation. No record found for Google User ID"
+                );
+            }
+            Ok(Some(user)) => {
+                if user.refresh_token.is_none() {
+                    log::debug!(
+                        "Skipping token revocation. User record is missing refresh token"
+                    );
+                }
+
+                if let Some(token) = user.refresh_token {
+                    // Revoke refresh token
+                    let refresh_token = RefreshToken::new(token);
+                    let revocable_token =
+                        StandardRevocableToken::RefreshToken(refresh_token);
+
+                    log::debug!("Preparing for token revocation");
+                    match self.revoke_revocable_token(revocable_token).await {
+                        Err(err) => {
+                            // TODO: Maybe return an error
+                            log::debug!(
+                                "Token revocation failed: {}",
+                                &err.to_string()
+                            );
+                        }
+                        Ok(()) => {
+                            log::debug!("Token successfully revoked");
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Applies the partial consent policy to a token response, revoking the
+    /// grant if it fails so that no unwanted tokens stay valid.
+    async fn check_consent(
+        &self,
+        response: &FireAuthTokenResponse,
+        google_user_id: &str,
+        policy: PartialConsentPolicy,
+        requested_scopes: &[Scope],
+        required_scopes: &[Scope],
+    ) -> crate::Result<ScopeSet> {
+        let result = policy.missing_scopes(
+            requested_scopes,
+            required_scopes,
+            response.scopes().map(Vec::as_slice),
+        );
+
+        if let Err(crate::Error::ScopesNotGranted(_)) = &result {
+            // If in doubt, keep the grant rather than losing a stored one.
+            let has_earlier_grant = self
+                .repository
+                .get(google_user_id)
+                .await
+                .map_or(true, |user| {
+                    user.is_some_and(|user| user.refresh_token.is_some())
+                });
+            match rejected_grant_token(response, has_earlier_grant) {
+                Some(token) => {
+                    if let Err(err) = self.revoke_revocable_token(token).await {
+                        log::warn!(
+                            "Failed to revoke partially granted tokens: {err}"
+                        );
+                    }
+                }
+                None => log::debug!(
+                    "Not revoking partially granted tokens of an earlier grant"
+                ),
+            }
+        }
+        result
+    }
+
+    async fn revoke_revocable_token(
+        &self,
+        token: StandardRevocableToken,
+    ) -> crate::Result<()> {
+        self.client
+            .revoke_token(token)?
+            .request_async(&self.http_client)
+            .await
+            .map_err(|err| crate::Error::TokenRevocationFailed {
                 because: err.to_string(),
             })
     }
 }
+
+/// Returns the token to revoke when rejecting a partially granted response.
+///
+/// Google revokes the whole grant of a token. Since incremental authorization
+/// adds to the grant of an earlier authorization, revoking would also revoke
+/// its stored refresh token, so no token is returned if one exists.
+fn rejected_grant_token(
+    response: &FireAuthTokenResponse,
+    has_earlier_grant: bool,
+) -> Option<StandardRevocableToken> {
+    if has_earlier_grant {
+        return None;
+    }
+    // Revoking the refresh token revokes its access tokens, too.
+    let token = match response.refresh_token() {
+        Some(token) => StandardRevocableToken::RefreshToken(token.clone()),
+        None => {
+            StandardRevocableToken::AccessToken(response.access_token().clone())
+        }
+    };
+    Some(token)
+}
+
+/// Returns the `error` code of an OAuth 2.0 error response body.
+fn oauth_error_code(body: &str) -> Option<String> {
+    serde_json::from_str::<serde_json::Value>(body)
+        .ok()
+        .and_then(|value| value["error"].as_str().map(str::to_owned))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::keys::test_keys;
+    use crate::providers::GitHubProvider;
+    use crate::testing::{serve_json, serve_json_once, serve_json_with_status};
+    use serde_json::json;
+
+    const CLIENT_ID: &str = "web-client.apps.googleusercontent.com";
+    const GOOGLE_USER_ID: &str = "google-123";
+    const ANDROID_CLIENT_ID: &str = "android-client.apps.googleusercontent.com";
+    const DEVICE_CLIENT_ID: &str = "device-client.apps.googleusercontent.com";
+
+    fn client(base_url: &str) -> FireAuthClient {
+        let json = json!({
+            "web": {
+                "client_id": CLIENT_ID,
+                "project_id": "test-project",
+                "auth_uri": "https://accounts.google.com/o/oauth2/auth",
+                "token_uri": format!("{base_url}/token"),
+                "auth_provider_x509_cert_url": "https://www.googleapis.com/oauth2/v1/certs",
+                "client_secret": "test-secret",
+            }
+        });
+        let config =
+            GoogleOAuthClientConfig::from_slice(json.to_string().as_bytes())
+                .unwrap();
+        let repository = GoogleUserRepository::in_memory("googleUsers");
+        FireAuthClient::with_repository(config, repository)
+            .unwrap()
+            .with_id_token_key_source(Arc::new(test_keys::key_source()))
+    }
+
+    /// Signs an ID token for the web client, overriding the given claims.
+    fn id_token(claims: &serde_json::Value) -> String {
+        let mut payload = json!({
+            "iss": "https://accounts.google.com",
+            "aud": CLIENT_ID,
+            "sub": GOOGLE_USER_ID,
+            "email": "alice@example.com",
+            "email_verified": true,
+            "iat": test_keys::now(),
+            "exp": test_keys::now() + 3600,
+        });
+        for (name, value) in claims.as_object().unwrap() {
+            payload[name] = value.clone();
+        }
+        test_keys::sign(&payload)
+    }
+
+    fn token_response(id_token: &str, scope: &str) -> serde_json::Value {
+        json!({
+            "access_token": "test-access-token",
+            "refresh_token": "test-refresh-token",
+            "id_token": id_token,
+            "token_type": "Bearer",
+            "expires_in": 3599,
+            "scope": scope,
+        })
+    }
+
+    #[tokio::test]
+    async fn test_validate_id_token_audiences() {
+        let client = client("http://127.0.0.1:9")
+            .with_audiences([ANDROID_CLIENT_ID])
+            .with_authorized_parties([CLIENT_ID, ANDROID_CLIENT_ID]);
+
+        for aud in [CLIENT_ID, ANDROID_CLIENT_ID] {
+            let token = id_token(&json!({ "aud": aud }));
+            let claims = client.validate_id_token(token).await.unwrap();
+            assert_eq!(claims.aud, aud);
+        }
+
+        let token = id_token(&json!({ "aud": "other-client" }));
+        assert!(matches!(
+            client.validate_id_token(token).await,
+            Err(crate::Error::Jwt(_))
+        ));
+
+        let token = id_token(&json!({ "azp": "other-client" }));
+        assert!(matches!(
+            client.validate_id_token(token).await,
+            Err(crate::Error::InvalidToken { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_verify_gis_credential_evaluates_sign_in_policy() {
+        let client = client("http://127.0.0.1:9").with_sign_in_policy(
+            SignInPolicy::default().with_blocked_domains(["example.org"]),
+        );
+
+        let claims = client.verify_gis_credential(id_token(&json!({}))).await;
+        assert_eq!(claims.unwrap().sub, GOOGLE_USER_ID);
+
+        let credential = id_token(&json!({ "email": "eve@example.org" }));
+        assert!(matches!(
+            client.verify_gis_credential(credential).await,
+            Err(crate::Error::SignInDenied(
+                crate::client::SignInDenial::DomainBlocked
+            ))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_exchange_github_authorization_code() {
+        let (base_url, requests) = serve_json(vec![
+            json!({
+                "access_token": "gho_access",
+                "token_type": "bearer",
+                "scope": "read:user,user:email",
+                "refresh_token": "ghr_refresh",
+                "expires_in": 28800,
+            }),
+            json!({
+                "id": 583_231,
+                "login": "octocat",
+                "name": "The Octocat",
+                "email": "octocat@github.com",
+            }),
+        ])
+        .await;
+        let mut client = client(&base_url);
+        client
+            .add_provider(
+                GitHubProvider::enterprise(&base_url),
+                "github-client",
+                "github-secret",
+            )
+            .unwrap();
+        let redirect_uri = url::Url::parse(
+            "https://app.example.com/providers/github/callback",
+        )
+        .unwrap();
+
+        let response = client
+            .provider(GitHubProvider::ID)
+            .unwrap()
+            .exchange_authorization_code(redirect_uri, "code", "verifier", None)
+            .await
+            .unwrap();
+
+        assert_eq!(response.access_token(), "gho_access");
+        assert_eq!(response.scope(), ["read:user", "user:email"]);
+        assert_eq!(response.identity().subject, "583231");
+        let requests = requests.await.unwrap();
+        assert!(requests[0].contains("code=code"));
+        assert!(requests[0].contains("client_secret=github-secret"));
+
+        let user = client
+            .repository
+            .with_collection("githubUsers")
+            .get("583231")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(user.refresh_token.as_deref(), Some("ghr_refresh"));
+        assert_eq!(user.email.as_deref(), Some("octocat@github.com"));
+        assert_eq!(user.scope.len(), 2);
+        assert!(client.repository.get("583231").await.unwrap().is_none());
+    }
+
+    /// Provider with the given ID, used to test registration.
+    struct NamedProvider(&'static str);
+
+    #[async_trait::async_trait]
+    impl Provider for NamedProvider {
+        fn id(&self) -> &str {
+            self.0
+        }
+
+        fn endpoints(&self) -> crate::ProviderEndpoints {
+            crate::ProviderEndpoints {
+                authorization_url: "https://idp.example.com/authorize".into(),
+                token_url: "https://idp.example.com/token".into(),
+                revocation_url: None,
+            }
+        }
+
+        fn default_scopes(&self) -> Vec<Scope> {
+            Vec::new()
+        }
+
+        async fn resolve_identity(
+            &self,
+            _http_client: &reqwest::Client,
+            _access_token: &str,
+            _id_token: Option<&str>,
+            _nonce: Option<&str>,
+        ) -> crate::Result<crate::ProviderIdentity> {
+            Err(crate::Error::UnknownProvider(self.0.into()))
+        }
+    }
+
+    #[test]
+    fn test_add_provider_rejects_duplicate_and_reserved_ids() {
+        let mut client = client("http://127.0.0.1:9");
+        client
+            .add_provider(GitHubProvider::default(), "id", "secret")
+            .unwrap();
+        assert!(matches!(
+            client.add_provider(NamedProvider("github"), "id", "secret"),
+            Err(crate::Error::DuplicateProvider(_))
+        ));
+
+        // `googleDevice` maps to the collection of device grants.
+        for id in ["google", "googleDevice"] {
+            assert!(matches!(
+                client.add_provider(NamedProvider(id), "id", "secret"),
+                Err(crate::Error::ReservedProviderId(_))
+            ));
+        }
+        assert!(client.provider("google").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_exchange_postmessage_code() {
+        let id_token = id_token(&json!({}));
+        let (base_url, request) =
+            serve_json_once(token_response(&id_token, "openid email")).await;
+        let client = client(&base_url);
+
+        let config =
+            ExchangeCodeConfig::new("test-code").google_user_id(GOOGLE_USER_ID);
+        let response = client.exchange_postmessage_code(config).await.unwrap();
+        assert_eq!(response.access_token, "test-access-token");
+        assert_eq!(response.id_token.as_deref(), Some(id_token.as_str()));
+
+        let request = request.await.unwrap();
+        assert!(request.contains("code=test-code"));
+        assert!(request.contains("redirect_uri=postmessage"));
+        assert!(!request.contains("code_verifier"));
+
+        let user = client.repository.get(GOOGLE_USER_ID).await.unwrap();
+        let user = user.unwrap();
+        assert_eq!(user.refresh_token.as_deref(), Some("test-refresh-token"));
+        assert_eq!(user.email.as_deref(), Some("alice@example.com"));
+    }
+
+    #[tokio::test]
+    async fn test_exchange_postmessage_code_replaces_scopes_and_syncs_claims() {
+        const CALENDAR: &str =
+            "https://www.googleapis.com/auth/calendar.readonly";
+
+        let id_token = id_token(&json!({}));
+        let (base_url, _) =
+            serve_json_once(token_response(&id_token, "openid email")).await;
+        let admin = Arc::new(crate::admin::InMemoryFirebaseAdmin::new());
+        admin.link(crate::GOOGLE_PROVIDER_ID, GOOGLE_USER_ID, "firebase-uid");
+        let mapping = serde_json::from_value(json!({
+            "claim": "google_scopes",
+            "scopes": { "email": "email", CALENDAR: "gcal:read" },
+        }))
+        .unwrap();
+        let client = client(&base_url).with_scope_claims_sync(
+            ScopeClaimsSync::new(mapping, admin.clone()),
+        );
+        client
+            .repository
+            .update(&GoogleUser {
+                id: GOOGLE_USER_ID.into(),
+                email: None,
+                refresh_token: Some("old-refresh-token".into()),
+                scope: vec![Scope::new(CALENDAR.into())],
+                client_id: None,
+                authenticated_at: None,
+            })
+            .await
+            .unwrap();
+
+        let config =
+            ExchangeCodeConfig::new("test-code").google_user_id(GOOGLE_USER_ID);
+        client.exchange_postmessage_code(config).await.unwrap();
+
+        // Google reports the full grant, so the calendar scope the user
+        // revoked in the meantime is dropped.
+        let user = client.repository.get(GOOGLE_USER_ID).await.unwrap();
+        let scopes = ScopeSet::from(user.unwrap().scope.as_slice());
+        assert!(!scopes.iter().any(|scope| scope == CALENDAR));
+        assert!(
+            scopes
+                .iter()
+                .any(|scope| scope == crate::GoogleScope::Email.url().as_str())
+        );
+        assert_eq!(
+            admin.claims_of("firebase-uid")["google_scopes"],
+            json!(["email"])
+        );
+    }
+
+    #[tokio::test]
+    async fn test_exchange_postmessage_code_fails_on_missing_required_scope() {
+        const CALENDAR: &str =
+            "https://www.googleapis.com/auth/calendar.readonly";
+
+        let id_token = id_token(&json!({}));
+        let (base_url, _) =
+            serve_json_once(token_response(&id_token, "openid email")).await;
+        let client = client(&base_url);
+        let earlier_grant = GoogleUser {
+            id: GOOGLE_USER_ID.into(),
+            email: None,
+            refresh_token: Some("old-refresh-token".into()),
+            scope: vec![Scope::new("email".into())],
+            client_id: None,
+            authenticated_at: None,
+        };
+        client.repository.update(&earlier_grant).await.unwrap();
+
+        let config = ExchangeCodeConfig::new("test-code")
+            .google_user_id(GOOGLE_USER_ID)
+            .requested_scopes(
+                ["openid", "email", CALENDAR]
+                    .map(|scope| Scope::new(scope.into())),
+            )
+            .required_scopes([Scope::new(CALENDAR.into())])
+            .partial_consent(PartialConsentPolicy::Fail);
+        let err = client.exchange_postmessage_code(config).await.unwrap_err();
+        assert!(matches!(
+            err,
+            crate::Error::ScopesNotGranted(missing) if missing.contains(CALENDAR)
+        ));
+
+        // The new tokens extend the earlier grant, so the stored grant is
+        // kept as is.
+        let user = client.repository.get(GOOGLE_USER_ID).await.unwrap();
+        let user = user.unwrap();
+        assert_eq!(user.refresh_token.as_deref(), Some("old-refresh-token"));
+        assert_eq!(user.scope, earlier_grant.scope);
+    }
+
+    #[test]
+    fn test_rejected_grant_token() {
+        let id_token = id_token(&json!({}));
+        let mut response = token_response(&id_token, "openid email");
+        let parse = |response: &serde_json::Value| -> FireAuthTokenResponse {
+            serde_json::from_value(response.clone()).unwrap()
+        };
+
+        let token = rejected_grant_token(&parse(&response), false);
+        assert!(matches!(
+            token,
+            Some(StandardRevocableToken::RefreshToken(token))
+                if token.secret() == "test-refresh-token"
+        ));
+        assert!(rejected_grant_token(&parse(&response), true).is_none());
+
+        response["refresh_token"].take();
+        let token = rejected_grant_token(&parse(&response), false);
+        assert!(matches!(
+            token,
+            Some(StandardRevocableToken::AccessToken(token))
+                if token.secret() == "test-access-token"
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_exchange_postmessage_code_falls_back_to_tokeninfo() {
+        const CALENDAR: &str =
+            "https://www.googleapis.com/auth/calendar.readonly";
+
+        // Without `openid` and with the requested scopes granted, Google
+        // returns neither an ID token nor the granted scopes.
+        let (base_url, requests) = serve_json(vec![
+            json!({
+                "access_token": "test-access-token",
+                "refresh_token": "test-refresh-token",
+                "token_type": "Bearer",
+                "expires_in": 3599,
+            }),
+            json!({
+                "aud": CLIENT_ID,
+                "sub": GOOGLE_USER_ID,
+                "email": "user@example.com",
+                "email_verified": "true",
+                "scope": format!("email {CALENDAR}"),
+            }),
+            json!({
+                "aud": CLIENT_ID,
+                "sub": GOOGLE_USER_ID,
+                "scope": format!("email {CALENDAR}"),
+            }),
+        ])
+        .await;
+        let client = client(&base_url)
+            .with_tokeninfo_url(format!("{base_url}/tokeninfo"));
+
+        let config = ExchangeCodeConfig::new("test-code")
+            .google_user_id(GOOGLE_USER_ID)
+            .requested_scopes([Scope::new("email".into())]);
+        client.exchange_postmessage_code(config).await.unwrap();
+
+        let user = client.repository.get(GOOGLE_USER_ID).await.unwrap();
+        let user = user.unwrap();
+        assert_eq!(user.email.as_deref(), Some("user@example.com"));
+        assert_eq!(user.refresh_token.as_deref(), Some("test-refresh-token"));
+        assert_eq!(
+            ScopeSet::from(user.scope.as_slice()),
+            ScopeSet::new(["email", CALENDAR])
+        );
+
+        let requests = requests.await.unwrap();
+        for request in &requests[1..] {
+            assert_eq!(request, "access_token=test-access-token");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_exchange_postmessage_code_rejects_other_google_user() {
+        let id_token = id_token(&json!({ "sub": "google-456" }));
+        let (base_url, _) =
+            serve_json_once(token_response(&id_token, "openid email")).await;
+        let client = client(&base_url);
+
+        let config =
+            ExchangeCodeConfig::new("test-code").google_user_id(GOOGLE_USER_ID);
+        let result = client.exchange_postmessage_code(config).await;
+        assert!(matches!(result, Err(crate::Error::InvalidToken { .. })));
+
+        let stored = client.repository.get("google-456").await.unwrap();
+        assert!(stored.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_exchange_authorization_code_records_authentication() {
+        let auth_time = test_keys::now() - 60;
+        let id_token = id_token(&json!({ "auth_time": auth_time }));
+        let mut response = token_response(&id_token, "openid email");
+        response["refresh_token"].take();
+        let (base_url, _) = serve_json(vec![response.clone(), response]).await;
+        let client = client(&base_url);
+
+        let config = |params: serde_json::Value| {
+            crate::ExchangeAuthorizationCodeConfigBuilder::new()
+                .csrf_token("state")
+                .state("state")
+                .code("test-code")
+                .pkce_verifier("verifier")
+                .params(serde_json::from_value(params).unwrap())
+                .scopes([Scope::new("openid".into())])
+                .redirect_to(
+                    url::Url::parse("https://app.example.com").unwrap(),
+                )
+                .build()
+                .unwrap()
+        };
+
+        // Without `max_age`, `auth_time` is not verified and not recorded.
+        let response = client
+            .exchange_authorization_code(config(json!({})))
+            .await
+            .unwrap();
+        assert!(response.to_string().contains("access_token="));
+        let authenticated_at = client
+            .google_authenticated_at(GOOGLE_USER_ID)
+            .await
+            .unwrap();
+        assert_eq!(authenticated_at, None);
+
+        // Recorded even though no refresh token was issued.
+        client
+            .exchange_authorization_code(config(json!({ "max_age": 300 })))
+            .await
+            .unwrap();
+        let authenticated_at = client
+            .google_authenticated_at(GOOGLE_USER_ID)
+            .await
+            .unwrap();
+        assert_eq!(authenticated_at, Some(auth_time));
+    }
+
+    #[tokio::test]
+    async fn test_exchange_server_auth_code() {
+        let id_token = id_token(&json!({ "azp": ANDROID_CLIENT_ID }));
+        let (base_url, request) =
+            serve_json_once(token_response(&id_token, "openid email")).await;
+        let client =
+            client(&base_url).with_platform_client_ids([ANDROID_CLIENT_ID]);
+
+        let config =
+            ExchangeCodeConfig::new("test-code").google_user_id(GOOGLE_USER_ID);
+        let response = client.exchange_server_auth_code(config).await.unwrap();
+        assert_eq!(response.access_token, "test-access-token");
+
+        let request = request.await.unwrap();
+        assert!(request.ends_with("redirect_uri="));
+        assert!(!request.contains("code_verifier"));
+
+        let user = client.repository.get(GOOGLE_USER_ID).await.unwrap();
+        assert!(user.is_some_and(|user| user.refresh_token.is_some()));
+    }
+
+    #[tokio::test]
+    async fn test_exchange_server_auth_code_requires_web_client_audience() {
+        // Accepted by `validate_id_token`, but not issued for the web client.
+        let id_token = id_token(&json!({
+            "aud": ANDROID_CLIENT_ID,
+            "azp": ANDROID_CLIENT_ID,
+        }));
+        let (base_url, _) =
+            serve_json_once(token_response(&id_token, "openid email")).await;
+        let client = client(&base_url)
+            .with_audiences([ANDROID_CLIENT_ID])
+            .with_platform_client_ids([ANDROID_CLIENT_ID]);
+
+        let config = ExchangeCodeConfig::new("test-code");
+        let result = client.exchange_server_auth_code(config).await;
+        assert!(matches!(result, Err(crate::Error::InvalidToken { .. })));
+        let stored = client.repository.get(GOOGLE_USER_ID).await.unwrap();
+        assert!(stored.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_start_device_authorization_applies_scope_policy() {
+        let client = client("http://127.0.0.1:9")
+            .with_device_client(DEVICE_CLIENT_ID, "device-secret")
+            .with_scope_policy(
+                ScopePolicy::default().with_allowed_scopes(["email"]),
+            );
+
+        let start = |payload: serde_json::Value| {
+            let payload: DeviceAuthorizationPayload =
+                serde_json::from_value(payload).unwrap();
+            let client = client.clone();
+            async move { client.start_device_authorization(&payload).await }
+        };
+
+        // Rejected before Google is contacted.
+        assert!(matches!(
+            start(json!({ "scope": "openid https://mail.google.com/" })).await,
+            Err(crate::Error::DisallowedScope(scope))
+                if scope == "https://mail.google.com/"
+        ));
+        assert!(matches!(
+            start(json!({ "profile": "drive" })).await,
+            Err(crate::Error::UnknownScopeProfile(_))
+        ));
+        assert!(matches!(
+            start(json!({})).await,
+            Err(crate::Error::MissingScope)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_start_device_authorization() {
+        let (base_url, request) = serve_json_once(json!({
+            "device_code": "device-code",
+            "user_code": "GQVQ-JKEC",
+            "verification_url": "https://www.google.com/device",
+            "expires_in": 1800,
+            "interval": 5,
+        }))
+        .await;
+        let client = client("http://127.0.0.1:9")
+            .with_device_client(DEVICE_CLIENT_ID, "device-secret")
+            .with_device_authorization_url(format!("{base_url}/device/code"));
+
+        let payload: DeviceAuthorizationPayload =
+            serde_json::from_value(json!({ "scope": "openid email" })).unwrap();
+        let response =
+            client.start_device_authorization(&payload).await.unwrap();
+
+        assert_eq!(response.device_code, "device-code");
+        assert_eq!(response.user_code, "GQVQ-JKEC");
+        assert_eq!(response.verification_url, "https://www.google.com/device");
+        assert_eq!(response.expires_in, 1800);
+        assert_eq!(response.interval, 5);
+
+        let request = request.await.unwrap();
+        assert!(request.contains(&format!("client_id={DEVICE_CLIENT_ID}")));
+        assert!(request.contains("scope=openid+email"));
+        assert!(!request.contains("client_secret"));
+    }
+
+    fn device_authorization(
+        expires_in: u64,
+        interval: u64,
+    ) -> DeviceAuthorizationResponse {
+        serde_json::from_value(json!({
+            "device_code": "device-code",
+            "user_code": "GQVQ-JKEC",
+            "verification_url": "https://www.google.com/device",
+            "expires_in": expires_in,
+            "interval": interval,
+        }))
+        .unwrap()
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_wait_for_device_token_honors_interval_and_slow_down() {
+        let id_token = id_token(&json!({ "aud": DEVICE_CLIENT_ID }));
+        let (base_url, requests) = serve_json_with_status(vec![
+            (428, json!({ "error": "authorization_pending" })),
+            (403, json!({ "error": "slow_down" })),
+            (428, json!({ "error": "authorization_pending" })),
+            (200, token_response(&id_token, "openid email")),
+        ])
+        .await;
+        let client = client(&base_url)
+            .with_device_client(DEVICE_CLIENT_ID, "device-secret");
+
+        let started = tokio::time::Instant::now();
+        let response = client
+            .wait_for_device_token(&device_authorization(1800, 1))
+            .await
+            .unwrap();
+
+        // 1s, 1s, then 6s twice after `slow_down`.
+        let elapsed = started.elapsed();
+        assert!(elapsed >= Duration::from_secs(14));
+        assert!(elapsed < Duration::from_secs(15));
+        assert_eq!(response.access_token, "test-access-token");
+        assert_eq!(requests.await.unwrap().len(), 4);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_wait_for_device_token_stops_when_code_expires() {
+        let (base_url, requests) = serve_json_with_status(vec![
+            (428, json!({ "error": "authorization_pending" })),
+            (428, json!({ "error": "authorization_pending" })),
+        ])
+        .await;
+        let client = client(&base_url)
+            .with_device_client(DEVICE_CLIENT_ID, "device-secret");
+
+        let result = client
+            .wait_for_device_token(&device_authorization(3, 1))
+            .await;
+
+        assert!(matches!(
+            result,
+            Err(crate::Error::DeviceAuthorizationFailed { because })
+                if because == "device code expired"
+        ));
+        assert_eq!(requests.await.unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_poll_device_token_pending_slow_down_and_denied() {
+        let (base_url, requests) = serve_json_with_status(vec![
+            (428, json!({ "error": "authorization_pending" })),
+            (403, json!({ "error": "slow_down" })),
+            (403, json!({ "error": "access_denied" })),
+            (400, json!({ "error": "expired_token" })),
+        ])
+        .await;
+        let client = client(&base_url)
+            .with_device_client(DEVICE_CLIENT_ID, "device-secret");
+
+        assert!(matches!(
+            client.poll_device_token("device-code").await,
+            Ok(DeviceTokenPoll::AuthorizationPending)
+        ));
+        assert!(matches!(
+            client.poll_device_token("device-code").await,
+            Ok(DeviceTokenPoll::SlowDown)
+        ));
+        for error in ["access_denied", "expired_token"] {
+            assert!(matches!(
+                client.poll_device_token("device-code").await,
+                Err(crate::Error::DeviceAuthorizationFailed { because })
+                    if because.contains(error)
+            ));
+        }
+
+        let requests = requests.await.unwrap();
+        assert!(requests[0].contains("device_code=device-code"));
+        assert!(requests[0].contains(&format!("client_id={DEVICE_CLIENT_ID}")));
+    }
+
+    #[tokio::test]
+    async fn test_poll_device_token_stores_grant_apart_from_web_grant() {
+        let id_token = id_token(&json!({ "aud": DEVICE_CLIENT_ID }));
+        let (base_url, requests) = serve_json(vec![
+            token_response(&id_token, "openid email"),
+            json!({
+                "access_token": "device-access-token",
+                "token_type": "Bearer",
+                "expires_in": 3599,
+            }),
+        ])
+        .await;
+        let client = client(&base_url)
+            .with_device_client(DEVICE_CLIENT_ID, "device-secret");
+        let web_user = GoogleUser {
+            id: GOOGLE_USER_ID.into(),
+            email: None,
+            refresh_token: Some("web-refresh-token".into()),
+            scope: Vec::new(),
+            client_id: Some(CLIENT_ID.into()),
+            authenticated_at: None,
+        };
+        client.repository.update(&web_user).await.unwrap();
+
+        let poll = client.poll_device_token("device-code").await.unwrap();
+        assert!(matches!(poll, DeviceTokenPoll::Complete(_)));
+
+        let web_user = client.repository.get(GOOGLE_USER_ID).await.unwrap();
+        let web_user = web_user.unwrap();
+        assert_eq!(
+            web_user.refresh_token.as_deref(),
+            Some("web-refresh-token")
+        );
+        let device_user =
+            client.device_grants().get(GOOGLE_USER_ID).await.unwrap();
+        let device_user = device_user.unwrap();
+        assert_eq!(
+            device_user.refresh_token.as_deref(),
+            Some("test-refresh-token")
+        );
+        assert_eq!(device_user.client_id.as_deref(), Some(DEVICE_CLIENT_ID));
+
+        let response = client
+            .exchange_device_refresh_token(GOOGLE_USER_ID)
+            .await
+            .unwrap();
+        assert_eq!(response.access_token, "device-access-token");
+        let requests = requests.await.unwrap();
+        assert!(requests[1].contains("refresh_token=test-refresh-token"));
+        assert!(requests[1].contains(&format!("client_id={DEVICE_CLIENT_ID}")));
+    }
+
+    #[tokio::test]
+    async fn test_exchange_refresh_token_rejects_other_client() {
+        let client = client("http://127.0.0.1:9");
+        let user = GoogleUser {
+            id: GOOGLE_USER_ID.into(),
+            email: None,
+            refresh_token: Some("device-refresh-token".into()),
+            scope: Vec::new(),
+            client_id: Some(DEVICE_CLIENT_ID.into()),
+            authenticated_at: None,
+        };
+        client.repository.update(&user).await.unwrap();
+
+        assert!(matches!(
+            client.exchange_refresh_token(GOOGLE_USER_ID).await,
+            Err(crate::Error::TokenExchangeFailed { .. })
+        ));
+    }
+}
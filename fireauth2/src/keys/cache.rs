@@ -0,0 +1,367 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+use jsonwebtoken::DecodingKey;
+use tokio::sync::RwLock;
+
+use super::KeySourceMetrics;
+
+/// A set of decoding keys keyed by `kid`, as fetched from a key endpoint.
+pub(crate) struct FetchedKeys {
+    pub(crate) keys: HashMap<String, DecodingKey>,
+    pub(crate) max_age: Option<Duration>,
+}
+
+/// Time-based cache shared by the HTTP key sources.
+///
+/// Expired keys are served for up to [`KeyCache::MAX_STALENESS`] while they
+/// are refetched in the background, so that verification neither waits on
+/// nor fails with the key endpoint. An unknown `kid` triggers a refetch of
+/// still-fresh keys, at most once per [`KeyCache::RETRY_INTERVAL`], so that
+/// rotated keys are picked up early.
+pub(crate) struct KeyCache {
+    entry: RwLock<Option<CacheEntry>>,
+    /// Whether a background refresh of stale keys is in flight.
+    refreshing: AtomicBool,
+    hits: AtomicU64,
+    misses: AtomicU64,
+    refreshes: AtomicU64,
+    refresh_failures: AtomicU64,
+    stale_hits: AtomicU64,
+}
+
+struct CacheEntry {
+    keys: HashMap<String, DecodingKey>,
+    fetched_at: Instant,
+    expires_at: Instant,
+    /// When the keys were last (re)fetched or a fetch was last attempted.
+    attempted_at: Instant,
+}
+
+impl KeyCache {
+    const FALLBACK_MAX_AGE: Duration = Duration::from_secs(60);
+    const MAX_STALENESS: Duration = Duration::from_secs(24 * 60 * 60);
+    const RETRY_INTERVAL: Duration = Duration::from_secs(30);
+
+    pub(crate) fn new() -> Self {
+        Self {
+            entry: RwLock::new(None),
+            refreshing: AtomicBool::new(false),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+            refreshes: AtomicU64::new(0),
+            refresh_failures: AtomicU64::new(0),
+            stale_hits: AtomicU64::new(0),
+        }
+    }
+
+    /// Returns the key for `kid`, calling `fetch` if the cache is empty, or
+    /// if it lacks `kid` and was not refetched within the last
+    /// [`KeyCache::RETRY_INTERVAL`].
+    ///
+    /// Expired keys fetched less than [`KeyCache::MAX_STALENESS`] ago are
+    /// served right away while a single spawned task refetches them. If that
+    /// fails, it is retried after [`KeyCache::RETRY_INTERVAL`].
+    ///
+    /// No lock is held while fetching, so lookups of cached keys never wait
+    /// on the key endpoint.
+    pub(crate) async fn get<F, Fut>(
+        self: &Arc<Self>,
+        kid: &str,
+        fetch: F,
+    ) -> crate::Result<DecodingKey>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = crate::Result<FetchedKeys>> + Send + 'static,
+    {
+        {
+            let entry = self.entry.read().await;
+            if let Some(entry) = entry.as_ref() {
+                if entry.serves(kid) {
+                    self.hits.fetch_add(1, Ordering::Relaxed);
+                    return lookup(&entry.keys, kid);
+                }
+                if entry.is_stale() && entry.keys.contains_key(kid) {
+                    self.misses.fetch_add(1, Ordering::Relaxed);
+                    self.stale_hits.fetch_add(1, Ordering::Relaxed);
+                    self.spawn_refresh(fetch());
+                    return lookup(&entry.keys, kid);
+                }
+            }
+        }
+
+        {
+            let mut entry = self.entry.write().await;
+            // Another task may have refreshed the keys while we were waiting.
+            if let Some(entry) = entry.as_mut() {
+                if entry.serves(kid) {
+                    self.hits.fetch_add(1, Ordering::Relaxed);
+                    return lookup(&entry.keys, kid);
+                }
+                // Rate-limits concurrent refetches for unknown `kid`s.
+                entry.attempted_at = Instant::now();
+            }
+        }
+
+        self.misses.fetch_add(1, Ordering::Relaxed);
+        let fetched = fetch().await;
+        let mut entry = self.entry.write().await;
+        match fetched {
+            Ok(fetched) => {
+                self.refreshes.fetch_add(1, Ordering::Relaxed);
+                let key = lookup(&fetched.keys, kid);
+                *entry = Some(CacheEntry::new(fetched));
+                key
+            }
+            Err(err) => {
+                self.refresh_failures.fetch_add(1, Ordering::Relaxed);
+                match entry.as_mut() {
+                    // Refetched for an unknown `kid`; the keys are still valid.
+                    Some(cached) if cached.expires_at > Instant::now() => {
+                        log::warn!("Failed to refetch signing keys: {err}");
+                        lookup(&cached.keys, kid)
+                    }
+                    Some(stale) if stale.is_stale() => {
+                        log::warn!("Serving stale signing keys: {err}");
+                        self.stale_hits.fetch_add(1, Ordering::Relaxed);
+                        stale.retry_later();
+                        lookup(&stale.keys, kid)
+                    }
+                    _ => Err(err),
+                }
+            }
+        }
+    }
+
+    /// Refetches stale keys in a spawned task, unless one is in flight.
+    fn spawn_refresh<Fut>(self: &Arc<Self>, fetch: Fut)
+    where
+        Fut: Future<Output = crate::Result<FetchedKeys>> + Send + 'static,
+    {
+        if self.refreshing.swap(true, Ordering::AcqRel) {
+            return;
+        }
+
+        let cache = Arc::clone(self);
+        tokio::spawn(async move {
+            if let Err(err) = cache.refresh(fetch).await {
+                log::warn!("Serving stale signing keys: {err}");
+                if let Some(stale) = cache.entry.write().await.as_mut() {
+                    stale.retry_later();
+                }
+            }
+            cache.refreshing.store(false, Ordering::Release);
+        });
+    }
+
+    /// Replaces the cached keys with freshly fetched ones, returning their
+    /// lifetime.
+    ///
+    /// On failure, the cached keys are left untouched.
+    pub(crate) async fn refresh<F>(&self, fetch: F) -> crate::Result<Duration>
+    where
+        F: Future<Output = crate::Result<FetchedKeys>>,
+    {
+        let fetched = match fetch.await {
+            Ok(fetched) => fetched,
+            Err(err) => {
+                self.refresh_failures.fetch_add(1, Ordering::Relaxed);
+                return Err(err);
+            }
+        };
+
+        self.refreshes.fetch_add(1, Ordering::Relaxed);
+        let entry = CacheEntry::new(fetched);
+        let max_age = entry.expires_at - entry.fetched_at;
+        *self.entry.write().await = Some(entry);
+        Ok(max_age)
+    }
+
+    /// Returns a snapshot of the cache counters.
+    pub(crate) fn metrics(&self) -> KeySourceMetrics {
+        KeySourceMetrics {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            refreshes: self.refreshes.load(Ordering::Relaxed),
+            refresh_failures: self.refresh_failures.load(Ordering::Relaxed),
+            stale_hits: self.stale_hits.load(Ordering::Relaxed),
+        }
+    }
+}
+
+impl CacheEntry {
+    fn new(fetched: FetchedKeys) -> Self {
+        let fetched_at = Instant::now();
+        let max_age = fetched.max_age.unwrap_or(KeyCache::FALLBACK_MAX_AGE);
+        Self {
+            keys: fetched.keys,
+            fetched_at,
+            expires_at: fetched_at + max_age,
+            attempted_at: fetched_at,
+        }
+    }
+
+    /// Whether the keys expired but may still be served.
+    fn is_stale(&self) -> bool {
+        self.expires_at <= Instant::now()
+            && self.fetched_at.elapsed() < KeyCache::MAX_STALENESS
+    }
+
+    /// Serves the stale keys as fresh until the next retry is due.
+    fn retry_later(&mut self) {
+        self.attempted_at = Instant::now();
+        self.expires_at = self.attempted_at + KeyCache::RETRY_INTERVAL;
+    }
+
+    /// Whether `kid` can be answered from the cache without a fetch.
+    fn serves(&self, kid: &str) -> bool {
+        self.expires_at > Instant::now()
+            && (self.keys.contains_key(kid)
+                || self.attempted_at.elapsed() < KeyCache::RETRY_INTERVAL)
+    }
+}
+
+fn lookup(
+    keys: &HashMap<String, DecodingKey>,
+    kid: &str,
+) -> crate::Result<DecodingKey> {
+    keys.get(kid)
+        .cloned()
+        .ok_or_else(|| crate::Error::SigningKeyNotFound(kid.to_owned()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::keys::test_keys;
+
+    fn fetched(max_age: Duration) -> FetchedKeys {
+        fetched_kids(&[test_keys::KID], max_age)
+    }
+
+    fn fetched_kids(kids: &[&str], max_age: Duration) -> FetchedKeys {
+        let key = DecodingKey::from_secret(b"secret");
+        FetchedKeys {
+            keys: kids
+                .iter()
+                .map(|kid| ((*kid).to_owned(), key.clone()))
+                .collect(),
+            max_age: Some(max_age),
+        }
+    }
+
+    fn failing_fetch()
+    -> impl Future<Output = crate::Result<FetchedKeys>> + Send + 'static {
+        std::future::ready(Err(crate::Error::SigningKeyNotFound(
+            "unavailable".into(),
+        )))
+    }
+
+    #[tokio::test]
+    async fn test_get_serves_stale_keys_when_refresh_fails() {
+        let cache = Arc::new(KeyCache::new());
+        let fresh = || async { Ok(fetched(Duration::ZERO)) };
+        cache.get(test_keys::KID, fresh).await.unwrap();
+
+        // The keys expired immediately, but the endpoint is down.
+        assert!(cache.get(test_keys::KID, failing_fetch).await.is_ok());
+        while cache.refreshing.load(Ordering::Acquire) {
+            tokio::task::yield_now().await;
+        }
+        // Served from cache until the retry interval elapses.
+        assert!(cache.get(test_keys::KID, failing_fetch).await.is_ok());
+
+        let metrics = cache.metrics();
+        assert_eq!(metrics.refreshes, 1);
+        assert_eq!(metrics.refresh_failures, 1);
+        assert_eq!(metrics.stale_hits, 1);
+        assert_eq!(metrics.hits, 1);
+    }
+
+    #[tokio::test]
+    async fn test_get_does_not_wait_on_hanging_fetch() {
+        let hour = Duration::from_secs(3600);
+        let cache = Arc::new(KeyCache::new());
+        cache
+            .get(test_keys::KID, || async { Ok(fetched(Duration::ZERO)) })
+            .await
+            .unwrap();
+
+        let hanging = std::future::pending::<crate::Result<FetchedKeys>>;
+        let within = |get| tokio::time::timeout(Duration::from_secs(1), get);
+
+        // Stale keys are served while the refetch hangs in the background.
+        within(cache.get(test_keys::KID, hanging))
+            .await
+            .unwrap()
+            .unwrap();
+        within(cache.get(test_keys::KID, hanging))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(cache.metrics().stale_hits, 2);
+
+        // A hanging refetch for an unknown `kid` does not block cached keys.
+        cache.refresh(async { Ok(fetched(hour)) }).await.unwrap();
+        if let Some(entry) = cache.entry.write().await.as_mut() {
+            entry.attempted_at -= KeyCache::RETRY_INTERVAL;
+        }
+        let unknown = tokio::spawn({
+            let cache = Arc::clone(&cache);
+            async move { cache.get("unknown", hanging).await.map(drop) }
+        });
+        tokio::task::yield_now().await;
+        within(cache.get(test_keys::KID, hanging))
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(!unknown.is_finished());
+        unknown.abort();
+    }
+
+    #[tokio::test]
+    async fn test_get_refetches_once_for_unknown_kid() {
+        const ROTATED: &str = "rotated";
+        let hour = Duration::from_secs(3600);
+        let rotated = || async move { Ok(fetched_kids(&[ROTATED], hour)) };
+
+        let cache = Arc::new(KeyCache::new());
+        cache
+            .get(test_keys::KID, || async move { Ok(fetched(hour)) })
+            .await
+            .unwrap();
+
+        // Fetched just now, so the unknown `kid` is not refetched yet.
+        assert!(matches!(
+            cache.get(ROTATED, rotated).await,
+            Err(crate::Error::SigningKeyNotFound(_))
+        ));
+        assert_eq!(cache.metrics().refreshes, 1);
+
+        if let Some(entry) = cache.entry.write().await.as_mut() {
+            entry.attempted_at -= KeyCache::RETRY_INTERVAL;
+        }
+        assert!(cache.get(ROTATED, rotated).await.is_ok());
+        assert_eq!(cache.metrics().refreshes, 2);
+
+        // A failed refetch keeps the fresh keys and is rate-limited, too.
+        if let Some(entry) = cache.entry.write().await.as_mut() {
+            entry.attempted_at -= KeyCache::RETRY_INTERVAL;
+        }
+        assert!(cache.get("unknown", failing_fetch).await.is_err());
+        assert!(cache.get(ROTATED, failing_fetch).await.is_ok());
+        assert!(cache.get("unknown", failing_fetch).await.is_err());
+        let metrics = cache.metrics();
+        assert_eq!(metrics.refresh_failures, 1);
+        assert_eq!(metrics.stale_hits, 0);
+    }
+
+    #[tokio::test]
+    async fn test_get_fails_without_cached_keys() {
+        let cache = Arc::new(KeyCache::new());
+        assert!(cache.get(test_keys::KID, failing_fetch).await.is_err());
+        assert_eq!(cache.metrics().misses, 1);
+    }
+}
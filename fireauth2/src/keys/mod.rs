@@ -0,0 +1,141 @@
+mod cache;
+mod jwks;
+mod static_keys;
+mod x509;
+
+#[cfg(any(test, feature = "testing"))]
+pub mod test_keys;
+
+pub use jwks::*;
+pub use static_keys::*;
+pub use x509::*;
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use jsonwebtoken::{DecodingKey, Validation, decode, decode_header};
+use oauth2::reqwest;
+use oauth2::reqwest::header::{CACHE_CONTROL, HeaderMap};
+use serde::de::DeserializeOwned;
+
+/// Source of public keys used to verify signed JWTs.
+///
+/// Implementations are responsible for fetching and caching keys; callers
+/// only look keys up by their `kid` header value.
+#[async_trait::async_trait]
+pub trait KeySource: Send + Sync {
+    /// Returns the decoding key identified by `kid`.
+    async fn key(&self, kid: &str) -> crate::Result<DecodingKey>;
+
+    /// Fetches the keys ahead of their expiry and returns how long they
+    /// are valid for, or `None` if the source does not cache keys.
+    async fn refresh(&self) -> crate::Result<Option<Duration>> {
+        Ok(None)
+    }
+
+    /// Returns cache counters, or `None` if the source does not cache keys.
+    fn metrics(&self) -> Option<KeySourceMetrics> {
+        None
+    }
+}
+
+/// Counters of a caching [`KeySource`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct KeySourceMetrics {
+    /// Lookups answered from unexpired cached keys.
+    pub hits: u64,
+    /// Lookups that found the cache empty or expired.
+    pub misses: u64,
+    /// Successful key fetches.
+    pub refreshes: u64,
+    /// Failed key fetches.
+    pub refresh_failures: u64,
+    /// Lookups answered from expired keys because a fetch failed.
+    pub stale_hits: u64,
+}
+
+/// Refreshes the keys of `keys` in the background shortly before they
+/// expire, so that verification rarely waits on the key endpoint.
+///
+/// Failed refreshes are retried every 30 seconds. The task ends right away
+/// for sources that do not cache keys.
+pub fn spawn_key_refresh(
+    keys: Arc<dyn KeySource>,
+) -> tokio::task::JoinHandle<()> {
+    const REFRESH_AHEAD: Duration = Duration::from_secs(60);
+    const MIN_INTERVAL: Duration = Duration::from_secs(30);
+
+    tokio::spawn(async move {
+        loop {
+            let interval = match keys.refresh().await {
+                Ok(Some(max_age)) => max_age.saturating_sub(REFRESH_AHEAD),
+                Ok(None) => return,
+                Err(err) => {
+                    log::warn!("Failed to refresh signing keys: {err}");
+                    MIN_INTERVAL
+                }
+            };
+            tokio::time::sleep(interval.max(MIN_INTERVAL)).await;
+        }
+    })
+}
+
+/// Verifies the signature and standard claims of `token` using a key from
+/// `keys`, returning the decoded claims.
+///
+/// The token header must carry a `kid` and use one of the algorithms allowed
+/// by `validation`.
+pub async fn verify_jwt<T: DeserializeOwned>(
+    token: &str,
+    keys: &dyn KeySource,
+    validation: &Validation,
+) -> crate::Result<T> {
+    let header = decode_header(token)?;
+
+    if !validation.algorithms.contains(&header.alg) {
+        return Err(crate::Error::InvalidToken {
+            because: format!("unexpected signing algorithm {:?}", header.alg),
+        });
+    }
+
+    let kid = header.kid.ok_or_else(|| crate::Error::InvalidToken {
+        because: "missing `kid` header".into(),
+    })?;
+
+    // jsonwebtoken rejects keys whose family differs from any of the
+    // allowed algorithms, so only the one the token was signed with is
+    // checked against the key.
+    let mut validation = validation.clone();
+    validation.algorithms = vec![header.alg];
+
+    let key = keys.key(&kid).await?;
+    let data = decode::<T>(token, &key, &validation)?;
+    Ok(data.claims)
+}
+
+/// Builds the HTTP client used to fetch keys.
+///
+/// Requests time out so that a hanging key endpoint cannot stall
+/// verification or the background refresh.
+pub(crate) fn http_client() -> crate::Result<reqwest::Client> {
+    const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+    const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+    Ok(reqwest::ClientBuilder::new()
+        .redirect(reqwest::redirect::Policy::none())
+        .connect_timeout(CONNECT_TIMEOUT)
+        .timeout(REQUEST_TIMEOUT)
+        .build()?)
+}
+
+/// Extracts the `max-age` directive from a `Cache-Control` response header.
+pub(crate) fn cache_max_age(headers: &HeaderMap) -> Option<Duration> {
+    let value = headers.get(CACHE_CONTROL)?.to_str().ok()?;
+    value.split(',').find_map(|directive| {
+        let (name, secs) = directive.trim().split_once('=')?;
+        if !name.trim().eq_ignore_ascii_case("max-age") {
+            return None;
+        }
+        secs.trim().parse().ok().map(Duration::from_secs)
+    })
+}
@@ -0,0 +1,41 @@
+use std::collections::HashMap;
+
+use jsonwebtoken::DecodingKey;
+
+use super::KeySource;
+
+/// [`KeySource`] backed by a fixed, in-memory set of keys.
+///
+/// Useful in tests, where tokens are signed with locally generated keys.
+#[derive(Clone, Default)]
+pub struct StaticKeySource {
+    keys: HashMap<String, DecodingKey>,
+}
+
+impl StaticKeySource {
+    /// Creates an empty key set.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a key identified by `kid`.
+    #[must_use]
+    pub fn with_key(
+        mut self,
+        kid: impl Into<String>,
+        key: DecodingKey,
+    ) -> Self {
+        self.keys.insert(kid.into(), key);
+        self
+    }
+}
+
+#[async_trait::async_trait]
+impl KeySource for StaticKeySource {
+    async fn key(&self, kid: &str) -> crate::Result<DecodingKey> {
+        self.keys
+            .get(kid)
+            .cloned()
+            .ok_or_else(|| crate::Error::SigningKeyNotFound(kid.to_owned()))
+    }
+}
@@ -0,0 +1,31 @@
+//! Locally generated RSA key pair for signing JWTs in tests.
+
+use jsonwebtoken::{Algorithm, DecodingKey, EncodingKey, Header, encode};
+use serde::Serialize;
+
+use super::StaticKeySource;
+
+/// Key ID of the test key pair.
+pub const KID: &str = "test-key";
+
+const PRIVATE_KEY: &[u8] = include_bytes!("../../testdata/rsa_private.pem");
+const PUBLIC_KEY: &[u8] = include_bytes!("../../testdata/rsa_public.pem");
+
+/// Returns a key source containing the test public key under [`KID`].
+pub fn key_source() -> StaticKeySource {
+    let key = DecodingKey::from_rsa_pem(PUBLIC_KEY).unwrap();
+    StaticKeySource::new().with_key(KID, key)
+}
+
+/// Signs `claims` as an RS256 JWT with the test private key.
+pub fn sign<T: Serialize>(claims: &T) -> String {
+    let mut header = Header::new(Algorithm::RS256);
+    header.kid = Some(KID.to_owned());
+    let key = EncodingKey::from_rsa_pem(PRIVATE_KEY).unwrap();
+    encode(&header, claims, &key).unwrap()
+}
+
+/// Returns the current UNIX timestamp in seconds.
+pub fn now() -> i64 {
+    chrono::Utc::now().timestamp()
+}
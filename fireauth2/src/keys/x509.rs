@@ -0,0 +1,70 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use jsonwebtoken::DecodingKey;
+use oauth2::reqwest;
+
+use super::cache::{FetchedKeys, KeyCache};
+use super::{KeySource, KeySourceMetrics, cache_max_age, http_client};
+
+/// [`KeySource`] that fetches a JSON object mapping key IDs to PEM-encoded
+/// X.509 certificates, the format used by Firebase for session cookies.
+///
+/// Keys are cached for the `max-age` advertised by the endpoint, and served
+/// past it for a while if the endpoint fails.
+pub struct X509CertKeySource {
+    url: String,
+    http_client: reqwest::Client,
+    cache: Arc<KeyCache>,
+}
+
+impl X509CertKeySource {
+    /// Creates a new key source for the given certificate endpoint.
+    pub fn new(url: impl Into<String>) -> crate::Result<Self> {
+        Ok(Self {
+            url: url.into(),
+            http_client: http_client()?,
+            cache: Arc::new(KeyCache::new()),
+        })
+    }
+
+    fn fetch(
+        &self,
+    ) -> impl Future<Output = crate::Result<FetchedKeys>> + Send + 'static {
+        Self::fetch_keys(self.http_client.get(&self.url))
+    }
+
+    async fn fetch_keys(
+        request: reqwest::RequestBuilder,
+    ) -> crate::Result<FetchedKeys> {
+        let response = request.send().await?.error_for_status()?;
+
+        let max_age = cache_max_age(response.headers());
+        let certs: HashMap<String, String> = response.json().await?;
+
+        let keys = certs
+            .into_iter()
+            .map(|(kid, pem)| {
+                DecodingKey::from_rsa_pem(pem.as_bytes()).map(|key| (kid, key))
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(FetchedKeys { keys, max_age })
+    }
+}
+
+#[async_trait::async_trait]
+impl KeySource for X509CertKeySource {
+    async fn key(&self, kid: &str) -> crate::Result<DecodingKey> {
+        self.cache.get(kid, || self.fetch()).await
+    }
+
+    async fn refresh(&self) -> crate::Result<Option<Duration>> {
+        self.cache.refresh(self.fetch()).await.map(Some)
+    }
+
+    fn metrics(&self) -> Option<KeySourceMetrics> {
+        Some(self.cache.metrics())
+    }
+}
@@ -17,9 +17,15 @@
 //!
 //! ## Modules
 //!
+//! - `admin`: Firebase Admin API abstraction, including syncing granted scopes into custom claims.
+//! - `app_check`: Firebase App Check token verification.
 //! - `client`: Core `OAuth2` client implementations and helpers for Google `OAuth2` flows, including Firebase Authentication integration.
+//! - `credentials`: Access token sources for stored user grants and service accounts.
 //! - `error`: Error handling types and utilities used throughout the crate.
+//! - `keys`: Public key sources and JWT verification helpers.
 //! - `models`: Data structures representing `OAuth2` payloads, tokens, config options, and Firebase token extensions.
+//! - `oidc`: Generic OIDC providers configured from discovery documents.
+//! - `providers`: `OAuth2` providers other than Google, such as GitHub, and the client driving their flows.
 //! - `repositories`: Persistence layer abstractions such as token storage, revocation, and Firestore syncing.
 //!
 //! ## Usage
@@ -50,17 +56,31 @@
 //! pull request on the GitHub repository.
 //!
 
+mod admin;
+mod app_check;
 mod client;
+mod credentials;
 mod error;
 mod fireauth;
+mod keys;
 mod models;
+mod oidc;
+mod providers;
 mod repositories;
+#[cfg(test)]
+mod testing;
 
 // Re-export core modules for easy access
+pub use admin::*;
+pub use app_check::*;
 pub use client::*;
+pub use credentials::*;
 pub use error::*;
 pub use fireauth::*;
+pub use keys::*;
 pub use models::*;
+pub use oidc::*;
+pub use providers::*;
 
 // Re-export oauth2 types
 pub use oauth2::{CsrfToken, PkceCodeVerifier};
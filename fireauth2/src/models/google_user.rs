@@ -21,6 +21,16 @@ pub struct GoogleUser {
 
     /// `OAuth2` scopes granted to the user.
     pub(crate) scope: Vec<Scope>,
+
+    /// OAuth client the refresh token was issued to. Unset for grants stored
+    /// before it was recorded, which belong to the web client.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) client_id: Option<String>,
+
+    /// Unix time the user last actively authenticated with Google, as
+    /// verified on a callback requesting `max_age`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) authenticated_at: Option<i64>,
 }
 
 // Custom `Debug` implementation to avoid exposing sensitive information.
@@ -31,6 +41,8 @@ impl fmt::Debug for GoogleUser {
             .field("email", &"<redacted>")
             .field("refresh_token", &"<redacted>")
             .field("scope", &self.scope)
+            .field("client_id", &self.client_id)
+            .field("authenticated_at", &self.authenticated_at)
             .finish()
     }
 }
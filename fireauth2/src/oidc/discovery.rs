@@ -0,0 +1,66 @@
+use oauth2::reqwest;
+use serde::{Deserialize, Serialize};
+
+/// Provider metadata published at `/.well-known/openid-configuration`
+/// ([OpenID Connect Discovery 1.0]).
+///
+/// [OpenID Connect Discovery 1.0]: https://openid.net/specs/openid-connect-discovery-1_0.html#ProviderMetadata
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OidcProviderMetadata {
+    /// Issuer identifier, matched against the `iss` claim of ID tokens.
+    pub issuer: String,
+    /// Authorization endpoint.
+    pub authorization_endpoint: String,
+    /// Token endpoint.
+    pub token_endpoint: String,
+    /// Token revocation endpoint (RFC 7009), if supported.
+    #[serde(default)]
+    pub revocation_endpoint: Option<String>,
+    /// `UserInfo` endpoint, if supported.
+    #[serde(default)]
+    pub userinfo_endpoint: Option<String>,
+    /// JSON Web Key Set used to sign ID tokens.
+    pub jwks_uri: String,
+    /// Scopes the provider supports, if published.
+    #[serde(default)]
+    pub scopes_supported: Option<Vec<String>>,
+    /// Algorithms the provider signs ID tokens with.
+    #[serde(default = "OidcProviderMetadata::default_signing_algs")]
+    pub id_token_signing_alg_values_supported: Vec<String>,
+}
+
+impl OidcProviderMetadata {
+    const WELL_KNOWN_PATH: &'static str = "/.well-known/openid-configuration";
+
+    fn default_signing_algs() -> Vec<String> {
+        vec!["RS256".to_owned()]
+    }
+
+    /// Fetches and validates the discovery document of the given issuer.
+    pub async fn discover(
+        issuer: &str,
+        http_client: &reqwest::Client,
+    ) -> crate::Result<Self> {
+        let issuer = issuer.trim_end_matches('/');
+        let url = format!("{issuer}{}", Self::WELL_KNOWN_PATH);
+
+        let response = http_client.get(&url).send().await?;
+        if !response.status().is_success() {
+            return Err(crate::Error::ProviderDiscoveryFailed {
+                because: format!("{url} returned {}", response.status()),
+            });
+        }
+
+        let metadata: Self = response.json().await?;
+        if metadata.issuer.trim_end_matches('/') != issuer {
+            return Err(crate::Error::ProviderDiscoveryFailed {
+                because: format!(
+                    "issuer `{}` does not match `{issuer}`",
+                    metadata.issuer
+                ),
+            });
+        }
+
+        Ok(metadata)
+    }
+}
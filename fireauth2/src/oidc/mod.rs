@@ -0,0 +1,47 @@
+mod discovery;
+mod provider;
+
+pub use discovery::*;
+pub use provider::*;
+
+use serde::Deserialize;
+
+/// Configuration of an OIDC provider other than Google, such as
+/// Microsoft Entra ID or Okta.
+///
+/// ### Example
+/// ```json
+/// {
+///   "id": "microsoft",
+///   "issuer": "https://login.microsoftonline.com/<tenant-id>/v2.0",
+///   "clientId": "...",
+///   "clientSecret": "...",
+///   "scopes": ["openid", "email", "offline_access"]
+/// }
+/// ```
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OidcProviderConfig {
+    /// Unique provider ID, used in routes and as storage namespace.
+    pub id: String,
+
+    /// Issuer URL; the discovery document is fetched from
+    /// `<issuer>/.well-known/openid-configuration`.
+    pub issuer: String,
+
+    /// OAuth client ID registered with the provider.
+    pub client_id: String,
+
+    /// OAuth client secret registered with the provider.
+    pub client_secret: String,
+
+    /// Scopes requested when the caller does not specify any.
+    #[serde(default = "OidcProviderConfig::default_scopes")]
+    pub scopes: Vec<String>,
+}
+
+impl OidcProviderConfig {
+    fn default_scopes() -> Vec<String> {
+        vec!["openid".to_owned(), "email".to_owned()]
+    }
+}
@@ -0,0 +1,364 @@
+use std::sync::Arc;
+
+use jsonwebtoken::{Algorithm, Validation};
+use oauth2::{Scope, reqwest};
+use serde::{Deserialize, Serialize};
+
+use super::{OidcProviderConfig, OidcProviderMetadata};
+use crate::keys::{JwksKeySource, KeySource, verify_jwt};
+use crate::providers::{
+    Provider, ProviderCapabilities, ProviderEndpoints, ProviderIdentity,
+};
+
+/// Standard claims of an OIDC ID token.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OidcIdTokenClaims {
+    /// Issuer identifier.
+    pub iss: String,
+    /// Subject identifier, unique per provider.
+    pub sub: String,
+    /// Expiration time (UNIX timestamp).
+    pub exp: i64,
+    /// Issued-at time (UNIX timestamp).
+    pub iat: i64,
+    /// The user's email address, if the `email` scope was granted.
+    #[serde(default)]
+    pub email: Option<String>,
+    /// Whether the provider verified the email address.
+    #[serde(default)]
+    pub email_verified: Option<bool>,
+    /// The user's display name, if the `profile` scope was granted.
+    #[serde(default)]
+    pub name: Option<String>,
+    /// Nonce of the authorization request the token was issued for.
+    #[serde(default)]
+    pub nonce: Option<String>,
+}
+
+impl From<OidcIdTokenClaims> for ProviderIdentity {
+    fn from(claims: OidcIdTokenClaims) -> Self {
+        Self {
+            subject: claims.sub,
+            email: claims.email,
+            email_verified: claims.email_verified,
+            name: claims.name,
+        }
+    }
+}
+
+/// An OIDC provider configured from its discovery document.
+///
+/// Identities are resolved from ID tokens verified against the discovered
+/// JWKS and the authorization request's nonce, or from the `UserInfo`
+/// endpoint if no ID token was issued.
+#[derive(Clone)]
+pub struct OidcProvider {
+    id: String,
+    metadata: OidcProviderMetadata,
+    default_scopes: Vec<Scope>,
+    keys: Arc<dyn KeySource>,
+    validation: Validation,
+    http_client: reqwest::Client,
+}
+
+impl OidcProvider {
+    /// Fetches the provider's discovery document and creates the provider.
+    pub async fn discover(config: &OidcProviderConfig) -> crate::Result<Self> {
+        // Explicitly disable redirects to avoid SSRF attack surface.
+        let http_client = reqwest::ClientBuilder::new()
+            .redirect(reqwest::redirect::Policy::none())
+            .build()?;
+        let metadata =
+            OidcProviderMetadata::discover(&config.issuer, &http_client)
+                .await?;
+        Self::from_metadata(config, metadata)
+    }
+
+    /// Creates the provider from already fetched metadata.
+    pub fn from_metadata(
+        config: &OidcProviderConfig,
+        metadata: OidcProviderMetadata,
+    ) -> crate::Result<Self> {
+        // Explicitly disable redirects to avoid SSRF attack surface.
+        let http_client = reqwest::ClientBuilder::new()
+            .redirect(reqwest::redirect::Policy::none())
+            .build()?;
+
+        // Only asymmetric algorithms can be verified with the published JWKS.
+        let algorithms = metadata
+            .id_token_signing_alg_values_supported
+            .iter()
+            .filter_map(|alg| alg.parse::<Algorithm>().ok())
+            .filter(|alg| {
+                !matches!(
+                    alg,
+                    Algorithm::HS256 | Algorithm::HS384 | Algorithm::HS512
+                )
+            })
+            .collect::<Vec<_>>();
+        let Some(first) = algorithms.first() else {
+            return Err(crate::Error::ProviderDiscoveryFailed {
+                because:
+                    "provider supports no asymmetric ID token signing algorithm"
+                        .into(),
+            });
+        };
+
+        let mut validation = Validation::new(*first);
+        validation.algorithms.clone_from(&algorithms);
+        validation.set_audience(&[&config.client_id]);
+        validation.set_issuer(&[&metadata.issuer]);
+        validation.set_required_spec_claims(&["exp", "iat", "sub"]);
+
+        let keys = JwksKeySource::new(metadata.jwks_uri.clone())?;
+
+        Ok(Self {
+            id: config.id.clone(),
+            default_scopes: config
+                .scopes
+                .iter()
+                .cloned()
+                .map(Scope::new)
+                .collect(),
+            metadata,
+            keys: Arc::new(keys),
+            validation,
+            http_client,
+        })
+    }
+
+    /// Verifies ID tokens with the given key source instead of the
+    /// discovered JWKS.
+    #[must_use]
+    pub fn with_key_source(mut self, keys: Arc<dyn KeySource>) -> Self {
+        self.keys = keys;
+        self
+    }
+
+    /// Returns the discovered provider metadata.
+    pub fn metadata(&self) -> &OidcProviderMetadata {
+        &self.metadata
+    }
+
+    /// Verifies an ID token issued by this provider for the configured client.
+    pub async fn verify_id_token(
+        &self,
+        id_token: &str,
+    ) -> crate::Result<OidcIdTokenClaims> {
+        verify_jwt(id_token, self.keys.as_ref(), &self.validation).await
+    }
+
+    /// Fetches the user's claims from the `UserInfo` endpoint.
+    pub async fn userinfo(
+        &self,
+        access_token: &str,
+    ) -> crate::Result<serde_json::Value> {
+        let endpoint = self
+            .metadata
+            .userinfo_endpoint
+            .as_deref()
+            .ok_or(crate::Error::MissingConfigField("userinfo_endpoint"))?;
+
+        let response = self
+            .http_client
+            .get(endpoint)
+            .bearer_auth(access_token)
+            .send()
+            .await?
+            .error_for_status()?;
+
+        Ok(response.json().await?)
+    }
+}
+
+#[async_trait::async_trait]
+impl Provider for OidcProvider {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn endpoints(&self) -> ProviderEndpoints {
+        ProviderEndpoints {
+            authorization_url: self.metadata.authorization_endpoint.clone(),
+            token_url: self.metadata.token_endpoint.clone(),
+            revocation_url: self.metadata.revocation_endpoint.clone(),
+        }
+    }
+
+    fn default_scopes(&self) -> Vec<Scope> {
+        self.default_scopes.clone()
+    }
+
+    fn capabilities(&self) -> ProviderCapabilities {
+        ProviderCapabilities {
+            revocation: self.metadata.revocation_endpoint.is_some(),
+            ..ProviderCapabilities::default()
+        }
+    }
+
+    async fn resolve_identity(
+        &self,
+        _http_client: &reqwest::Client,
+        access_token: &str,
+        id_token: Option<&str>,
+        nonce: Option<&str>,
+    ) -> crate::Result<ProviderIdentity> {
+        if let Some(id_token) = id_token {
+            let claims = self.verify_id_token(id_token).await?;
+            if nonce.is_some() && claims.nonce.as_deref() != nonce {
+                return Err(crate::Error::NonceMismatch);
+            }
+            return Ok(claims.into());
+        }
+
+        let claims = self.userinfo(access_token).await?;
+        Ok(serde_json::from_value(serde_json::json!({
+            "subject": claims["sub"],
+            "email": claims["email"],
+            "emailVerified": claims["email_verified"],
+            "name": claims["name"],
+        }))?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::keys::test_keys;
+    use crate::testing::serve_json_once;
+    use serde_json::json;
+
+    const ISSUER: &str = "https://login.example.com/tenant/v2.0";
+    const CLIENT_ID: &str = "test-client";
+
+    fn config(issuer: &str) -> OidcProviderConfig {
+        serde_json::from_value(json!({
+            "id": "example",
+            "issuer": issuer,
+            "clientId": CLIENT_ID,
+            "clientSecret": "test-secret",
+        }))
+        .unwrap()
+    }
+
+    fn metadata(issuer: &str) -> serde_json::Value {
+        json!({
+            "issuer": issuer,
+            "authorization_endpoint": "https://login.example.com/authorize",
+            "token_endpoint": "https://login.example.com/token",
+            "userinfo_endpoint": format!("{issuer}/userinfo"),
+            "jwks_uri": "https://login.example.com/keys",
+        })
+    }
+
+    fn provider() -> OidcProvider {
+        let config = config(ISSUER);
+        let metadata = serde_json::from_value(metadata(ISSUER)).unwrap();
+
+        OidcProvider::from_metadata(&config, metadata)
+            .unwrap()
+            .with_key_source(Arc::new(test_keys::key_source()))
+    }
+
+    fn claims(aud: &str) -> serde_json::Value {
+        let now = test_keys::now();
+        json!({
+            "iss": ISSUER,
+            "aud": aud,
+            "sub": "user-123",
+            "email": "user@example.com",
+            "iat": now,
+            "exp": now + 3600,
+        })
+    }
+
+    #[tokio::test]
+    async fn test_verify_id_token_checks_discovered_issuer_and_audience() {
+        let provider = provider();
+
+        let token = test_keys::sign(&claims(CLIENT_ID));
+        let verified = provider.verify_id_token(&token).await.unwrap();
+        assert_eq!(verified.sub, "user-123");
+        assert_eq!(verified.email.as_deref(), Some("user@example.com"));
+
+        let token = test_keys::sign(&claims("other-client"));
+        assert!(provider.verify_id_token(&token).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_verify_id_token_with_mixed_algorithm_families() {
+        let config = config(ISSUER);
+        let mut metadata = metadata(ISSUER);
+        metadata["id_token_signing_alg_values_supported"] =
+            json!(["RS256", "ES256"]);
+        let metadata = serde_json::from_value(metadata).unwrap();
+        let provider = OidcProvider::from_metadata(&config, metadata)
+            .unwrap()
+            .with_key_source(Arc::new(test_keys::key_source()));
+
+        let token = test_keys::sign(&claims(CLIENT_ID));
+        let verified = provider.verify_id_token(&token).await.unwrap();
+        assert_eq!(verified.sub, "user-123");
+    }
+
+    #[tokio::test]
+    async fn test_resolve_identity_checks_nonce() {
+        let provider = provider();
+        let client = reqwest::Client::new();
+
+        let mut claims = claims(CLIENT_ID);
+        claims["nonce"] = json!("expected");
+        let token = test_keys::sign(&claims);
+        let identity = provider
+            .resolve_identity(&client, "", Some(&token), Some("expected"))
+            .await
+            .unwrap();
+        assert_eq!(identity.subject, "user-123");
+
+        for nonce in [Some("expected"), None] {
+            match nonce {
+                Some(nonce) => claims["nonce"] = json!(nonce),
+                None => claims["nonce"] = serde_json::Value::Null,
+            }
+            let token = test_keys::sign(&claims);
+            let result = provider
+                .resolve_identity(&client, "", Some(&token), Some("other"))
+                .await;
+            assert!(matches!(result, Err(crate::Error::NonceMismatch)));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_discover_rejects_issuer_mismatch() {
+        let (base_url, _) = serve_json_once(metadata(ISSUER)).await;
+
+        let result = OidcProvider::discover(&config(&base_url)).await;
+        assert!(matches!(
+            result,
+            Err(crate::Error::ProviderDiscoveryFailed { because })
+                if because.contains(ISSUER)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_resolve_identity_falls_back_to_userinfo() {
+        let (base_url, _) = serve_json_once(json!({
+            "sub": "user-456",
+            "email": "user@example.com",
+            "email_verified": true,
+        }))
+        .await;
+        let metadata = serde_json::from_value(metadata(&base_url)).unwrap();
+        let provider =
+            OidcProvider::from_metadata(&config(&base_url), metadata).unwrap();
+
+        let identity = provider
+            .resolve_identity(&reqwest::Client::new(), "token", None, None)
+            .await
+            .unwrap();
+        assert_eq!(identity.subject, "user-456");
+        assert_eq!(identity.email.as_deref(), Some("user@example.com"));
+        assert_eq!(identity.email_verified, Some(true));
+        assert!(identity.name.is_none());
+    }
+}
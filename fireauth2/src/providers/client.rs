@@ -0,0 +1,357 @@
+use std::sync::Arc;
+
+use oauth2::basic::{
+    BasicErrorResponse, BasicRevocationErrorResponse,
+    BasicTokenIntrospectionResponse, BasicTokenType,
+};
+use oauth2::{
+    AuthType, AuthUrl, AuthorizationCode, Client, ClientId, ClientSecret,
+    CsrfToken, EndpointMaybeSet, EndpointNotSet, EndpointSet, ExtraTokenFields,
+    PkceCodeChallenge, PkceCodeVerifier, RedirectUrl, RevocationUrl, Scope,
+    StandardRevocableToken, StandardTokenResponse, TokenResponse, TokenUrl,
+    reqwest,
+};
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+use super::{Provider, ProviderIdentity};
+use crate::client::{Nonce, RequestAccessTokenResponse};
+use crate::models::GoogleUser;
+use crate::repositories::GoogleUserRepository;
+
+/// Extra token response fields of `OAuth2` providers.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProviderExtraTokenFields {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    id_token: Option<String>,
+}
+
+impl ExtraTokenFields for ProviderExtraTokenFields {}
+
+type ProviderOAuthTokenResponse =
+    StandardTokenResponse<ProviderExtraTokenFields, BasicTokenType>;
+
+type ProviderOAuthClient = Client<
+    BasicErrorResponse,
+    ProviderOAuthTokenResponse,
+    BasicTokenIntrospectionResponse,
+    StandardRevocableToken,
+    BasicRevocationErrorResponse,
+    EndpointSet,
+    EndpointNotSet,
+    EndpointNotSet,
+    EndpointMaybeSet,
+    EndpointSet,
+>;
+
+/// Tokens returned by [`ProviderClient::exchange_authorization_code`].
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderTokenResponse {
+    access_token: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    id_token: Option<String>,
+    scope: Vec<String>,
+    issued_at: i64,
+    expires_in: Option<u64>,
+    identity: ProviderIdentity,
+}
+
+impl ProviderTokenResponse {
+    /// Returns the access token.
+    pub fn access_token(&self) -> &str {
+        &self.access_token
+    }
+
+    /// Returns the verified ID token, if the provider issued one.
+    pub fn id_token(&self) -> Option<&str> {
+        self.id_token.as_deref()
+    }
+
+    /// Returns the granted scopes, if reported by the provider.
+    pub fn scope(&self) -> &[String] {
+        &self.scope
+    }
+
+    /// Returns the UNIX timestamp when the tokens were issued.
+    pub fn issued_at(&self) -> i64 {
+        self.issued_at
+    }
+
+    /// Returns the access token lifetime in seconds, if the token expires.
+    pub fn expires_in(&self) -> Option<u64> {
+        self.expires_in
+    }
+
+    /// Returns the user the tokens were issued for.
+    pub fn identity(&self) -> &ProviderIdentity {
+        &self.identity
+    }
+}
+
+/// Drives the authorization code flow of a [`Provider`].
+#[derive(Clone)]
+pub struct ProviderClient {
+    provider: Arc<dyn Provider>,
+    client: ProviderOAuthClient,
+    http_client: reqwest::Client,
+    repository: Option<GoogleUserRepository>,
+}
+
+impl ProviderClient {
+    /// Creates a client for the given provider and OAuth client
+    /// credentials.
+    pub fn new(
+        provider: impl Provider + 'static,
+        client_id: impl Into<String>,
+        client_secret: impl Into<String>,
+    ) -> crate::Result<Self> {
+        let endpoints = provider.endpoints();
+        let capabilities = provider.capabilities();
+
+        let revocation_url = endpoints
+            .revocation_url
+            .filter(|_| capabilities.revocation)
+            .map(RevocationUrl::new)
+            .transpose()?;
+        let auth_type = if capabilities.client_secret_post {
+            AuthType::RequestBody
+        } else {
+            AuthType::BasicAuth
+        };
+
+        let client = Client::new(ClientId::new(client_id.into()))
+            .set_client_secret(ClientSecret::new(client_secret.into()))
+            .set_auth_type(auth_type)
+            .set_auth_uri(AuthUrl::new(endpoints.authorization_url)?)
+            .set_token_uri(TokenUrl::new(endpoints.token_url)?)
+            .set_revocation_url_option(revocation_url);
+
+        // Explicitly disable redirects to avoid SSRF attack surface.
+        let http_client = reqwest::ClientBuilder::new()
+            .redirect(reqwest::redirect::Policy::none())
+            .build()?;
+
+        Ok(Self {
+            provider: Arc::new(provider),
+            client,
+            http_client,
+            repository: None,
+        })
+    }
+
+    /// Persists refresh tokens to the given repository.
+    #[must_use]
+    pub(crate) fn with_repository(
+        mut self,
+        repository: GoogleUserRepository,
+    ) -> Self {
+        self.repository = Some(repository);
+        self
+    }
+
+    /// Returns the provider ID.
+    pub fn id(&self) -> &str {
+        self.provider.id()
+    }
+
+    /// Returns the underlying provider.
+    pub fn provider(&self) -> &dyn Provider {
+        self.provider.as_ref()
+    }
+
+    /// Generates an authorization URL with a CSRF token and, if supported,
+    /// a PKCE challenge. OIDC requests (with the `openid` scope) carry a
+    /// random nonce as well.
+    ///
+    /// Uses the provider's default scopes if `scopes` is empty.
+    pub fn request_access_token(
+        &self,
+        redirect_uri: Url,
+        scopes: &[Scope],
+    ) -> RequestAccessTokenResponse {
+        let (pkce_challenge, pkce_verifier) =
+            PkceCodeChallenge::new_random_sha256();
+        let scopes = if scopes.is_empty() {
+            self.provider.default_scopes()
+        } else {
+            scopes.to_vec()
+        };
+
+        let client = self
+            .client
+            .clone()
+            .set_redirect_uri(RedirectUrl::from_url(redirect_uri));
+        let mut request = client
+            .authorize_url(CsrfToken::new_random)
+            .add_scopes(scopes.clone());
+        if self.provider.capabilities().pkce {
+            request = request.set_pkce_challenge(pkce_challenge);
+        }
+        for (name, value) in self.provider.extra_params() {
+            request = request.add_extra_param(name, value);
+        }
+        let nonce = scopes
+            .iter()
+            .any(|scope| scope.as_str() == "openid")
+            .then(Nonce::new_random);
+        if let Some(nonce) = &nonce {
+            request = request.add_extra_param("nonce", nonce.to_string());
+        }
+        let (auth_url, csrf_token) = request.url();
+
+        RequestAccessTokenResponse::new(pkce_verifier, csrf_token, auth_url)
+            .with_nonce(nonce)
+            .with_scopes(scopes)
+    }
+
+    /// Exchanges an authorization code for tokens and resolves the user
+    /// they were issued for.
+    ///
+    /// `nonce` must be the nonce returned by
+    /// [`ProviderClient::request_access_token`], if any. The refresh token,
+    /// if any, is persisted under the resolved subject; the exchange fails
+    /// if it cannot be stored.
+    pub async fn exchange_authorization_code(
+        &self,
+        redirect_uri: Url,
+        code: impl Into<String>,
+        pkce_verifier: impl Into<String>,
+        nonce: Option<&str>,
+    ) -> crate::Result<ProviderTokenResponse> {
+        let client = self
+            .client
+            .clone()
+            .set_redirect_uri(RedirectUrl::from_url(redirect_uri));
+        let mut request =
+            client.exchange_code(AuthorizationCode::new(code.into()));
+        if self.provider.capabilities().pkce {
+            request = request
+                .set_pkce_verifier(PkceCodeVerifier::new(pkce_verifier.into()));
+        }
+        let response =
+            request
+                .request_async(&self.http_client)
+                .await
+                .map_err(|err| crate::Error::TokenExchangeFailed {
+                    because: err.to_string(),
+                })?;
+
+        let access_token = response.access_token().secret().to_owned();
+        let id_token = response.extra_fields().id_token.clone();
+        let identity = self
+            .provider
+            .resolve_identity(
+                &self.http_client,
+                &access_token,
+                id_token.as_deref(),
+                nonce,
+            )
+            .await?;
+
+        // Some providers (e.g. GitHub) separate granted scopes by commas.
+        let scope = response
+            .scopes()
+            .into_iter()
+            .flatten()
+            .flat_map(|scope| scope.split(','))
+            .map(str::to_owned)
+            .collect::<Vec<_>>();
+
+        if let (Some(repository), Some(token)) =
+            (&self.repository, response.refresh_token())
+        {
+            let user = GoogleUser {
+                id: identity.subject.clone(),
+                email: identity.email.clone(),
+                refresh_token: Some(token.secret().to_owned()),
+                scope: scope.iter().cloned().map(Scope::new).collect(),
+                client_id: Some(self.client.client_id().to_string()),
+                authenticated_at: None,
+            };
+            repository.update(&user).await?;
+        }
+
+        Ok(ProviderTokenResponse {
+            access_token,
+            id_token,
+            scope,
+            issued_at: chrono::Utc::now().timestamp(),
+            expires_in: response.expires_in().map(|d| d.as_secs()),
+            identity,
+        })
+    }
+
+    /// Revokes an access or refresh token, if the provider supports
+    /// revocation.
+    pub async fn revoke_token(
+        &self,
+        token: StandardRevocableToken,
+    ) -> crate::Result<()> {
+        self.client
+            .revoke_token(token)
+            .map_err(|_| crate::Error::TokenRevocationFailed {
+                because: format!(
+                    "provider `{}` does not support token revocation",
+                    self.id()
+                ),
+            })?
+            .request_async(&self.http_client)
+            .await
+            .map_err(|err| crate::Error::TokenRevocationFailed {
+                because: err.to_string(),
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::GitHubProvider;
+
+    #[test]
+    fn test_request_access_token_adds_provider_params_and_default_scopes() {
+        let client = ProviderClient::new(
+            GitHubProvider::new().with_allow_signup(false),
+            "test-client",
+            "test-secret",
+        )
+        .unwrap();
+        let redirect_uri =
+            Url::parse("https://app.example.com/providers/github/callback")
+                .unwrap();
+
+        let response = client.request_access_token(redirect_uri, &[]);
+        let query = response
+            .url()
+            .query_pairs()
+            .into_owned()
+            .collect::<std::collections::HashMap<_, _>>();
+
+        assert_eq!(query["scope"], "read:user user:email");
+        assert_eq!(query["allow_signup"], "false");
+        assert_eq!(query["code_challenge_method"], "S256");
+        assert!(!query.contains_key("nonce"));
+        assert!(response.nonce().is_none());
+    }
+
+    #[test]
+    fn test_request_access_token_adds_nonce_to_oidc_requests() {
+        let client =
+            ProviderClient::new(GitHubProvider::new(), "test-client", "secret")
+                .unwrap();
+        let redirect_uri =
+            Url::parse("https://app.example.com/providers/github/callback")
+                .unwrap();
+
+        let response = client
+            .request_access_token(redirect_uri, &[Scope::new("openid".into())]);
+        let nonce = response.nonce().unwrap().to_string();
+        assert!(
+            response
+                .url()
+                .query_pairs()
+                .any(|(name, value)| name == "nonce" && value == nonce)
+        );
+    }
+}
@@ -0,0 +1,276 @@
+use oauth2::reqwest::StatusCode;
+use oauth2::reqwest::header::{ACCEPT, USER_AGENT};
+use oauth2::{Scope, reqwest};
+use serde::Deserialize;
+
+use super::{
+    Provider, ProviderCapabilities, ProviderEndpoints, ProviderIdentity,
+};
+
+#[derive(Deserialize)]
+struct GitHubUser {
+    id: u64,
+    login: String,
+    name: Option<String>,
+    email: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct GitHubEmail {
+    email: String,
+    primary: bool,
+    verified: bool,
+}
+
+/// GitHub (or GitHub Enterprise Server) as `OAuth2` provider.
+///
+/// GitHub issues no ID tokens; the user is resolved via the REST API's
+/// `/user` endpoint, with the numeric account ID as subject. The primary
+/// email address is looked up via `/user/emails` if the public profile
+/// has none and the `user:email` scope was granted.
+#[derive(Debug, Clone)]
+pub struct GitHubProvider {
+    web_url: String,
+    api_url: String,
+    scopes: Vec<String>,
+    allow_signup: Option<bool>,
+}
+
+impl Default for GitHubProvider {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl GitHubProvider {
+    /// Provider ID of GitHub.
+    pub const ID: &'static str = "github";
+
+    const WEB_URL: &'static str = "https://github.com";
+    const API_URL: &'static str = "https://api.github.com";
+    const API_MEDIA_TYPE: &'static str = "application/vnd.github+json";
+
+    /// Creates a provider for github.com.
+    pub fn new() -> Self {
+        Self {
+            web_url: Self::WEB_URL.to_owned(),
+            api_url: Self::API_URL.to_owned(),
+            scopes: vec!["read:user".to_owned(), "user:email".to_owned()],
+            allow_signup: None,
+        }
+    }
+
+    /// Creates a provider for the GitHub Enterprise Server at `base_url`.
+    pub fn enterprise(base_url: &str) -> Self {
+        let base_url = base_url.trim_end_matches('/');
+        Self {
+            web_url: base_url.to_owned(),
+            api_url: format!("{base_url}/api/v3"),
+            ..Self::new()
+        }
+    }
+
+    /// Sets the scopes requested when the caller does not specify any.
+    #[must_use]
+    pub fn with_scopes(
+        mut self,
+        scopes: impl IntoIterator<Item = impl Into<String>>,
+    ) -> Self {
+        self.scopes = scopes.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Whether unauthenticated users are offered to sign up for GitHub
+    /// during authorization.
+    #[must_use]
+    pub fn with_allow_signup(mut self, allow_signup: bool) -> Self {
+        self.allow_signup = Some(allow_signup);
+        self
+    }
+
+    async fn get<T: serde::de::DeserializeOwned>(
+        &self,
+        http_client: &reqwest::Client,
+        path: &str,
+        access_token: &str,
+    ) -> crate::Result<T> {
+        let response = http_client
+            .get(format!("{}{path}", self.api_url))
+            .bearer_auth(access_token)
+            .header(ACCEPT, Self::API_MEDIA_TYPE)
+            .header(USER_AGENT, env!("CARGO_PKG_NAME"))
+            .send()
+            .await?
+            .error_for_status()?;
+
+        Ok(response.json().await?)
+    }
+}
+
+#[async_trait::async_trait]
+impl Provider for GitHubProvider {
+    fn id(&self) -> &str {
+        Self::ID
+    }
+
+    fn endpoints(&self) -> ProviderEndpoints {
+        ProviderEndpoints {
+            authorization_url: format!(
+                "{}/login/oauth/authorize",
+                self.web_url
+            ),
+            token_url: format!("{}/login/oauth/access_token", self.web_url),
+            revocation_url: None,
+        }
+    }
+
+    fn default_scopes(&self) -> Vec<Scope> {
+        self.scopes.iter().cloned().map(Scope::new).collect()
+    }
+
+    fn extra_params(&self) -> Vec<(String, String)> {
+        self.allow_signup
+            .map(|allow| vec![("allow_signup".to_owned(), allow.to_string())])
+            .unwrap_or_default()
+    }
+
+    fn capabilities(&self) -> ProviderCapabilities {
+        // Token revocation requires a Basic-authenticated REST API call
+        // instead of an RFC 7009 endpoint.
+        ProviderCapabilities {
+            pkce: true,
+            revocation: false,
+            client_secret_post: true,
+        }
+    }
+
+    async fn resolve_identity(
+        &self,
+        http_client: &reqwest::Client,
+        access_token: &str,
+        _id_token: Option<&str>,
+        _nonce: Option<&str>,
+    ) -> crate::Result<ProviderIdentity> {
+        let user: GitHubUser =
+            self.get(http_client, "/user", access_token).await?;
+
+        let (email, email_verified) = match user.email {
+            Some(email) => (Some(email), None),
+            None => match self
+                .get::<Vec<GitHubEmail>>(
+                    http_client,
+                    "/user/emails",
+                    access_token,
+                )
+                .await
+            {
+                Ok(emails) => emails
+                    .into_iter()
+                    .find(|e| e.primary)
+                    .map_or((None, None), |e| {
+                        (Some(e.email), Some(e.verified))
+                    }),
+                // The `user:email` scope was not granted.
+                Err(crate::Error::Http(err))
+                    if matches!(
+                        err.status(),
+                        Some(StatusCode::FORBIDDEN | StatusCode::NOT_FOUND)
+                    ) =>
+                {
+                    (None, None)
+                }
+                Err(err) => return Err(err),
+            },
+        };
+
+        Ok(ProviderIdentity {
+            subject: user.id.to_string(),
+            email,
+            email_verified,
+            name: user.name.or(Some(user.login)),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{serve_json_once, serve_json_with_status};
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn test_resolve_identity_uses_account_id_as_subject() {
+        let (base_url, _) = serve_json_once(json!({
+            "id": 583_231,
+            "login": "octocat",
+            "name": null,
+            "email": "octocat@github.com"
+        }))
+        .await;
+        let provider = GitHubProvider::enterprise(&base_url);
+
+        let identity = provider
+            .resolve_identity(&reqwest::Client::new(), "test-token", None, None)
+            .await
+            .unwrap();
+
+        assert_eq!(identity.subject, "583231");
+        assert_eq!(identity.email.as_deref(), Some("octocat@github.com"));
+        assert_eq!(identity.name.as_deref(), Some("octocat"));
+    }
+
+    #[tokio::test]
+    async fn test_resolve_identity_without_public_email() {
+        let user = json!({
+            "id": 583_231,
+            "login": "octocat",
+            "name": null,
+            "email": null
+        });
+        let resolve = |provider: GitHubProvider| async move {
+            provider
+                .resolve_identity(
+                    &reqwest::Client::new(),
+                    "test-token",
+                    None,
+                    None,
+                )
+                .await
+        };
+
+        let (base_url, _) = serve_json_with_status(vec![
+            (200, user.clone()),
+            (
+                200,
+                json!([
+                    { "email": "other@example.com", "primary": false, "verified": true },
+                    { "email": "octocat@github.com", "primary": true, "verified": false },
+                ]),
+            ),
+        ])
+        .await;
+        let identity = resolve(GitHubProvider::enterprise(&base_url))
+            .await
+            .unwrap();
+        assert_eq!(identity.email.as_deref(), Some("octocat@github.com"));
+        assert_eq!(identity.email_verified, Some(false));
+
+        let (base_url, _) = serve_json_with_status(vec![
+            (200, user.clone()),
+            (404, json!({ "message": "Not Found" })),
+        ])
+        .await;
+        let identity = resolve(GitHubProvider::enterprise(&base_url))
+            .await
+            .unwrap();
+        assert_eq!(identity.email, None);
+
+        let (base_url, _) = serve_json_with_status(vec![
+            (200, user),
+            (500, json!({ "message": "Server Error" })),
+        ])
+        .await;
+        let result = resolve(GitHubProvider::enterprise(&base_url)).await;
+        assert!(matches!(result, Err(crate::Error::Http(_))));
+    }
+}
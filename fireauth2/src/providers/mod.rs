@@ -0,0 +1,106 @@
+mod client;
+mod github;
+
+pub use client::*;
+pub use github::*;
+
+use oauth2::{Scope, reqwest};
+use serde::{Deserialize, Serialize};
+
+/// Endpoints of an `OAuth2` provider.
+#[derive(Debug, Clone)]
+pub struct ProviderEndpoints {
+    /// Authorization endpoint the user is redirected to.
+    pub authorization_url: String,
+    /// Token endpoint authorization codes are exchanged at.
+    pub token_url: String,
+    /// Token revocation endpoint (RFC 7009), if supported.
+    pub revocation_url: Option<String>,
+}
+
+/// Protocol features supported by an `OAuth2` provider.
+#[derive(Debug, Clone, Copy)]
+pub struct ProviderCapabilities {
+    /// Whether the provider supports PKCE (RFC 7636).
+    pub pkce: bool,
+    /// Whether tokens can be revoked at
+    /// [`ProviderEndpoints::revocation_url`].
+    pub revocation: bool,
+    /// Whether the client secret is sent in the request body instead of
+    /// the `Authorization` header.
+    pub client_secret_post: bool,
+}
+
+impl Default for ProviderCapabilities {
+    fn default() -> Self {
+        Self {
+            pkce: true,
+            revocation: false,
+            client_secret_post: false,
+        }
+    }
+}
+
+/// The user an `OAuth2` grant was issued for.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderIdentity {
+    /// Stable user identifier, unique per provider.
+    pub subject: String,
+    /// The user's email address, if available.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub email: Option<String>,
+    /// Whether the provider verified the email address, if known.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub email_verified: Option<bool>,
+    /// The user's display name, if available.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+}
+
+/// An `OAuth2` provider other than Google.
+///
+/// Providers describe their endpoints and capabilities and resolve the
+/// identity behind an access grant; the flow itself is driven by
+/// [`ProviderClient`]. Providers that do not issue ID tokens, such as
+/// [`GitHubProvider`], resolve identities through their user APIs.
+#[async_trait::async_trait]
+pub trait Provider: Send + Sync {
+    /// Unique provider ID, used in routes and as storage namespace.
+    fn id(&self) -> &str;
+
+    /// Returns the provider's endpoints.
+    fn endpoints(&self) -> ProviderEndpoints;
+
+    /// Scopes requested when the caller does not specify any.
+    fn default_scopes(&self) -> Vec<Scope>;
+
+    /// Provider-specific parameters added to authorization URLs.
+    fn extra_params(&self) -> Vec<(String, String)> {
+        Vec::new()
+    }
+
+    /// Returns the protocol features the provider supports.
+    fn capabilities(&self) -> ProviderCapabilities {
+        ProviderCapabilities::default()
+    }
+
+    /// Name of the Firestore collection grants of this provider are stored
+    /// in. Defaults to `<id>Users`.
+    fn storage_namespace(&self) -> String {
+        format!("{}Users", self.id())
+    }
+
+    /// Resolves the user a token response was issued for.
+    ///
+    /// `id_token` is only set if the provider returned one. `nonce` is the
+    /// nonce sent with the authorization request, if any; providers issuing
+    /// ID tokens must reject tokens that do not carry it.
+    async fn resolve_identity(
+        &self,
+        http_client: &reqwest::Client,
+        access_token: &str,
+        id_token: Option<&str>,
+        nonce: Option<&str>,
+    ) -> crate::Result<ProviderIdentity>;
+}
@@ -1,3 +1,8 @@
+#[cfg(test)]
+use std::collections::HashMap;
+#[cfg(test)]
+use std::sync::{Arc, RwLock};
+
 use firestore::FirestoreDb;
 
 use crate::{Result, models::GoogleUser};
@@ -5,7 +10,16 @@ use crate::{Result, models::GoogleUser};
 #[derive(Clone)]
 pub struct GoogleUserRepository {
     collection_name: String,
-    db: FirestoreDb,
+    store: Store,
+}
+
+#[derive(Clone)]
+enum Store {
+    Firestore(FirestoreDb),
+    /// Users keyed by collection name and ID, shared by all repositories
+    /// derived via [`GoogleUserRepository::with_collection`].
+    #[cfg(test)]
+    InMemory(Arc<RwLock<HashMap<(String, String), GoogleUser>>>),
 }
 
 impl GoogleUserRepository {
@@ -15,7 +29,29 @@ impl GoogleUserRepository {
     ) -> GoogleUserRepository {
         GoogleUserRepository {
             collection_name: collection_name.as_ref().to_string(),
-            db,
+            store: Store::Firestore(db),
+        }
+    }
+
+    /// Returns a repository backed by an in-memory map instead of Firestore.
+    #[cfg(test)]
+    pub fn in_memory(collection_name: impl AsRef<str>) -> Self {
+        GoogleUserRepository {
+            collection_name: collection_name.as_ref().to_string(),
+            store: Store::InMemory(Arc::default()),
+        }
+    }
+
+    /// Returns the name of the collection users are stored in.
+    pub fn collection_name(&self) -> &str {
+        &self.collection_name
+    }
+
+    /// Returns a repository for another collection of the same database.
+    pub fn with_collection(&self, collection_name: impl AsRef<str>) -> Self {
+        GoogleUserRepository {
+            collection_name: collection_name.as_ref().to_string(),
+            store: self.store.clone(),
         }
     }
 
@@ -23,30 +59,46 @@ impl GoogleUserRepository {
         &self,
         id: ID,
     ) -> Result<Option<GoogleUser>> {
-        let user: Option<GoogleUser> = self
-            .db
-            .fluent()
-            .select()
-            .by_id_in(&self.collection_name)
-            .obj()
-            .one(id.as_ref())
-            .await
-            .map_err(crate::Error::Firestore)?;
-
-        Ok(user)
+        match &self.store {
+            Store::Firestore(db) => {
+                let user: Option<GoogleUser> = db
+                    .fluent()
+                    .select()
+                    .by_id_in(&self.collection_name)
+                    .obj()
+                    .one(id.as_ref())
+                    .await
+                    .map_err(crate::Error::Firestore)?;
+
+                Ok(user)
+            }
+            #[cfg(test)]
+            Store::InMemory(users) => {
+                let key = (self.collection_name.clone(), id.as_ref().into());
+                Ok(users.read().unwrap().get(&key).cloned())
+            }
+        }
     }
 
     pub async fn update(&self, user: &GoogleUser) -> Result<()> {
-        let _firestore_result: Result<GoogleUser> = self
-            .db
-            .fluent()
-            .update()
-            .in_col(&self.collection_name)
-            .document_id(&user.id)
-            .object(user)
-            .execute()
-            .await
-            .map_err(crate::Error::Firestore);
+        match &self.store {
+            Store::Firestore(db) => {
+                let _: GoogleUser = db
+                    .fluent()
+                    .update()
+                    .in_col(&self.collection_name)
+                    .document_id(&user.id)
+                    .object(user)
+                    .execute()
+                    .await
+                    .map_err(crate::Error::Firestore)?;
+            }
+            #[cfg(test)]
+            Store::InMemory(users) => {
+                let key = (self.collection_name.clone(), user.id.clone());
+                users.write().unwrap().insert(key, user.clone());
+            }
+        }
 
         Ok(())
     }
@@ -0,0 +1,97 @@
+//! Helpers shared by tests across modules.
+
+use std::net::Ipv4Addr;
+
+use oauth2::http::StatusCode;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::task::JoinHandle;
+
+/// Serves a single HTTP request on `127.0.0.1` with the given JSON body.
+///
+/// Returns the server's base URL and a handle resolving to the request body.
+pub(crate) async fn serve_json_once(
+    body: serde_json::Value,
+) -> (String, JoinHandle<String>) {
+    let (base_url, handle) = serve_json(vec![body]).await;
+    let handle =
+        tokio::spawn(
+            async move { handle.await.unwrap().pop().unwrap_or_default() },
+        );
+    (base_url, handle)
+}
+
+/// Serves one HTTP request per given JSON body, in order, on `127.0.0.1`.
+///
+/// Returns the server's base URL and a handle resolving to the request
+/// bodies.
+pub(crate) async fn serve_json(
+    bodies: Vec<serde_json::Value>,
+) -> (String, JoinHandle<Vec<String>>) {
+    serve_json_with_status(bodies.into_iter().map(|body| (200, body)).collect())
+        .await
+}
+
+/// Serves one HTTP request per given status code and JSON body, in order,
+/// on `127.0.0.1`.
+///
+/// Returns the server's base URL and a handle resolving to the request
+/// bodies.
+pub(crate) async fn serve_json_with_status(
+    responses: Vec<(u16, serde_json::Value)>,
+) -> (String, JoinHandle<Vec<String>>) {
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+
+    let handle = tokio::spawn(async move {
+        let mut requests = Vec::new();
+        for (status, body) in responses {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            requests.push(respond(&mut stream, status, &body).await);
+        }
+        requests
+    });
+
+    (format!("http://127.0.0.1:{port}"), handle)
+}
+
+/// Reads a request from `stream`, answers it with `status` and `body` and
+/// returns the request body.
+async fn respond(
+    stream: &mut TcpStream,
+    status: u16,
+    body: &serde_json::Value,
+) -> String {
+    let mut buf = Vec::new();
+    let mut chunk = [0; 1024];
+    let request_body = loop {
+        let read = stream.read(&mut chunk).await.unwrap();
+        buf.extend_from_slice(&chunk[..read]);
+        let request = String::from_utf8_lossy(&buf);
+        if let Some((head, body)) = request.split_once("\r\n\r\n") {
+            let length = head
+                .lines()
+                .find_map(|line| {
+                    line.to_ascii_lowercase()
+                        .strip_prefix("content-length:")
+                        .map(|value| value.trim().parse().unwrap())
+                })
+                .unwrap_or(0);
+            if body.len() >= length || read == 0 {
+                break body.to_owned();
+            }
+        }
+    };
+
+    let body = body.to_string();
+    let reason = StatusCode::from_u16(status)
+        .unwrap()
+        .canonical_reason()
+        .unwrap_or_default();
+    let response = format!(
+        "HTTP/1.1 {status} {reason}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    );
+    stream.write_all(response.as_bytes()).await.unwrap();
+    request_body
+}